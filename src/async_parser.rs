@@ -0,0 +1,212 @@
+use std::io::ErrorKind;
+
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+
+use crate::{
+    types::{HttpParseError, ParseErrorKind},
+    HttpMethod, HttpRequest, HttpResponse, ParseStatus, ParserConfig, RequestParser,
+    ResponseParser,
+};
+
+/// A Parser for HTTP content read from a `tokio::io::AsyncRead`.
+///
+/// This is the asynchronous counterpart of [`HttpParser`](crate::HttpParser), it feeds the
+/// same push parsers so both accept and reject exactly the same messages. Bytes read past the
+/// end of one message are kept for the next one.
+///
+/// Available with the `tokio` feature.
+///
+/// # Example:
+/// ```rust
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// use http_parse::AsyncHttpParser;
+///
+/// let mut reader: &[u8] = b"GET /a HTTP/1.1\r\nHost: a\r\n\r\nGET /b HTTP/1.1\r\nHost: a\r\n\r\n";
+/// let mut parser = AsyncHttpParser::from_reader(&mut reader);
+/// while let Some(request) = parser.next_request().await.unwrap() {
+///     let mut output = Vec::new();
+///     request.write_to(&mut output).await.unwrap();
+///     assert_eq!(output, request.into_bytes());
+/// }
+/// # });
+/// ```
+pub struct AsyncHttpParser<'a, R> {
+    reader: BufReader<&'a mut R>,
+    // kept between calls, a message cut short by a dropped future continues where it stopped
+    request_parser: RequestParser,
+    response_parser: ResponseParser,
+}
+
+impl<'a, R: AsyncRead + Unpin> AsyncHttpParser<'a, R> {
+    /// Create a HTTP Parser from a reader that implements `tokio::io::AsyncRead`.
+    pub fn from_reader(reader: &'a mut R) -> Self {
+        Self::with_config(reader, ParserConfig::default())
+    }
+
+    /// Create a HTTP Parser from a reader that applies the limits in `config`.
+    pub fn with_config(reader: &'a mut R, config: ParserConfig) -> Self {
+        Self {
+            reader: BufReader::new(reader),
+            request_parser: RequestParser::with_config(config),
+            response_parser: ResponseParser::with_config(config),
+        }
+    }
+
+    /// Set the method of the request the next responses answer.
+    ///
+    /// See [`ResponseParser::set_request_method`].
+    pub fn set_request_method(&mut self, method: HttpMethod) {
+        self.response_parser.set_request_method(method);
+    }
+
+    /// Parse a `HttpResponse`, head and body, by reading bytes in this reader/stream.
+    ///
+    /// A stream that ends before a response starts produces `ParseErrorKind::UnexpectedEof`.
+    ///
+    /// # Errors:
+    /// When reading from the Reader produces any error or the data provided is not formatted properly.
+    pub async fn response(&mut self) -> Result<HttpResponse, HttpParseError> {
+        self.next_response()
+            .await?
+            .ok_or_else(|| ParseErrorKind::UnexpectedEof.into())
+    }
+
+    /// Parse the head of a `HttpResponse`, its body is skipped completely.
+    ///
+    /// # Errors:
+    /// When reading from the Reader produces any error or the data provided is not formatted properly.
+    pub async fn response_head_only(&mut self) -> Result<HttpResponse, HttpParseError> {
+        let (response, _) = Self::drive(
+            &mut self.reader,
+            &mut self.response_parser,
+            ResponseParser::feed_head,
+            ResponseParser::finish_head,
+        )
+        .await?
+        .ok_or(ParseErrorKind::UnexpectedEof)?;
+        Ok(response)
+    }
+
+    /// Parse the next `HttpResponse` on this reader/stream, head and body.
+    ///
+    /// Returns `None` when the stream ends cleanly before another response starts.
+    ///
+    /// # Errors:
+    /// When reading from the Reader produces any error, the data provided is not formatted
+    /// properly or the stream ends in the middle of a response.
+    pub async fn next_response(&mut self) -> Result<Option<HttpResponse>, HttpParseError> {
+        Self::drive(
+            &mut self.reader,
+            &mut self.response_parser,
+            ResponseParser::feed,
+            ResponseParser::finish,
+        )
+        .await
+    }
+
+    /// Parse a `HttpRequest`, head and body, by reading bytes in this reader/stream.
+    ///
+    /// A stream that ends before a request starts produces `ParseErrorKind::UnexpectedEof`.
+    ///
+    /// # Errors:
+    /// When reading from the Reader produces any error or the data provided is not formatted properly.
+    pub async fn request(&mut self) -> Result<HttpRequest, HttpParseError> {
+        self.next_request()
+            .await?
+            .ok_or_else(|| ParseErrorKind::UnexpectedEof.into())
+    }
+
+    /// Parse the head of a `HttpRequest`, its body is skipped completely.
+    ///
+    /// # Errors:
+    /// When reading from the Reader produces any error or the data provided is not formatted properly.
+    pub async fn request_head_only(&mut self) -> Result<HttpRequest, HttpParseError> {
+        let (request, _) = Self::drive(
+            &mut self.reader,
+            &mut self.request_parser,
+            RequestParser::feed_head,
+            RequestParser::finish_head,
+        )
+        .await?
+        .ok_or(ParseErrorKind::UnexpectedEof)?;
+        Ok(request)
+    }
+
+    /// Parse the next `HttpRequest` on this reader/stream, head and body.
+    ///
+    /// Returns `None` when the stream ends cleanly before another request starts.
+    ///
+    /// # Errors:
+    /// When reading from the Reader produces any error, the data provided is not formatted
+    /// properly or the stream ends in the middle of a request.
+    pub async fn next_request(&mut self) -> Result<Option<HttpRequest>, HttpParseError> {
+        Self::drive(
+            &mut self.reader,
+            &mut self.request_parser,
+            RequestParser::feed,
+            RequestParser::finish,
+        )
+        .await
+    }
+
+    /// The bytes read from the stream that were not used by a message yet.
+    pub fn buffered(&self) -> &[u8] {
+        self.reader.buffer()
+    }
+
+    /// Feed the bytes available in the reader to a push parser until it produces a message.
+    ///
+    /// Returns `None` when the stream ends before a message starts.
+    async fn drive<P, T>(
+        reader: &mut BufReader<&'a mut R>,
+        parser: &mut P,
+        feed: fn(&mut P, &[u8]) -> Result<ParseStatus<T>, HttpParseError>,
+        finish: fn(&mut P) -> Result<Option<T>, HttpParseError>,
+    ) -> Result<Option<T>, HttpParseError> {
+        loop {
+            let available = match reader.fill_buf().await {
+                Ok(available) => available,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            };
+            if available.is_empty() {
+                break;
+            }
+            let length = available.len();
+            match feed(parser, available)? {
+                ParseStatus::Partial => reader.consume(length),
+                ParseStatus::Complete(message, used) => {
+                    reader.consume(used);
+                    return Ok(Some(message));
+                }
+            }
+        }
+        finish(parser)
+    }
+}
+
+impl HttpRequest {
+    /// Write this request to `writer`, as [`HttpRequest::into_bytes`] serializes it.
+    ///
+    /// Available with the `tokio` feature.
+    ///
+    /// # Errors:
+    /// When writing to the Writer produces any error.
+    pub async fn write_to<W: AsyncWrite + Unpin>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&self.into_bytes()).await?;
+        writer.flush().await
+    }
+}
+
+impl HttpResponse {
+    /// Write this response to `writer`, as [`HttpResponse::into_bytes`] serializes it.
+    ///
+    /// Available with the `tokio` feature.
+    ///
+    /// # Errors:
+    /// When writing to the Writer produces any error.
+    pub async fn write_to<W: AsyncWrite + Unpin>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&self.into_bytes()).await?;
+        writer.flush().await
+    }
+}
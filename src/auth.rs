@@ -0,0 +1,393 @@
+use std::fmt::Display;
+
+use crate::{
+    borrowed::{is_token, is_token_char, read_quoted},
+    typed_headers::{invalid, list, single},
+    types::{quote_if_needed, HttpParseError},
+    DigestCredentials, HttpRequestBuilder, TypedHeader, H_AUTHORIZATION, H_PROXY_AUTHENTICATE,
+    H_WWW_AUTHENTICATE,
+};
+
+/// The alphabet of base64, RFC 4648 section 4.
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// `Authorization`, the credentials a client authenticates with.
+///
+/// # Example:
+/// ```rust
+/// use http_parse::{Authorization, HttpRequestBuilder};
+///
+/// let request = HttpRequestBuilder::new().basic_auth("Aladdin", "open sesame").build();
+/// assert_eq!(
+///     request.header("Authorization").unwrap().value::<String>().unwrap(),
+///     "Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ=="
+/// );
+/// let credentials = request.typed_header::<Authorization>().unwrap().unwrap();
+/// assert_eq!(credentials, Authorization::basic("Aladdin", "open sesame"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Authorization {
+    /// A user name and password, RFC 7617.
+    Basic { username: String, password: String },
+    /// A token the client was given, RFC 6750.
+    Bearer(String),
+    /// The answer to a `Digest` challenge, RFC 7616.
+    Digest(DigestCredentials),
+}
+
+impl Authorization {
+    /// Authenticate with a user name and password, the user name cannot hold a `:`.
+    pub fn basic(username: &str, password: &str) -> Self {
+        Authorization::Basic {
+            username: username.to_string(),
+            password: password.to_string(),
+        }
+    }
+
+    /// Authenticate with a token, such as an OAuth access token.
+    pub fn bearer(token: &str) -> Self {
+        Authorization::Bearer(token.to_string())
+    }
+
+    /// The authentication scheme, as it is written in the header.
+    pub fn scheme(&self) -> &'static str {
+        match self {
+            Authorization::Basic { .. } => "Basic",
+            Authorization::Bearer(_) => "Bearer",
+            Authorization::Digest(_) => "Digest",
+        }
+    }
+}
+
+impl TypedHeader for Authorization {
+    fn name() -> &'static str {
+        H_AUTHORIZATION
+    }
+
+    /// Schemes are not case sensitive, credentials of other schemes are not valid.
+    fn decode(values: &[&str]) -> Result<Self, HttpParseError> {
+        let value = single::<Self>(values)?;
+        let parse = || {
+            let (scheme, credentials) = value.split_once([' ', '\t'])?;
+            let credentials = credentials.trim();
+            match scheme.to_ascii_lowercase().as_str() {
+                "basic" if is_token68(credentials) => {
+                    let decoded = String::from_utf8(base64_decode(credentials)?).ok()?;
+                    let (username, password) = decoded.split_once(':')?;
+                    Some(Self::basic(username, password))
+                }
+                "bearer" if is_token68(credentials) => Some(Self::bearer(credentials)),
+                "digest" => DigestCredentials::parse(credentials).map(Authorization::Digest),
+                _ => None,
+            }
+        };
+        parse().ok_or_else(|| invalid::<Self>(values))
+    }
+
+    fn encode(&self) -> Vec<String> {
+        match self {
+            Authorization::Basic { username, password } => {
+                let credentials = format!("{}:{}", username, password);
+                vec![format!("Basic {}", base64_encode(credentials.as_bytes()))]
+            }
+            Authorization::Bearer(token) => vec![format!("Bearer {}", token)],
+            Authorization::Digest(credentials) => vec![format!("Digest {}", credentials)],
+        }
+    }
+}
+
+/// A challenge a server sends to ask for credentials, a scheme along with either a token
+/// or parameters such as `realm`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Challenge {
+    pub(crate) scheme: String,
+    pub(crate) token68: Option<String>,
+    pub(crate) params: Vec<(String, String)>,
+}
+
+impl Challenge {
+    /// Create a challenge for a scheme, such as `Basic`.
+    pub fn new(scheme: &str) -> Self {
+        Self {
+            scheme: scheme.to_string(),
+            token68: None,
+            params: Vec::new(),
+        }
+    }
+
+    /// Add a parameter, such as `realm`.
+    pub fn param<T: Display>(mut self, name: &str, value: T) -> Self {
+        self.params
+            .push((name.to_ascii_lowercase(), value.to_string()));
+        self
+    }
+
+    /// The scheme, as it was sent.
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    /// The token sent in place of parameters, for schemes that use one.
+    pub fn token68(&self) -> Option<&str> {
+        self.token68.as_deref()
+    }
+
+    /// The parameters, in the order they were sent with names in lower case.
+    pub fn params(&self) -> &[(String, String)] {
+        &self.params
+    }
+
+    /// The value of a parameter, names are not case sensitive.
+    pub fn get_param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(param, _)| param.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The `realm` parameter, the protection space the credentials are asked for.
+    pub fn realm(&self) -> Option<&str> {
+        self.get_param("realm")
+    }
+}
+
+impl Display for Challenge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.scheme)?;
+        if let Some(token68) = &self.token68 {
+            return write!(f, " {}", token68);
+        }
+        for (index, (name, value)) in self.params.iter().enumerate() {
+            let separator = if index == 0 { " " } else { ", " };
+            // these are always quoted, for historical reasons
+            let value = if ["realm", "domain", "nonce", "opaque", "qop"].contains(&name.as_str()) {
+                quote(value)
+            } else {
+                quote_if_needed(value)
+            };
+            write!(f, "{}{}={}", separator, name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// `WWW-Authenticate`, the challenges a `401 Unauthorized` response asks credentials with.
+///
+/// # Example:
+/// ```rust
+/// use http_parse::{TypedHeader, WwwAuthenticate};
+///
+/// let header = WwwAuthenticate::decode(&[
+///     "Newauth realm=\"apps\", type=1, title=\"Login to \\\"apps\\\"\", Basic realm=\"simple\"",
+/// ])
+/// .unwrap();
+/// let challenges = header.challenges();
+/// assert_eq!(challenges[0].get_param("title"), Some("Login to \"apps\""));
+/// assert_eq!(challenges[1].scheme(), "Basic");
+/// assert_eq!(header.find("basic").unwrap().realm(), Some("simple"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct WwwAuthenticate(pub(crate) Vec<Challenge>);
+
+/// `Proxy-Authenticate`, the challenges a `407 Proxy Authentication Required` response asks
+/// credentials with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProxyAuthenticate(pub(crate) Vec<Challenge>);
+
+impl WwwAuthenticate {
+    /// Create the header from the challenges, in order of preference.
+    pub fn new(challenges: &[Challenge]) -> Self {
+        Self(challenges.to_vec())
+    }
+
+    /// The challenges, in the order they were sent.
+    pub fn challenges(&self) -> &[Challenge] {
+        &self.0
+    }
+
+    /// The first challenge for a scheme, schemes are not case sensitive.
+    pub fn find(&self, scheme: &str) -> Option<&Challenge> {
+        find_challenge(&self.0, scheme)
+    }
+}
+
+impl TypedHeader for WwwAuthenticate {
+    fn name() -> &'static str {
+        H_WWW_AUTHENTICATE
+    }
+
+    fn decode(values: &[&str]) -> Result<Self, HttpParseError> {
+        parse_challenges(values)
+            .map(Self)
+            .ok_or_else(|| invalid::<Self>(values))
+    }
+
+    /// Each challenge is sent as a header of its own.
+    fn encode(&self) -> Vec<String> {
+        self.0.iter().map(Challenge::to_string).collect()
+    }
+}
+
+impl ProxyAuthenticate {
+    /// Create the header from the challenges, in order of preference.
+    pub fn new(challenges: &[Challenge]) -> Self {
+        Self(challenges.to_vec())
+    }
+
+    /// The challenges, in the order they were sent.
+    pub fn challenges(&self) -> &[Challenge] {
+        &self.0
+    }
+
+    /// The first challenge for a scheme, schemes are not case sensitive.
+    pub fn find(&self, scheme: &str) -> Option<&Challenge> {
+        find_challenge(&self.0, scheme)
+    }
+}
+
+impl TypedHeader for ProxyAuthenticate {
+    fn name() -> &'static str {
+        H_PROXY_AUTHENTICATE
+    }
+
+    fn decode(values: &[&str]) -> Result<Self, HttpParseError> {
+        parse_challenges(values)
+            .map(Self)
+            .ok_or_else(|| invalid::<Self>(values))
+    }
+
+    /// Each challenge is sent as a header of its own.
+    fn encode(&self) -> Vec<String> {
+        self.0.iter().map(Challenge::to_string).collect()
+    }
+}
+
+fn find_challenge<'a>(challenges: &'a [Challenge], scheme: &str) -> Option<&'a Challenge> {
+    challenges
+        .iter()
+        .find(|challenge| challenge.scheme.eq_ignore_ascii_case(scheme))
+}
+
+/// Parse a list of challenges, RFC 9110 section 11.6.1.
+///
+/// Commas separate both challenges and their parameters, an element that starts with a
+/// parameter belongs to the challenge before it.
+fn parse_challenges(values: &[&str]) -> Option<Vec<Challenge>> {
+    let mut challenges: Vec<Challenge> = Vec::new();
+    for element in list(values) {
+        let scheme_length = element
+            .bytes()
+            .take_while(|byte| is_token_char(*byte))
+            .count();
+        if scheme_length == 0 {
+            return None;
+        }
+        let rest = &element[scheme_length..];
+        if rest.trim_start().starts_with('=') {
+            let challenge = challenges
+                .last_mut()
+                .filter(|challenge| challenge.token68.is_none())?;
+            challenge.params.push(parse_param(element)?);
+            continue;
+        }
+        if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+            return None;
+        }
+        let mut challenge = Challenge::new(&element[..scheme_length]);
+        let rest = rest.trim();
+        if is_token68(rest) {
+            challenge.token68 = Some(rest.to_string());
+        } else if !rest.is_empty() {
+            challenge.params.push(parse_param(rest)?);
+        }
+        challenges.push(challenge);
+    }
+    (!challenges.is_empty()).then_some(challenges)
+}
+
+/// Parse a `name=value` parameter, the value either a token or a quoted string.
+pub(crate) fn parse_param(text: &str) -> Option<(String, String)> {
+    let (name, value) = text.split_once('=')?;
+    let name = name.trim();
+    if !is_token(name.as_bytes()) {
+        return None;
+    }
+    let value = value.trim();
+    let value = match value.strip_prefix('"') {
+        Some(quoted) => {
+            let (unquoted, rest) = read_quoted(quoted.as_bytes())?;
+            if !rest.trim_ascii().is_empty() {
+                return None;
+            }
+            String::from_utf8_lossy(&unquoted).to_string()
+        }
+        None if is_token(value.as_bytes()) => value.to_string(),
+        None => return None,
+    };
+    Some((name.to_ascii_lowercase(), value))
+}
+
+/// Write a value as a quoted string, whether it needs to be or not.
+pub(crate) fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Whether `text` is a token68, the characters of base64 and its variants.
+fn is_token68(text: &str) -> bool {
+    let value = text.trim_end_matches('=');
+    !value.is_empty()
+        && value
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || b"-._~+/".contains(&byte))
+}
+
+/// Encode bytes in base64, with padding.
+pub(crate) fn base64_encode(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (index, byte)| {
+            group | (*byte as u32) << (16 - 8 * index)
+        });
+        for index in 0..4 {
+            if index <= chunk.len() {
+                text.push(BASE64[(group >> (18 - 6 * index) & 63) as usize] as char);
+            } else {
+                text.push('=');
+            }
+        }
+    }
+    text
+}
+
+/// Decode base64, padding is optional but has to be complete when present.
+pub(crate) fn base64_decode(text: &str) -> Option<Vec<u8>> {
+    let data = text.trim_end_matches('=');
+    let padding = text.len() - data.len();
+    if padding > 2 || data.len() % 4 == 1 || (padding > 0 && !text.len().is_multiple_of(4)) {
+        return None;
+    }
+    let mut bytes = Vec::with_capacity(data.len() * 3 / 4);
+    for chunk in data.as_bytes().chunks(4) {
+        let mut group = 0u32;
+        for (index, character) in chunk.iter().enumerate() {
+            let sextet = BASE64.iter().position(|byte| byte == character)? as u32;
+            group |= sextet << (18 - 6 * index);
+        }
+        for index in 0..chunk.len() - 1 {
+            bytes.push((group >> (16 - 8 * index)) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+impl HttpRequestBuilder {
+    /// Authenticate with a user name and password, in a `Basic` `Authorization` header.
+    pub fn basic_auth(self, username: &str, password: &str) -> Self {
+        self.typed_header(&Authorization::basic(username, password))
+    }
+
+    /// Authenticate with a token, in a `Bearer` `Authorization` header.
+    pub fn bearer_auth(self, token: &str) -> Self {
+        self.typed_header(&Authorization::bearer(token))
+    }
+}
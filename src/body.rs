@@ -0,0 +1,604 @@
+use std::io::{BufRead, ErrorKind, Read};
+
+use crate::{
+    borrowed::{is_token, next_parameter, parse_trailers},
+    types::{HttpParseError, ParseErrorKind},
+    HttpChunkExtension, HttpHeader, HttpHeaderRef, HttpMethod, HttpResponseRef, HttpVersion,
+    ParseMode, ParserConfig, H_CONTENT_LENGTH, H_TRANSFER_ENCODING,
+};
+
+/// How the body of a message is delimited on the wire.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Framing {
+    /// The message carries no body.
+    None,
+    /// The body is exactly this many bytes long.
+    Length(usize),
+    /// The body uses the chunked transfer coding.
+    Chunked,
+    /// The body ends when the connection is closed.
+    UntilClose,
+}
+
+impl Framing {
+    /// Determine the framing of a request body, following RFC 9112 section 6.3.
+    ///
+    /// # Errors:
+    /// When the framing headers are invalid or ambiguous.
+    pub(crate) fn for_request(
+        headers: &[HttpHeaderRef],
+        version: HttpVersion,
+        config: &ParserConfig,
+    ) -> Result<Self, HttpParseError> {
+        match Self::from_headers(headers, version, config)? {
+            // a request body can not be delimited by closing the connection
+            Framing::UntilClose => {
+                Err(ParseErrorKind::ChunkedNotFinal(transfer_codings(headers)).into())
+            }
+            framing => Ok(framing),
+        }
+    }
+
+    /// Determine the framing of a response body, following RFC 9112 section 6.3.
+    ///
+    /// `method` is the method of the request being answered, when it is known.
+    /// Responses to `HEAD`, successful responses to `CONNECT` and `1xx`, `204` and `304`
+    /// responses never have a body whatever their headers say. Any other response without
+    /// framing headers is delimited by closing the connection.
+    ///
+    /// # Errors:
+    /// When the framing headers are invalid or ambiguous.
+    pub(crate) fn for_response(
+        response: &HttpResponseRef,
+        method: Option<&HttpMethod>,
+        config: &ParserConfig,
+    ) -> Result<Self, HttpParseError> {
+        let status = response.status_code;
+        let tunnel = method == Some(&HttpMethod::Connect) && (200..300).contains(&status);
+        if method == Some(&HttpMethod::Head)
+            || tunnel
+            || (100..200).contains(&status)
+            || status == 204
+            || status == 304
+        {
+            return Ok(Framing::None);
+        }
+        match Self::from_headers(&response.headers, response.version, config)? {
+            Framing::None => Ok(Framing::UntilClose),
+            framing => Ok(framing),
+        }
+    }
+
+    fn from_headers(
+        headers: &[HttpHeaderRef],
+        version: HttpVersion,
+        config: &ParserConfig,
+    ) -> Result<Self, HttpParseError> {
+        let encoding = Self::transfer_encoding(headers, config)?;
+        let length = Self::content_length(headers)?;
+        match (encoding, length) {
+            (Some(_), Some(_)) => Err(ParseErrorKind::ConflictingFraming.into()),
+            (Some(_), None) if version == HttpVersion::Http10 => {
+                Err(ParseErrorKind::InvalidTransferEncoding(transfer_codings(headers)).into())
+            }
+            (Some(framing), None) => Ok(framing),
+            (None, Some(length)) => Ok(Framing::Length(length)),
+            (None, None) => Ok(Framing::None),
+        }
+    }
+
+    /// The framing given by the `Transfer-Encoding` headers, if any coding is present.
+    fn transfer_encoding(
+        headers: &[HttpHeaderRef],
+        config: &ParserConfig,
+    ) -> Result<Option<Self>, HttpParseError> {
+        let mut codings = Vec::new();
+        for value in header_values(headers, H_TRANSFER_ENCODING) {
+            let coding = value.split(|byte| *byte == b';').next().unwrap_or_default();
+            let coding = coding.trim_ascii();
+            if !is_token(coding) {
+                return Err(
+                    ParseErrorKind::InvalidTransferEncoding(transfer_codings(headers)).into(),
+                );
+            }
+            // `identity` was removed from the registry, lenient parsing ignores it
+            if coding.eq_ignore_ascii_case(b"identity") {
+                if config.mode == ParseMode::Strict {
+                    return Err(
+                        ParseErrorKind::InvalidTransferEncoding(transfer_codings(headers)).into(),
+                    );
+                }
+                continue;
+            }
+            codings.push(coding);
+        }
+        let chunked = |coding: &[u8]| coding.eq_ignore_ascii_case(b"chunked");
+        match codings.iter().filter(|coding| chunked(coding)).count() {
+            _ if codings.is_empty() => Ok(None),
+            0 => Ok(Some(Framing::UntilClose)),
+            1 if codings.last().is_some_and(|coding| chunked(coding)) => Ok(Some(Framing::Chunked)),
+            1 => Ok(Some(Framing::UntilClose)),
+            _ => Err(ParseErrorKind::InvalidTransferEncoding(transfer_codings(headers)).into()),
+        }
+    }
+
+    /// The length given by the `Content-Length` headers, if any.
+    ///
+    /// Repeated values are accepted as long as they are all the same.
+    fn content_length(headers: &[HttpHeaderRef]) -> Result<Option<usize>, HttpParseError> {
+        let mut length = None;
+        for value in header_values(headers, H_CONTENT_LENGTH) {
+            let value = value.trim_ascii();
+            let parsed = std::str::from_utf8(value)
+                .ok()
+                .filter(|value| !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()))
+                .and_then(|value| value.parse::<usize>().ok());
+            let Some(parsed) = parsed else {
+                return Err(ParseErrorKind::InvalidContentLength(
+                    String::from_utf8_lossy(value).to_string(),
+                )
+                .into());
+            };
+            match length {
+                Some(length) if length != parsed => {
+                    return Err(ParseErrorKind::ConflictingContentLength(format!(
+                        "{}, {}",
+                        length, parsed
+                    ))
+                    .into())
+                }
+                _ => length = Some(parsed),
+            }
+        }
+        Ok(length)
+    }
+}
+
+/// Every element of the comma separated lists in the headers named `name`.
+fn header_values<'a, 'buf>(
+    headers: &'a [HttpHeaderRef<'buf>],
+    name: &'a str,
+) -> impl Iterator<Item = &'buf [u8]> + 'a {
+    headers
+        .iter()
+        .filter(move |header| header.name.eq_ignore_ascii_case(name.as_bytes()))
+        .flat_map(|header| header.value.split(|byte| *byte == b','))
+        .filter(|element| !element.trim_ascii().is_empty())
+}
+
+/// All the `Transfer-Encoding` values of a message, used to describe errors.
+fn transfer_codings(headers: &[HttpHeaderRef]) -> String {
+    headers
+        .iter()
+        .filter(|header| {
+            header
+                .name
+                .eq_ignore_ascii_case(H_TRANSFER_ENCODING.as_bytes())
+        })
+        .map(|header| String::from_utf8_lossy(header.value))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Something produced while decoding a body.
+#[derive(Debug, PartialEq)]
+pub(crate) enum BodyEvent<'a> {
+    /// Decoded body bytes, borrowed from the input.
+    Data(&'a [u8]),
+    /// A new chunk of the given size begins, the last chunk has a size of zero.
+    ChunkStart(usize),
+    /// All of the input was consumed and more is needed.
+    NeedMore,
+    /// The body is complete.
+    Done,
+}
+
+#[derive(Debug)]
+enum BodyState {
+    UntilClose,
+    Length(usize),
+    ChunkSize,
+    ChunkData(usize),
+    ChunkDataEnd,
+    Trailers,
+    Done,
+}
+
+/// A state machine that removes the framing from a message body.
+///
+/// The decoder never owns the input, it is handed slices of bytes and reports how many of them
+/// were used, so it can be driven by a blocking reader or by bytes pushed by the caller alike.
+#[derive(Debug)]
+pub(crate) struct BodyDecoder {
+    state: BodyState,
+    line: Vec<u8>,
+    config: ParserConfig,
+    total: usize,
+    extensions: Vec<HttpChunkExtension>,
+    trailer_lines: usize,
+    trailer_section: Vec<u8>,
+    trailers: Vec<HttpHeader>,
+    /// The bytes and line feeds decoded so far, errors are located from them.
+    position: (usize, usize),
+    line_start: (usize, usize),
+    trailers_start: (usize, usize),
+}
+
+impl BodyDecoder {
+    /// Create a decoder for the given body framing.
+    ///
+    /// # Errors:
+    /// When the announced length is over the configured limit.
+    pub(crate) fn new(framing: Framing, config: &ParserConfig) -> Result<Self, HttpParseError> {
+        let state = match framing {
+            Framing::None | Framing::Length(0) => BodyState::Done,
+            Framing::Length(length) if length > config.max_body_size => {
+                return Err(ParseErrorKind::BodyTooLarge(config.max_body_size).into())
+            }
+            Framing::Length(length) => BodyState::Length(length),
+            Framing::Chunked => BodyState::ChunkSize,
+            Framing::UntilClose => BodyState::UntilClose,
+        };
+        Ok(Self {
+            state,
+            line: Vec::new(),
+            config: *config,
+            total: 0,
+            extensions: Vec::new(),
+            trailer_lines: 0,
+            trailer_section: Vec::new(),
+            trailers: Vec::new(),
+            position: (0, 0),
+            line_start: (0, 0),
+            trailers_start: (0, 0),
+        })
+    }
+
+    /// Whether the whole body has been decoded.
+    pub(crate) fn is_done(&self) -> bool {
+        matches!(self.state, BodyState::Done)
+    }
+
+    /// The extensions of the chunk decoded last.
+    pub(crate) fn chunk_extensions(&self) -> &[HttpChunkExtension] {
+        &self.extensions
+    }
+
+    /// The raw trailer section of a chunked body, without its final empty line.
+    pub(crate) fn trailer_section(&self) -> &[u8] {
+        &self.trailer_section
+    }
+
+    /// The trailer fields of a chunked body, available once the body is done.
+    pub(crate) fn trailers(&self) -> &[HttpHeader] {
+        &self.trailers
+    }
+
+    /// Signal the end of the stream, which completes a body delimited by closing the connection.
+    ///
+    /// Returns whether the body is complete.
+    pub(crate) fn finish(&mut self) -> bool {
+        if matches!(self.state, BodyState::UntilClose) {
+            self.state = BodyState::Done;
+        }
+        self.is_done()
+    }
+
+    /// Decode the next piece of the body from `input`.
+    ///
+    /// Returns the number of bytes consumed from `input` along with the event produced.
+    pub(crate) fn decode<'a>(
+        &mut self,
+        input: &'a [u8],
+    ) -> Result<(usize, BodyEvent<'a>), HttpParseError> {
+        match self.decode_event(input) {
+            Ok((used, event)) => {
+                self.position = self.location(&input[..used]);
+                Ok((used, event))
+            }
+            Err(e) => {
+                // errors found in a line of the framing point at its start
+                let (offset, lines) = match self.line.is_empty() {
+                    true => self.position,
+                    false => self.line_start,
+                };
+                Err(e.at(offset, Some(lines + 1)))
+            }
+        }
+    }
+
+    fn decode_event<'a>(
+        &mut self,
+        input: &'a [u8],
+    ) -> Result<(usize, BodyEvent<'a>), HttpParseError> {
+        let mut consumed = 0;
+        loop {
+            let remainder = &input[consumed..];
+            match self.state {
+                BodyState::Done => return Ok((consumed, BodyEvent::Done)),
+                _ if remainder.is_empty() => return Ok((consumed, BodyEvent::NeedMore)),
+                BodyState::UntilClose => {
+                    self.total = self.total.saturating_add(remainder.len());
+                    if self.total > self.config.max_body_size {
+                        return Err(ParseErrorKind::BodyTooLarge(self.config.max_body_size).into());
+                    }
+                    return Ok((input.len(), BodyEvent::Data(remainder)));
+                }
+                BodyState::Length(left) | BodyState::ChunkData(left) => {
+                    let size = left.min(remainder.len());
+                    self.state = match (&self.state, left - size) {
+                        (BodyState::Length(_), 0) => BodyState::Done,
+                        (BodyState::Length(_), left) => BodyState::Length(left),
+                        (_, 0) => BodyState::ChunkDataEnd,
+                        (_, left) => BodyState::ChunkData(left),
+                    };
+                    return Ok((consumed + size, BodyEvent::Data(&remainder[..size])));
+                }
+                BodyState::ChunkSize => {
+                    let line = self.take_line(input, consumed);
+                    if self.line.len() > self.config.max_header_size {
+                        return Err(
+                            ParseErrorKind::Body("Chunk size line too long".to_string()).into()
+                        );
+                    }
+                    let Some(used) = line else {
+                        return Ok((input.len(), BodyEvent::NeedMore));
+                    };
+                    consumed += used;
+                    self.check_line_end()?;
+                    let size = self.parse_chunk_line()?;
+                    self.line.clear();
+                    self.total = self.total.saturating_add(size);
+                    if self.total > self.config.max_body_size {
+                        return Err(ParseErrorKind::BodyTooLarge(self.config.max_body_size).into());
+                    }
+                    self.state = match size {
+                        0 => {
+                            self.trailers_start = self.location(&input[..consumed]);
+                            BodyState::Trailers
+                        }
+                        size => BodyState::ChunkData(size),
+                    };
+                    return Ok((consumed, BodyEvent::ChunkStart(size)));
+                }
+                BodyState::ChunkDataEnd => {
+                    let Some(used) = self.take_line(input, consumed) else {
+                        return Ok((input.len(), BodyEvent::NeedMore));
+                    };
+                    consumed += used;
+                    self.check_line_end()?;
+                    let trailing = match self.config.mode {
+                        ParseMode::Strict => self.line != b"\r\n",
+                        ParseMode::Lenient => !self.line.trim_ascii().is_empty(),
+                    };
+                    if trailing {
+                        return Err(ParseErrorKind::Body(
+                            "Missing line break after chunk data".to_string(),
+                        )
+                        .into());
+                    }
+                    self.line.clear();
+                    self.state = BodyState::ChunkSize;
+                }
+                BodyState::Trailers => {
+                    let line = self.take_line(input, consumed);
+                    if self.line.len() > self.config.max_header_size {
+                        return Err(
+                            ParseErrorKind::HeaderTooLarge(self.config.max_header_size).into()
+                        );
+                    }
+                    let Some(used) = line else {
+                        return Ok((input.len(), BodyEvent::NeedMore));
+                    };
+                    consumed += used;
+                    self.check_line_end()?;
+                    if self.line.trim_ascii().is_empty() {
+                        let strict = self.config.mode == ParseMode::Strict;
+                        let (offset, lines) = self.trailers_start;
+                        self.trailers = parse_trailers(&self.trailer_section, strict)
+                            .map_err(|e| e.shifted(offset, lines))?
+                            .iter()
+                            .map(HttpHeaderRef::to_owned)
+                            .collect();
+                        self.state = BodyState::Done;
+                    } else {
+                        self.trailer_lines += 1;
+                        if self.trailer_lines > self.config.max_headers {
+                            return Err(
+                                ParseErrorKind::TooManyHeaders(self.config.max_headers).into()
+                            );
+                        }
+                        self.trailer_section.extend_from_slice(&self.line);
+                    }
+                    self.line.clear();
+                }
+            }
+        }
+    }
+
+    /// Append bytes up to and including the next line feed to the line buffer,
+    /// `input[..consumed]` was decoded already.
+    ///
+    /// Returns how many bytes were used when the line is complete.
+    fn take_line(&mut self, input: &[u8], consumed: usize) -> Option<usize> {
+        if self.line.is_empty() {
+            self.line_start = self.location(&input[..consumed]);
+        }
+        let input = &input[consumed..];
+        match input.iter().position(|byte| *byte == b'\n') {
+            Some(index) => {
+                self.line.extend_from_slice(&input[..=index]);
+                Some(index + 1)
+            }
+            None => {
+                self.line.extend_from_slice(input);
+                None
+            }
+        }
+    }
+
+    /// The bytes and line feeds of the body once `decoded` is decoded as well.
+    fn location(&self, decoded: &[u8]) -> (usize, usize) {
+        let (offset, lines) = self.position;
+        let line_feeds = decoded.iter().filter(|byte| **byte == b'\n').count();
+        (offset + decoded.len(), lines + line_feeds)
+    }
+
+    /// Strict parsing requires every line of the chunked framing to end with `CRLF`.
+    fn check_line_end(&self) -> Result<(), HttpParseError> {
+        if self.config.mode == ParseMode::Strict && !self.line.ends_with(b"\r\n") {
+            Err(ParseErrorKind::BareLineFeed.into())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Parse a chunk size line, keeping its extensions.
+    fn parse_chunk_line(&mut self) -> Result<usize, HttpParseError> {
+        let line = match self.config.mode {
+            ParseMode::Strict => &self.line[..self.line.len() - 2],
+            ParseMode::Lenient => self.line.trim_ascii(),
+        };
+        let (digits, extensions) = match line.iter().position(|byte| *byte == b';') {
+            // whitespace is allowed ahead of the `;` of the first extension
+            Some(index) => (line[..index].trim_ascii_end(), &line[index..]),
+            None => (line, &line[line.len()..]),
+        };
+        let size = Self::parse_chunk_size(digits)?;
+        self.extensions =
+            parse_chunk_extensions(extensions, self.config.mode == ParseMode::Strict)?;
+        Ok(size)
+    }
+
+    fn parse_chunk_size(digits: &[u8]) -> Result<usize, HttpParseError> {
+        let invalid = || {
+            HttpParseError::from(ParseErrorKind::Body(format!(
+                "Invalid chunk size `{}`",
+                String::from_utf8_lossy(digits)
+            )))
+        };
+        if digits.is_empty() || !digits.iter().all(u8::is_ascii_hexdigit) {
+            return Err(invalid());
+        }
+        let digits = std::str::from_utf8(digits).map_err(|_| invalid())?;
+        usize::from_str_radix(digits, 16).map_err(|_| invalid())
+    }
+}
+
+/// Parse the extensions following a chunk size, `;name=value` pairs where the value is
+/// a token or a quoted string.
+///
+/// Lenient parsing keeps the extensions ahead of the first malformed one.
+fn parse_chunk_extensions(
+    mut text: &[u8],
+    strict: bool,
+) -> Result<Vec<HttpChunkExtension>, HttpParseError> {
+    let mut extensions = Vec::new();
+    loop {
+        text = text.trim_ascii_start();
+        if text.is_empty() {
+            return Ok(extensions);
+        }
+        match next_parameter(&mut text) {
+            Some((name, value)) => extensions.push(HttpChunkExtension { name, value }),
+            None if strict => {
+                return Err(ParseErrorKind::Body(format!(
+                    "Invalid chunk extension `{}`",
+                    String::from_utf8_lossy(text)
+                ))
+                .into())
+            }
+            None => return Ok(extensions),
+        }
+    }
+}
+
+/// A handle to the body of a message that is decoded while it is being read.
+///
+/// The body framing (`Content-Length` or chunked) is removed on the fly, so the body never has
+/// to be held in memory at once. Once the reader returns `0` the underlying stream is positioned
+/// at the start of the next message.
+/// A body that is dropped before it was read to the end leaves its remaining bytes in the stream.
+///
+/// # Example:
+/// ```rust
+/// use std::io::{Cursor, Read};
+/// use http_parse::HttpParser;
+///
+/// let text = "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n0\r\n\r\n";
+/// let mut reader = Cursor::new(text.as_bytes());
+/// let mut parser = HttpParser::from_reader(&mut reader);
+/// let (response, mut body) = parser.response_streaming().unwrap();
+///
+/// let mut data = String::new();
+/// body.read_to_string(&mut data).unwrap();
+/// assert_eq!(response.status_code(), 200);
+/// assert_eq!(data, "hello");
+/// ```
+#[derive(Debug)]
+pub struct BodyReader<'p, R> {
+    reader: &'p mut R,
+    decoder: BodyDecoder,
+}
+
+impl<'p, R: BufRead> BodyReader<'p, R> {
+    pub(crate) fn new(reader: &'p mut R, decoder: BodyDecoder) -> Self {
+        Self { reader, decoder }
+    }
+
+    /// Whether the whole body has been read.
+    pub fn is_done(&self) -> bool {
+        self.decoder.is_done()
+    }
+
+    /// The extensions sent along with the chunk being read, for chunked bodies.
+    pub fn chunk_extensions(&self) -> &[HttpChunkExtension] {
+        self.decoder.chunk_extensions()
+    }
+
+    /// The trailer fields sent after a chunked body, available once the whole body was read.
+    pub fn trailers(&self) -> &[HttpHeader] {
+        self.decoder.trailers()
+    }
+}
+
+impl<R: BufRead> Read for BodyReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            if self.decoder.is_done() {
+                return Ok(0);
+            }
+            let available = match self.reader.fill_buf() {
+                Ok(available) => available,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            if available.is_empty() {
+                if self.decoder.finish() {
+                    return Ok(0);
+                }
+                return Err(std::io::Error::new(
+                    ErrorKind::UnexpectedEof,
+                    "Stream ended before the end of the body",
+                ));
+            }
+            // never take more body bytes than the caller has room for
+            let limit = available.len().min(buf.len());
+            let (used, event) = self.decoder.decode(&available[..limit])?;
+            let read = match event {
+                BodyEvent::Data(data) => {
+                    buf[..data.len()].copy_from_slice(data);
+                    Some(data.len())
+                }
+                BodyEvent::ChunkStart(_) | BodyEvent::NeedMore | BodyEvent::Done => None,
+            };
+            self.reader.consume(used);
+            if let Some(read) = read {
+                return Ok(read);
+            }
+        }
+    }
+}
@@ -0,0 +1,774 @@
+use std::fmt::Display;
+
+use crate::{
+    body::{BodyDecoder, BodyEvent, Framing},
+    types::{line_number, HttpParseError, ParseErrorKind},
+    HttpChunkExtension, HttpHeader, HttpMethod, HttpRequest, HttpResponse, HttpVersion, ParseMode,
+    ParseStatus, ParserConfig, RequestTarget,
+};
+
+/// A HTTP Header borrowed from the buffer it was parsed from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HttpHeaderRef<'buf> {
+    pub(crate) name: &'buf [u8],
+    pub(crate) value: &'buf [u8],
+}
+
+impl<'buf> HttpHeaderRef<'buf> {
+    /// The name for a header
+    pub fn name(&self) -> &'buf [u8] {
+        self.name
+    }
+
+    /// The raw value for a header, including any obsolete line folding.
+    pub fn value(&self) -> &'buf [u8] {
+        self.value
+    }
+
+    /// Copy this header into an owned `HttpHeader`.
+    ///
+    /// Values that were folded over several lines (only accepted by `ParseMode::Lenient`)
+    /// have every fold replaced with a single space.
+    pub fn to_owned(&self) -> HttpHeader {
+        let value = if self.value.contains(&b'\n') {
+            self.value
+                .split(|byte| *byte == b'\n')
+                .map(|line| String::from_utf8_lossy(line.trim_ascii()))
+                .collect::<Vec<_>>()
+                .join(" ")
+        } else {
+            String::from_utf8_lossy(self.value).to_string()
+        };
+        HttpHeader::new(String::from_utf8_lossy(self.name), value)
+    }
+}
+
+impl Display for HttpHeaderRef<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {}",
+            String::from_utf8_lossy(self.name),
+            String::from_utf8_lossy(self.value)
+        )
+    }
+}
+
+/// A HTTP Request borrowed from the buffer it was parsed from.
+///
+/// Nothing is copied while parsing, the target, headers and body all point into the input.
+/// Use [`HttpRequestRef::to_owned`] to obtain a `HttpRequest` that outlives the buffer.
+///
+/// # Example:
+/// ```rust
+/// use http_parse::{HttpMethod, HttpRequestRef, ParseStatus};
+///
+/// let buffer = b"POST /upload HTTP/1.1\r\nContent-Length: 5\r\n\r\nhello";
+/// let ParseStatus::Complete(request, consumed) = HttpRequestRef::parse(buffer).unwrap() else {
+///     panic!("the request is complete");
+/// };
+/// assert_eq!(consumed, buffer.len());
+/// assert_eq!(request.method(), HttpMethod::Post);
+/// assert_eq!(request.target(), b"/upload");
+/// assert_eq!(request.data(), &[b"hello"]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpRequestRef<'buf> {
+    pub(crate) method: HttpMethod,
+    pub(crate) target: &'buf [u8],
+    pub(crate) version: HttpVersion,
+    pub(crate) headers: Vec<HttpHeaderRef<'buf>>,
+    pub(crate) body: BodyRef<'buf>,
+}
+
+impl<'buf> HttpRequestRef<'buf> {
+    /// Parse a complete request, head and body, from the start of `buffer`.
+    ///
+    /// Returns [`ParseStatus::Partial`] when `buffer` does not hold the whole request yet,
+    /// otherwise the request and the number of bytes it spans.
+    ///
+    /// # Errors:
+    /// When the data provided is not formatted properly.
+    pub fn parse(buffer: &'buf [u8]) -> Result<ParseStatus<Self>, HttpParseError> {
+        Self::parse_with_config(buffer, &ParserConfig::default())
+    }
+
+    /// Parse a complete message from the start of `buffer` applying the limits in `config`.
+    ///
+    /// # Errors:
+    /// When the data provided is not formatted properly or exceeds a limit.
+    pub fn parse_with_config(
+        buffer: &'buf [u8],
+        config: &ParserConfig,
+    ) -> Result<ParseStatus<Self>, HttpParseError> {
+        let Some(head_length) = find_head_end(buffer, config)? else {
+            return Ok(ParseStatus::Partial);
+        };
+        let mut request = Self::parse_head(&buffer[..head_length], config)?;
+        let framing = Framing::for_request(&request.headers, request.version, config)?;
+        let body = &buffer[head_length..];
+        let body_length = read_body(framing, config, body, &mut request.body)
+            .map_err(|e| e.shifted(head_length, line_number(buffer, head_length) - 1))?;
+        match body_length {
+            Some(body_length) => Ok(ParseStatus::Complete(request, head_length + body_length)),
+            None => Ok(ParseStatus::Partial),
+        }
+    }
+
+    /// Parse the request line and headers of a complete request head.
+    pub(crate) fn parse_head(
+        head: &'buf [u8],
+        config: &ParserConfig,
+    ) -> Result<Self, HttpParseError> {
+        let strict = config.mode == ParseMode::Strict;
+        let mut lines = head_lines(head);
+        let (_, start_line) = lines.next().unwrap_or_default();
+        let (method, target, version) = split_start_line(start_line, strict);
+
+        if strict && !is_token(method) {
+            return Err(HttpParseError::from(ParseErrorKind::Method(
+                String::from_utf8_lossy(method).to_string(),
+            ))
+            .within(head, offset_in(head, method)));
+        }
+        let method_text = method;
+        let method = parse_method(method).map_err(located(head, method_text))?;
+        if target.is_empty() || (strict && target.iter().any(|byte| !byte.is_ascii_graphic())) {
+            return Err(HttpParseError::from(ParseErrorKind::Url(
+                String::from_utf8_lossy(start_line).to_string(),
+            ))
+            .within(head, offset_in(head, start_line)));
+        }
+        if strict && version.iter().any(u8::is_ascii_whitespace) {
+            return Err(HttpParseError::from(ParseErrorKind::Version(
+                String::from_utf8_lossy(version).to_string(),
+            ))
+            .within(head, offset_in(head, version)));
+        }
+        if strict {
+            RequestTarget::parse(&String::from_utf8_lossy(target), &method)
+                .map_err(located(head, target))?;
+        }
+        let version = parse_version(version).map_err(located(head, version))?;
+        let headers = parse_headers(head, lines, strict)?;
+
+        Ok(Self {
+            method,
+            target,
+            version,
+            headers,
+            body: BodyRef::default(),
+        })
+    }
+
+    /// Retrieve the method for this request.
+    pub fn method(&self) -> HttpMethod {
+        self.method.clone()
+    }
+
+    /// Retrieve the raw request target.
+    pub fn target(&self) -> &'buf [u8] {
+        self.target
+    }
+
+    /// Get the version portion of this request.
+    pub fn version(&self) -> HttpVersion {
+        self.version
+    }
+
+    /// Get all the headers in this request.
+    pub fn headers(&self) -> &[HttpHeaderRef<'buf>] {
+        &self.headers
+    }
+
+    /// Retrieve the header with the given name, the name is not case sensitive.
+    pub fn header(&self, name: &str) -> Option<&HttpHeaderRef<'buf>> {
+        find_header(&self.headers, name)
+    }
+
+    /// Get the body data of this request.
+    ///
+    /// Bodies delimited by `Content-Length` are a single slice,
+    /// chunked bodies have one slice for every chunk.
+    pub fn data(&self) -> &[&'buf [u8]] {
+        &self.body.data
+    }
+
+    /// Get the extensions sent along with the chunk at `index` of a chunked body,
+    /// the last (empty) chunk included.
+    pub fn chunk_extensions(&self, index: usize) -> &[HttpChunkExtension] {
+        self.body.chunk_extensions(index)
+    }
+
+    /// Get the trailer fields sent after a chunked body.
+    pub fn trailers(&self) -> &[HttpHeaderRef<'buf>] {
+        &self.body.trailers
+    }
+
+    /// Copy this request into an owned `HttpRequest`.
+    pub fn to_owned(&self) -> HttpRequest {
+        let (body, chunks) = self.body.to_owned();
+        HttpRequest {
+            method: self.method.clone(),
+            url: String::from_utf8_lossy(self.target).to_string(),
+            version: self.version,
+            headers: self.headers.iter().map(HttpHeaderRef::to_owned).collect(),
+            body,
+            chunked: !chunks.is_empty(),
+            chunks,
+            extensions: self.body.extensions.clone(),
+            trailers: self
+                .body
+                .trailers
+                .iter()
+                .map(HttpHeaderRef::to_owned)
+                .collect(),
+        }
+    }
+}
+
+/// A HTTP Response borrowed from the buffer it was parsed from.
+///
+/// See [`HttpRequestRef`] for details.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpResponseRef<'buf> {
+    pub(crate) version: HttpVersion,
+    pub(crate) status_code: usize,
+    pub(crate) status_msg: &'buf [u8],
+    pub(crate) headers: Vec<HttpHeaderRef<'buf>>,
+    pub(crate) body: BodyRef<'buf>,
+}
+
+impl<'buf> HttpResponseRef<'buf> {
+    /// Parse a complete response, head and body, from the start of `buffer`.
+    ///
+    /// Returns [`ParseStatus::Partial`] when `buffer` does not hold the whole response yet,
+    /// otherwise the response and the number of bytes it spans.
+    ///
+    /// # Errors:
+    /// When the data provided is not formatted properly.
+    pub fn parse(buffer: &'buf [u8]) -> Result<ParseStatus<Self>, HttpParseError> {
+        Self::parse_with_config(buffer, &ParserConfig::default())
+    }
+
+    /// Parse a complete message from the start of `buffer` applying the limits in `config`.
+    ///
+    /// A response without framing headers is delimited by closing the connection, which a
+    /// buffer can not tell, such a response is always [`ParseStatus::Partial`].
+    /// Use a [`crate::ResponseParser`] to read it to the end of the stream instead.
+    ///
+    /// # Errors:
+    /// When the data provided is not formatted properly or exceeds a limit.
+    pub fn parse_with_config(
+        buffer: &'buf [u8],
+        config: &ParserConfig,
+    ) -> Result<ParseStatus<Self>, HttpParseError> {
+        Self::parse_answer(buffer, None, config)
+    }
+
+    /// Parse a complete response to a request made with `method` from the start of `buffer`.
+    ///
+    /// The method decides whether the response has a body, responses to `HEAD` never do.
+    ///
+    /// # Errors:
+    /// When the data provided is not formatted properly or exceeds a limit.
+    pub fn parse_with_request_method(
+        buffer: &'buf [u8],
+        method: &HttpMethod,
+        config: &ParserConfig,
+    ) -> Result<ParseStatus<Self>, HttpParseError> {
+        Self::parse_answer(buffer, Some(method), config)
+    }
+
+    fn parse_answer(
+        buffer: &'buf [u8],
+        method: Option<&HttpMethod>,
+        config: &ParserConfig,
+    ) -> Result<ParseStatus<Self>, HttpParseError> {
+        let Some(head_length) = find_head_end(buffer, config)? else {
+            return Ok(ParseStatus::Partial);
+        };
+        let mut response = Self::parse_head(&buffer[..head_length], config)?;
+        let framing = Framing::for_response(&response, method, config)?;
+        let body = &buffer[head_length..];
+        let body_length = read_body(framing, config, body, &mut response.body)
+            .map_err(|e| e.shifted(head_length, line_number(buffer, head_length) - 1))?;
+        match body_length {
+            Some(body_length) => Ok(ParseStatus::Complete(response, head_length + body_length)),
+            None => Ok(ParseStatus::Partial),
+        }
+    }
+
+    /// Parse the status line and headers of a complete response head.
+    pub(crate) fn parse_head(
+        head: &'buf [u8],
+        config: &ParserConfig,
+    ) -> Result<Self, HttpParseError> {
+        let strict = config.mode == ParseMode::Strict;
+        let mut lines = head_lines(head);
+        let (_, start_line) = lines.next().unwrap_or_default();
+        let (version, status_code, status_msg) = split_start_line(start_line, strict);
+
+        let version_text = version;
+        let version = parse_version(version).map_err(located(head, version_text))?;
+        let malformed = status_code.len() != 3
+            || !status_code.iter().all(u8::is_ascii_digit)
+            || start_line.len() == version_text.len() + 4;
+        if strict && malformed {
+            return Err(HttpParseError::from(ParseErrorKind::StatusCode(
+                String::from_utf8_lossy(status_code).to_string(),
+            ))
+            .within(head, offset_in(head, status_code)));
+        }
+        let status_code_text = status_code;
+        let status_code =
+            parse_status_code(status_code).map_err(located(head, status_code_text))?;
+        let headers = parse_headers(head, lines, strict)?;
+
+        Ok(Self {
+            version,
+            status_code,
+            status_msg,
+            headers,
+            body: BodyRef::default(),
+        })
+    }
+
+    /// Retrieve the version in this response.
+    pub fn version(&self) -> HttpVersion {
+        self.version
+    }
+
+    /// Retrieve the status code in this response
+    pub fn status_code(&self) -> usize {
+        self.status_code
+    }
+
+    /// Retrieve the raw status message of this response.
+    pub fn status_msg(&self) -> &'buf [u8] {
+        self.status_msg
+    }
+
+    /// Get all the headers in this response.
+    pub fn headers(&self) -> &[HttpHeaderRef<'buf>] {
+        &self.headers
+    }
+
+    /// Retrieve the header with the given name, the name is not case sensitive.
+    pub fn header(&self, name: &str) -> Option<&HttpHeaderRef<'buf>> {
+        find_header(&self.headers, name)
+    }
+
+    /// Get the body data of this response.
+    ///
+    /// Bodies delimited by `Content-Length` are a single slice,
+    /// chunked bodies have one slice for every chunk.
+    pub fn data(&self) -> &[&'buf [u8]] {
+        &self.body.data
+    }
+
+    /// Get the extensions sent along with the chunk at `index` of a chunked body,
+    /// the last (empty) chunk included.
+    pub fn chunk_extensions(&self, index: usize) -> &[HttpChunkExtension] {
+        self.body.chunk_extensions(index)
+    }
+
+    /// Get the trailer fields sent after a chunked body.
+    pub fn trailers(&self) -> &[HttpHeaderRef<'buf>] {
+        &self.body.trailers
+    }
+
+    /// Copy this response into an owned `HttpResponse`.
+    pub fn to_owned(&self) -> HttpResponse {
+        let (body, chunks) = self.body.to_owned();
+        HttpResponse {
+            version: self.version,
+            status_code: self.status_code,
+            status_msg: String::from_utf8_lossy(self.status_msg).to_string(),
+            headers: self.headers.iter().map(HttpHeaderRef::to_owned).collect(),
+            body,
+            chunked: !chunks.is_empty(),
+            chunks,
+            extensions: self.body.extensions.clone(),
+            trailers: self
+                .body
+                .trailers
+                .iter()
+                .map(HttpHeaderRef::to_owned)
+                .collect(),
+            interim: Vec::new(),
+        }
+    }
+}
+
+fn find_header<'a, 'buf>(
+    headers: &'a [HttpHeaderRef<'buf>],
+    name: &str,
+) -> Option<&'a HttpHeaderRef<'buf>> {
+    headers
+        .iter()
+        .find(|header| header.name.eq_ignore_ascii_case(name.as_bytes()))
+}
+
+/// The body of a borrowed message.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct BodyRef<'buf> {
+    data: Vec<&'buf [u8]>,
+    extensions: Vec<Vec<HttpChunkExtension>>,
+    trailers: Vec<HttpHeaderRef<'buf>>,
+    chunked: bool,
+}
+
+impl<'buf> BodyRef<'buf> {
+    fn chunk_extensions(&self, index: usize) -> &[HttpChunkExtension] {
+        self.extensions.get(index).map_or(&[], Vec::as_slice)
+    }
+
+    /// Copy the body data, returns the data along with the ranges of its chunks.
+    fn to_owned(&self) -> (Vec<u8>, Vec<(usize, usize)>) {
+        let mut body = Vec::with_capacity(self.data.iter().map(|piece| piece.len()).sum());
+        let mut chunks = Vec::new();
+        for piece in &self.data {
+            if self.chunked {
+                chunks.push((body.len(), body.len() + piece.len()));
+            }
+            body.extend_from_slice(piece);
+        }
+        // last chunk 0 data
+        if self.chunked {
+            chunks.push((0, 0));
+        }
+        (body, chunks)
+    }
+}
+
+/// Decode a whole body from `input`, collecting borrowed slices of its data.
+///
+/// Returns the number of bytes the body spans, or `None` when `input` holds only part of it.
+fn read_body<'buf>(
+    framing: Framing,
+    config: &ParserConfig,
+    input: &'buf [u8],
+    body: &mut BodyRef<'buf>,
+) -> Result<Option<usize>, HttpParseError> {
+    let mut decoder = BodyDecoder::new(framing, config)?;
+    let mut consumed = 0;
+    let mut trailers_start = 0;
+    body.chunked = framing == Framing::Chunked;
+    loop {
+        let (used, event) = decoder.decode(&input[consumed..])?;
+        consumed += used;
+        match event {
+            BodyEvent::Data(data) => body.data.push(data),
+            BodyEvent::ChunkStart(size) => {
+                body.extensions.push(decoder.chunk_extensions().to_vec());
+                if size == 0 {
+                    trailers_start = consumed;
+                }
+            }
+            BodyEvent::NeedMore => return Ok(None),
+            BodyEvent::Done => {
+                // the decoder validated the trailers, borrow them from the input
+                let length = decoder.trailer_section().len();
+                let section = &input[trailers_start..trailers_start + length];
+                body.trailers = parse_trailers(section, config.mode == ParseMode::Strict)?;
+                return Ok(Some(consumed));
+            }
+        }
+    }
+}
+
+/// Find the end of the message head at the start of `buffer`.
+///
+/// Returns the length of the head including the empty line that ends it.
+pub(crate) fn find_head_end(
+    buffer: &[u8],
+    config: &ParserConfig,
+) -> Result<Option<usize>, HttpParseError> {
+    let mut scanner = HeadScanner::default();
+    let mut line_start = 0;
+    while let Some(index) = buffer[line_start..].iter().position(|byte| *byte == b'\n') {
+        let end = line_start + index + 1;
+        let line = scanner
+            .line(&buffer[line_start..end], config)
+            .map_err(|e| e.within(buffer, line_start))?;
+        if line == HeadLine::End {
+            return Ok(Some(end));
+        }
+        line_start = end;
+    }
+    scanner
+        .partial(&buffer[line_start..], config)
+        .map_err(|e| e.within(buffer, line_start))?;
+    Ok(None)
+}
+
+/// The kind of a line found in a message head.
+#[derive(Debug, PartialEq)]
+pub(crate) enum HeadLine {
+    /// An empty line ahead of the start line, it is ignored.
+    Skip,
+    /// The start line or a header line.
+    Field,
+    /// The empty line that ends the head.
+    End,
+}
+
+/// Follows the lines of a message head as they are found, enforcing the configured limits.
+#[derive(Debug, Default)]
+pub(crate) struct HeadScanner {
+    lines: usize,
+}
+
+impl HeadScanner {
+    /// Classify the next complete line of the head, including its line terminator.
+    pub(crate) fn line(
+        &mut self,
+        line: &[u8],
+        config: &ParserConfig,
+    ) -> Result<HeadLine, HttpParseError> {
+        if config.mode == ParseMode::Strict && !line.ends_with(b"\r\n") {
+            return Err(ParseErrorKind::BareLineFeed.into());
+        }
+        if is_empty_line(line) {
+            return Ok(if self.lines == 0 {
+                HeadLine::Skip
+            } else {
+                HeadLine::End
+            });
+        }
+        self.partial(line, config)?;
+        self.lines += 1;
+        // the start line is not a header
+        if self.lines > config.max_headers.saturating_add(1) {
+            return Err(ParseErrorKind::TooManyHeaders(config.max_headers).into());
+        }
+        Ok(HeadLine::Field)
+    }
+
+    /// Check a line that may not be complete yet against the length limits.
+    pub(crate) fn partial(&self, line: &[u8], config: &ParserConfig) -> Result<(), HttpParseError> {
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        let length = line.strip_suffix(b"\r").unwrap_or(line).len();
+        if self.lines == 0 && length > config.max_start_line_length {
+            Err(ParseErrorKind::StartLineTooLong(config.max_start_line_length).into())
+        } else if self.lines > 0 && length > config.max_header_size {
+            Err(ParseErrorKind::HeaderTooLarge(config.max_header_size).into())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn is_empty_line(line: &[u8]) -> bool {
+    line == b"\n" || line == b"\r\n"
+}
+
+/// Split a head into its lines and their offsets, without the line terminators.
+fn head_lines(head: &[u8]) -> impl Iterator<Item = (usize, &[u8])> {
+    let mut offset = 0;
+    head.split(|byte| *byte == b'\n')
+        .map(move |line| {
+            let start = offset;
+            offset += line.len() + 1;
+            (start, line.strip_suffix(b"\r").unwrap_or(line))
+        })
+        .filter(|(_, line)| !line.is_empty())
+}
+
+/// Split a start line into its three parts.
+///
+/// Strict parsing expects single spaces between the parts, lenient parsing splits on any
+/// run of whitespace.
+fn split_start_line(line: &[u8], strict: bool) -> (&[u8], &[u8], &[u8]) {
+    if strict {
+        let mut parts = line.splitn(3, |byte| *byte == b' ');
+        let first = parts.next().unwrap_or_default();
+        let second = parts.next().unwrap_or_default();
+        (first, second, parts.next().unwrap_or_default())
+    } else {
+        let (first, rest) = split_word(line.trim_ascii());
+        let (second, rest) = split_word(rest);
+        (first, second, rest)
+    }
+}
+
+fn split_word(bytes: &[u8]) -> (&[u8], &[u8]) {
+    match bytes.iter().position(u8::is_ascii_whitespace) {
+        Some(index) => (&bytes[..index], bytes[index..].trim_ascii_start()),
+        None => (bytes, &[]),
+    }
+}
+
+/// Whether `byte` can be part of a token, as defined by RFC 9110.
+pub(crate) fn is_token_char(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&byte)
+}
+
+/// Whether `bytes` is a non empty token, as defined by RFC 9110.
+pub(crate) fn is_token(bytes: &[u8]) -> bool {
+    !bytes.is_empty() && bytes.iter().all(|byte| is_token_char(*byte))
+}
+
+/// Read one `;name[=value]` parameter from the start of `text` and advance past it,
+/// the value is a token or a quoted string.
+///
+/// Chunk extensions and media type parameters share this syntax.
+pub(crate) fn next_parameter(text: &mut &[u8]) -> Option<(String, Option<String>)> {
+    let rest = text.strip_prefix(b";")?.trim_ascii_start();
+    let name_length = rest.iter().take_while(|byte| is_token_char(**byte)).count();
+    if name_length == 0 {
+        return None;
+    }
+    let name = String::from_utf8_lossy(&rest[..name_length]).to_string();
+    let mut rest = rest[name_length..].trim_ascii_start();
+    let mut value = None;
+    if let Some(after) = rest.strip_prefix(b"=") {
+        let after = after.trim_ascii_start();
+        let (parsed, remainder) = match after.strip_prefix(b"\"") {
+            Some(quoted) => read_quoted(quoted)?,
+            None => {
+                let length = after
+                    .iter()
+                    .take_while(|byte| is_token_char(**byte))
+                    .count();
+                if length == 0 {
+                    return None;
+                }
+                (after[..length].to_vec(), &after[length..])
+            }
+        };
+        value = Some(String::from_utf8_lossy(&parsed).to_string());
+        rest = remainder;
+    }
+    *text = rest;
+    Some((name, value))
+}
+
+/// Read a quoted string up to its closing quote.
+///
+/// Returns the unescaped content and the bytes following the quote.
+pub(crate) fn read_quoted(text: &[u8]) -> Option<(Vec<u8>, &[u8])> {
+    let mut value = Vec::new();
+    let mut bytes = text.iter().enumerate();
+    while let Some((index, byte)) = bytes.next() {
+        match byte {
+            b'"' => return Some((value, &text[index + 1..])),
+            b'\\' => value.push(*bytes.next()?.1),
+            byte => value.push(*byte),
+        }
+    }
+    None
+}
+
+/// Parse the trailer fields of a chunked body, `section` holds the lines of the fields.
+pub(crate) fn parse_trailers(
+    section: &[u8],
+    strict: bool,
+) -> Result<Vec<HttpHeaderRef<'_>>, HttpParseError> {
+    parse_headers(section, head_lines(section), strict)
+}
+
+/// Parse the header lines of `head`.
+///
+/// Strict parsing rejects anything RFC 9112 does not allow. Lenient parsing ignores lines
+/// without a colon or name and joins obsolete line folding to the value of the previous header.
+/// Whitespace before the colon is rejected in both, a name read differently by another parser
+/// could change where the body ends.
+fn parse_headers<'buf, I>(
+    head: &'buf [u8],
+    lines: I,
+    strict: bool,
+) -> Result<Vec<HttpHeaderRef<'buf>>, HttpParseError>
+where
+    I: Iterator<Item = (usize, &'buf [u8])>,
+{
+    let mut headers: Vec<HttpHeaderRef> = Vec::new();
+    // where the value of the header an obsolete line fold continues starts
+    let mut value_start = None;
+    for (offset, line) in lines {
+        let lossy = || String::from_utf8_lossy(line).to_string();
+        let invalid = |kind: ParseErrorKind| HttpParseError::from(kind).within(head, offset);
+        if line.starts_with(b" ") || line.starts_with(b"\t") {
+            if strict {
+                return Err(invalid(ParseErrorKind::ObsoleteLineFolding(lossy())));
+            }
+            // a fold ahead of the first header has nothing to continue and is ignored
+            if let (Some(last), Some(start)) = (headers.last_mut(), value_start) {
+                let value_end = offset + line.trim_ascii_end().len();
+                last.value = head[start..value_end].trim_ascii();
+            }
+            continue;
+        }
+        let Some(colon) = line.iter().position(|byte| *byte == b':') else {
+            if strict {
+                return Err(invalid(ParseErrorKind::MissingColon(lossy())));
+            }
+            // folds after an ignored line must not join it to the previous header
+            value_start = None;
+            continue;
+        };
+        let name = &line[..colon];
+        let value = line[colon + 1..].trim_ascii();
+        if name.last().is_some_and(u8::is_ascii_whitespace) {
+            return Err(invalid(ParseErrorKind::WhitespaceBeforeColon(lossy())));
+        }
+        if strict {
+            if !is_token(name) {
+                return Err(invalid(ParseErrorKind::InvalidHeaderName(lossy())));
+            }
+            if value
+                .iter()
+                .any(|byte| byte.is_ascii_control() && *byte != b'\t')
+            {
+                return Err(invalid(ParseErrorKind::InvalidHeaderValue(lossy())));
+            }
+        }
+        if name.is_empty() {
+            value_start = None;
+            continue;
+        }
+        value_start = Some(offset + colon + 1);
+        headers.push(HttpHeaderRef { name, value });
+    }
+    Ok(headers)
+}
+
+/// Locate the errors found in `part` of `head`.
+fn located<'a>(head: &'a [u8], part: &'a [u8]) -> impl Fn(HttpParseError) -> HttpParseError + 'a {
+    move |e| e.within(head, offset_in(head, part))
+}
+
+/// The offset of `part` within `buffer`, `part` must be a slice of `buffer`.
+fn offset_in(buffer: &[u8], part: &[u8]) -> usize {
+    let offset = (part.as_ptr() as usize).saturating_sub(buffer.as_ptr() as usize);
+    offset.min(buffer.len())
+}
+
+fn parse_method(method: &[u8]) -> Result<HttpMethod, HttpParseError> {
+    let method = String::from_utf8_lossy(method.trim_ascii());
+    method.parse()
+}
+
+fn parse_version(version: &[u8]) -> Result<HttpVersion, HttpParseError> {
+    match version.trim_ascii() {
+        b"HTTP/1.0" => Ok(HttpVersion::Http10),
+        b"HTTP/1.1" => Ok(HttpVersion::Http11),
+        b"HTTP/2" => Ok(HttpVersion::Http2),
+        b"HTTP/3" => Ok(HttpVersion::Http3),
+        _ => Err(ParseErrorKind::Version(
+            String::from_utf8_lossy(version.trim_ascii()).to_string(),
+        )
+        .into()),
+    }
+}
+
+fn parse_status_code(status_code: &[u8]) -> Result<usize, HttpParseError> {
+    let code_string = String::from_utf8_lossy(status_code);
+    match code_string.trim().parse::<usize>() {
+        Ok(value) => Ok(value),
+        _ => {
+            Err(ParseErrorKind::StatusCode(String::from_utf8_lossy(status_code).to_string()).into())
+        }
+    }
+}
@@ -0,0 +1,444 @@
+use std::fmt::Display;
+
+use crate::{
+    typed_headers::{invalid, list, single},
+    types::HttpParseError,
+    HttpDate, HttpMethod, HttpRequest, StatusCode, TypedHeader, H_ETAG, H_IF_MATCH,
+    H_IF_MODIFIED_SINCE, H_IF_NONE_MATCH, H_IF_RANGE, H_IF_UNMODIFIED_SINCE,
+};
+
+/// `ETag`, an opaque validator that changes along with the representation.
+///
+/// A weak tag, written `W/"tag"`, only changes when the meaning of the representation does.
+///
+/// # Example:
+/// ```rust
+/// use http_parse::{ETag, TypedHeader};
+///
+/// let etag = ETag::decode(&["W/\"v1\""]).unwrap();
+/// assert!(etag.is_weak());
+/// assert!(etag.weak_eq(&ETag::strong("v1")));
+/// assert!(!etag.strong_eq(&ETag::strong("v1")));
+/// assert_eq!(etag.to_string(), "W/\"v1\"");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ETag {
+    pub(crate) tag: String,
+    pub(crate) weak: bool,
+}
+
+impl ETag {
+    /// A strong tag, for representations that are the same byte for byte.
+    pub fn strong(tag: &str) -> Self {
+        Self {
+            tag: tag.to_string(),
+            weak: false,
+        }
+    }
+
+    /// A weak tag, for representations that are the same in meaning.
+    pub fn weak(tag: &str) -> Self {
+        Self {
+            tag: tag.to_string(),
+            weak: true,
+        }
+    }
+
+    /// The tag, without its quotes.
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// Whether the tag is weak.
+    pub fn is_weak(&self) -> bool {
+        self.weak
+    }
+
+    /// Strong comparison, both tags are strong and the same.
+    pub fn strong_eq(&self, other: &ETag) -> bool {
+        !self.weak && !other.weak && self.tag == other.tag
+    }
+
+    /// Weak comparison, the tags are the same whether they are weak or not.
+    pub fn weak_eq(&self, other: &ETag) -> bool {
+        self.tag == other.tag
+    }
+
+    fn parse(text: &str) -> Option<Self> {
+        let (weak, quoted) = match text.strip_prefix("W/") {
+            Some(quoted) => (true, quoted),
+            None => (false, text),
+        };
+        let tag = quoted.strip_prefix('"')?.strip_suffix('"')?;
+        let valid = tag
+            .bytes()
+            .all(|byte| byte == 0x21 || (0x23..=0x7E).contains(&byte) || byte >= 0x80);
+        valid.then(|| Self {
+            tag: tag.to_string(),
+            weak,
+        })
+    }
+}
+
+impl Display for ETag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.weak {
+            write!(f, "W/")?;
+        }
+        write!(f, "\"{}\"", self.tag)
+    }
+}
+
+impl TypedHeader for ETag {
+    fn name() -> &'static str {
+        H_ETAG
+    }
+
+    fn decode(values: &[&str]) -> Result<Self, HttpParseError> {
+        let value = single::<Self>(values)?;
+        Self::parse(value).ok_or_else(|| invalid::<Self>(values))
+    }
+
+    fn encode(&self) -> Vec<String> {
+        vec![self.to_string()]
+    }
+}
+
+/// `If-Match`, perform the request only when the representation has one of the tags.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IfMatch {
+    /// `*`, any representation, as long as there is one.
+    Any,
+    Tags(Vec<ETag>),
+}
+
+impl IfMatch {
+    /// Whether the current representation matches, with strong comparison.
+    pub fn matches(&self, validators: &Validators) -> bool {
+        match self {
+            IfMatch::Any => validators.exists,
+            IfMatch::Tags(tags) => validators
+                .etag
+                .as_ref()
+                .is_some_and(|current| tags.iter().any(|tag| tag.strong_eq(current))),
+        }
+    }
+}
+
+impl TypedHeader for IfMatch {
+    fn name() -> &'static str {
+        H_IF_MATCH
+    }
+
+    fn decode(values: &[&str]) -> Result<Self, HttpParseError> {
+        match decode_tags(values) {
+            Some(Some(tags)) => Ok(IfMatch::Tags(tags)),
+            Some(None) => Ok(IfMatch::Any),
+            None => Err(invalid::<Self>(values)),
+        }
+    }
+
+    fn encode(&self) -> Vec<String> {
+        match self {
+            IfMatch::Any => vec!["*".to_string()],
+            IfMatch::Tags(tags) => encode_tags(tags),
+        }
+    }
+}
+
+/// `If-None-Match`, perform the request only when the representation has none of the tags.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IfNoneMatch {
+    /// `*`, no representation at all.
+    Any,
+    Tags(Vec<ETag>),
+}
+
+impl IfNoneMatch {
+    /// Whether the current representation matches one of the tags, with weak comparison,
+    /// which makes the condition false.
+    pub fn matches(&self, validators: &Validators) -> bool {
+        match self {
+            IfNoneMatch::Any => validators.exists,
+            IfNoneMatch::Tags(tags) => validators
+                .etag
+                .as_ref()
+                .is_some_and(|current| tags.iter().any(|tag| tag.weak_eq(current))),
+        }
+    }
+}
+
+impl TypedHeader for IfNoneMatch {
+    fn name() -> &'static str {
+        H_IF_NONE_MATCH
+    }
+
+    fn decode(values: &[&str]) -> Result<Self, HttpParseError> {
+        match decode_tags(values) {
+            Some(Some(tags)) => Ok(IfNoneMatch::Tags(tags)),
+            Some(None) => Ok(IfNoneMatch::Any),
+            None => Err(invalid::<Self>(values)),
+        }
+    }
+
+    fn encode(&self) -> Vec<String> {
+        match self {
+            IfNoneMatch::Any => vec!["*".to_string()],
+            IfNoneMatch::Tags(tags) => encode_tags(tags),
+        }
+    }
+}
+
+/// `If-Modified-Since`, perform a `GET` or `HEAD` only when the representation changed
+/// after the date.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IfModifiedSince(pub(crate) HttpDate);
+
+impl IfModifiedSince {
+    /// Create the header from a date, usually the `Last-Modified` of a cached response.
+    pub fn new(date: HttpDate) -> Self {
+        Self(date)
+    }
+
+    /// The date the representation has to be modified after.
+    pub fn date(&self) -> HttpDate {
+        self.0
+    }
+}
+
+impl TypedHeader for IfModifiedSince {
+    fn name() -> &'static str {
+        H_IF_MODIFIED_SINCE
+    }
+
+    fn decode(values: &[&str]) -> Result<Self, HttpParseError> {
+        let value = single::<Self>(values)?;
+        value.parse().map(Self)
+    }
+
+    fn encode(&self) -> Vec<String> {
+        vec![self.0.to_string()]
+    }
+}
+
+/// `If-Unmodified-Since`, perform the request only when the representation did not change
+/// after the date.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IfUnmodifiedSince(pub(crate) HttpDate);
+
+impl IfUnmodifiedSince {
+    /// Create the header from a date.
+    pub fn new(date: HttpDate) -> Self {
+        Self(date)
+    }
+
+    /// The date the representation must not be modified after.
+    pub fn date(&self) -> HttpDate {
+        self.0
+    }
+}
+
+impl TypedHeader for IfUnmodifiedSince {
+    fn name() -> &'static str {
+        H_IF_UNMODIFIED_SINCE
+    }
+
+    fn decode(values: &[&str]) -> Result<Self, HttpParseError> {
+        let value = single::<Self>(values)?;
+        value.parse().map(Self)
+    }
+
+    fn encode(&self) -> Vec<String> {
+        vec![self.0.to_string()]
+    }
+}
+
+/// `If-Range`, send the ranges asked for only when the representation is unchanged, the
+/// whole representation otherwise.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IfRange {
+    ETag(ETag),
+    Date(HttpDate),
+}
+
+impl TypedHeader for IfRange {
+    fn name() -> &'static str {
+        H_IF_RANGE
+    }
+
+    fn decode(values: &[&str]) -> Result<Self, HttpParseError> {
+        let value = single::<Self>(values)?;
+        if let Some(etag) = ETag::parse(value) {
+            return Ok(IfRange::ETag(etag));
+        }
+        value
+            .parse()
+            .map(IfRange::Date)
+            .map_err(|_| invalid::<Self>(values))
+    }
+
+    fn encode(&self) -> Vec<String> {
+        match self {
+            IfRange::ETag(etag) => vec![etag.to_string()],
+            IfRange::Date(date) => vec![date.to_string()],
+        }
+    }
+}
+
+/// The current validators of a resource, that preconditions are evaluated against.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Validators {
+    pub(crate) exists: bool,
+    pub(crate) etag: Option<ETag>,
+    pub(crate) last_modified: Option<HttpDate>,
+}
+
+impl Validators {
+    /// The validators of an existing representation, either may be unknown.
+    pub fn new(etag: Option<ETag>, last_modified: Option<HttpDate>) -> Self {
+        Self {
+            exists: true,
+            etag,
+            last_modified,
+        }
+    }
+
+    /// A resource with no current representation, such as one about to be created.
+    pub fn missing() -> Self {
+        Self {
+            exists: false,
+            etag: None,
+            last_modified: None,
+        }
+    }
+
+    /// The entity tag of the representation.
+    pub fn etag(&self) -> Option<&ETag> {
+        self.etag.as_ref()
+    }
+
+    /// The date the representation last changed.
+    pub fn last_modified(&self) -> Option<HttpDate> {
+        self.last_modified
+    }
+}
+
+/// The outcome of evaluating the preconditions of a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precondition {
+    /// Perform the request as if it had no precondition.
+    Proceed,
+    /// Answer with `304 Not Modified`, the client already has the representation.
+    NotModified,
+    /// Answer with `412 Precondition Failed`, without performing the request.
+    Failed,
+}
+
+impl Precondition {
+    /// The status to answer with, `None` to perform the request.
+    pub fn status_code(&self) -> Option<StatusCode> {
+        match self {
+            Precondition::Proceed => None,
+            Precondition::NotModified => Some(StatusCode::NOT_MODIFIED),
+            Precondition::Failed => Some(StatusCode::PRECONDITION_FAILED),
+        }
+    }
+}
+
+impl HttpRequest {
+    /// Evaluate the preconditions of this request against the current validators of the
+    /// resource, in the order of RFC 9110 section 13.2.2.
+    ///
+    /// Conditions that are not formatted properly are ignored, as are date conditions when
+    /// the resource has no modification date, except `If-Match`: a client that sends one
+    /// must not have its request performed on a representation it did not expect, so an
+    /// `If-Match` that cannot be parsed fails. `If-Range` is left to
+    /// [`HttpRequest::if_range_allows`].
+    ///
+    /// # Example:
+    /// ```rust
+    /// use http_parse::{ETag, HttpRequestBuilder, Precondition, Validators};
+    ///
+    /// let validators = Validators::new(Some(ETag::strong("v2")), None);
+    /// let request = HttpRequestBuilder::new()
+    ///     .header("If-None-Match", "\"v1\", W/\"v2\"")
+    ///     .build();
+    /// assert_eq!(request.evaluate_preconditions(&validators), Precondition::NotModified);
+    /// ```
+    pub fn evaluate_preconditions(&self, validators: &Validators) -> Precondition {
+        let is_read = matches!(self.method, HttpMethod::Get | HttpMethod::Head);
+
+        // 1 and 2, the representation must be the one the client expects
+        if self.header(H_IF_MATCH).is_some() {
+            match self.typed_header::<IfMatch>() {
+                Ok(Some(if_match)) if if_match.matches(validators) => {}
+                _ => return Precondition::Failed,
+            }
+        } else if let (Ok(Some(since)), Some(modified)) = (
+            self.typed_header::<IfUnmodifiedSince>(),
+            validators.last_modified,
+        ) {
+            if modified > since.date() {
+                return Precondition::Failed;
+            }
+        }
+
+        // 3 and 4, the client may already have the representation
+        if self.header(H_IF_NONE_MATCH).is_some() {
+            if let Ok(Some(if_none_match)) = self.typed_header::<IfNoneMatch>() {
+                if if_none_match.matches(validators) {
+                    return if is_read {
+                        Precondition::NotModified
+                    } else {
+                        Precondition::Failed
+                    };
+                }
+            }
+        } else if let (true, Ok(Some(since)), Some(modified)) = (
+            is_read,
+            self.typed_header::<IfModifiedSince>(),
+            validators.last_modified,
+        ) {
+            if modified <= since.date() {
+                return Precondition::NotModified;
+            }
+        }
+        Precondition::Proceed
+    }
+
+    /// Whether the `If-Range` of this request lets the ranges asked for be sent, rather
+    /// than the whole representation.
+    ///
+    /// Without `If-Range` ranges can always be sent. An entity tag has to match the
+    /// current one with strong comparison, a date has to be the modification date exactly.
+    /// An `If-Range` that is not formatted properly never matches.
+    pub fn if_range_allows(&self, validators: &Validators) -> bool {
+        match self.typed_header::<IfRange>() {
+            Ok(None) => true,
+            Ok(Some(IfRange::ETag(etag))) => validators
+                .etag
+                .as_ref()
+                .is_some_and(|current| etag.strong_eq(current)),
+            Ok(Some(IfRange::Date(date))) => validators.last_modified == Some(date),
+            Err(_) => false,
+        }
+    }
+}
+
+/// Decode a list of entity tags, `None` inside for `*`.
+fn decode_tags(values: &[&str]) -> Option<Option<Vec<ETag>>> {
+    let elements = list(values);
+    if elements == ["*"] {
+        return Some(None);
+    }
+    let tags = elements
+        .into_iter()
+        .map(ETag::parse)
+        .collect::<Option<Vec<ETag>>>()?;
+    (!tags.is_empty()).then_some(Some(tags))
+}
+
+fn encode_tags(tags: &[ETag]) -> Vec<String> {
+    let tags: Vec<String> = tags.iter().map(ETag::to_string).collect();
+    vec![tags.join(", ")]
+}
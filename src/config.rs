@@ -0,0 +1,124 @@
+/// Default longest request-line or status-line accepted, in bytes.
+pub const DEFAULT_MAX_START_LINE_LENGTH: usize = 8 * 1024;
+/// Default largest number of header fields accepted in a message.
+pub const DEFAULT_MAX_HEADERS: usize = 100;
+/// Default longest header field line accepted, in bytes.
+pub const DEFAULT_MAX_HEADER_SIZE: usize = 8 * 1024;
+/// Default largest number of interim (`1xx`) responses accepted ahead of a final response.
+pub const DEFAULT_MAX_INTERIM_RESPONSES: usize = 10;
+
+/// How closely a parser follows the message syntax of RFC 9112.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ParseMode {
+    /// Reject anything the RFC does not allow, with an error naming the problem:
+    /// header names that are not tokens, control characters in header values,
+    /// header lines without a colon, obsolete line folding,
+    /// lines ended by a bare `LF` and a head missing its final empty line.
+    Strict,
+    /// Tolerate the common mistakes found in the wild:
+    /// lines may end with a bare `LF`, start lines may be split by any run of whitespace,
+    /// header lines without a colon or name are ignored, obsolete line folding continues
+    /// the previous header value and the final empty line of a head may be missing at the end
+    /// of the stream.
+    ///
+    /// Whitespace between a header name and its colon is rejected in every mode,
+    /// as RFC 9112 requires, since it is a known way to smuggle a request past a proxy.
+    #[default]
+    Lenient,
+}
+
+/// Limits and syntax rules applied while parsing HTTP messages.
+///
+/// A parser rejects a message as soon as it exceeds one of these limits, before buffering
+/// more of it, and reports which limit was exceeded through a dedicated `ParseErrorKind`.
+///
+/// # Example:
+/// ```rust
+/// use http_parse::{ParseErrorKind, ParserConfig, RequestParser};
+///
+/// let config = ParserConfig::new().max_headers(1).max_body_size(1024);
+/// let mut parser = RequestParser::with_config(config);
+/// let result = parser.feed(b"GET / HTTP/1.1\r\nHost: a\r\nAccept: */*\r\n\r\n");
+/// let error = result.unwrap_err();
+/// assert_eq!(error.kind(), &ParseErrorKind::TooManyHeaders(1));
+/// assert_eq!(error.line(), Some(3));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParserConfig {
+    pub(crate) max_start_line_length: usize,
+    pub(crate) max_headers: usize,
+    pub(crate) max_header_size: usize,
+    pub(crate) max_body_size: usize,
+    pub(crate) max_interim_responses: usize,
+    pub(crate) mode: ParseMode,
+}
+
+impl Default for ParserConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ParserConfig {
+    /// Create a configuration with the default limits.
+    ///
+    /// Defaults to 8 KiB for the start line and each header line, 100 headers,
+    /// 10 interim responses, no limit on the body size and `ParseMode::Lenient`.
+    pub fn new() -> Self {
+        Self {
+            max_start_line_length: DEFAULT_MAX_START_LINE_LENGTH,
+            max_headers: DEFAULT_MAX_HEADERS,
+            max_header_size: DEFAULT_MAX_HEADER_SIZE,
+            max_body_size: usize::MAX,
+            max_interim_responses: DEFAULT_MAX_INTERIM_RESPONSES,
+            mode: ParseMode::Lenient,
+        }
+    }
+
+    /// Set how closely the parser follows the message syntax, see [`ParseMode`].
+    pub fn mode(mut self, mode: ParseMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set the longest request-line or status-line accepted, in bytes.
+    ///
+    /// Exceeding it produces `ParseErrorKind::StartLineTooLong`.
+    pub fn max_start_line_length(mut self, length: usize) -> Self {
+        self.max_start_line_length = length;
+        self
+    }
+
+    /// Set the largest number of header fields accepted, trailer fields are counted separately.
+    ///
+    /// Exceeding it produces `ParseErrorKind::TooManyHeaders`.
+    pub fn max_headers(mut self, count: usize) -> Self {
+        self.max_headers = count;
+        self
+    }
+
+    /// Set the longest header field line accepted, in bytes.
+    ///
+    /// Exceeding it produces `ParseErrorKind::HeaderTooLarge`.
+    pub fn max_header_size(mut self, size: usize) -> Self {
+        self.max_header_size = size;
+        self
+    }
+
+    /// Set the largest body accepted, in bytes.
+    /// Both the `Content-Length` announced and the total size of a chunked body are checked.
+    ///
+    /// Exceeding it produces `ParseErrorKind::BodyTooLarge`.
+    pub fn max_body_size(mut self, size: usize) -> Self {
+        self.max_body_size = size;
+        self
+    }
+
+    /// Set the largest number of interim (`1xx`) responses accepted ahead of a final response.
+    ///
+    /// Exceeding it produces `ParseErrorKind::TooManyInterimResponses`.
+    pub fn max_interim_responses(mut self, count: usize) -> Self {
+        self.max_interim_responses = count;
+        self
+    }
+}
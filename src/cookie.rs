@@ -0,0 +1,548 @@
+use std::fmt::Display;
+
+use crate::{
+    types::{HttpParseError, ParseErrorKind},
+    HeaderMap, HttpDate, HttpRequest, HttpRequestBuilder, HttpResponse, HttpResponseBuilder,
+    HttpUrl, H_COOKIE, H_SET_COOKIE,
+};
+
+/// The `SameSite` attribute of a cookie, whether it is sent along with cross-site requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SameSite {
+    Strict,
+    Lax,
+    None,
+}
+
+impl Display for SameSite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SameSite::Strict => write!(f, "Strict"),
+            SameSite::Lax => write!(f, "Lax"),
+            SameSite::None => write!(f, "None"),
+        }
+    }
+}
+
+/// A HTTP cookie, as a `Set-Cookie` header sets it or a `Cookie` header sends it back.
+///
+/// Cookies sent back by a client only carry their name and value.
+///
+/// # Example:
+/// ```rust
+/// use http_parse::{Cookie, SameSite};
+///
+/// let cookie = Cookie::parse("id=a3fWa; Max-Age=3600; Path=/docs; Secure; SameSite=lax").unwrap();
+/// assert_eq!((cookie.name(), cookie.value()), ("id", "a3fWa"));
+/// assert_eq!(cookie.max_age(), Some(3600));
+/// assert_eq!(cookie.same_site(), Some(SameSite::Lax));
+///
+/// let cookie = Cookie::builder("lang", "en").path("/").http_only(true).build();
+/// assert_eq!(cookie.to_string(), "lang=en; Path=/; HttpOnly");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cookie {
+    pub(crate) name: String,
+    pub(crate) value: String,
+    pub(crate) expires: Option<HttpDate>,
+    pub(crate) max_age: Option<i64>,
+    pub(crate) domain: Option<String>,
+    pub(crate) path: Option<String>,
+    pub(crate) secure: bool,
+    pub(crate) http_only: bool,
+    pub(crate) same_site: Option<SameSite>,
+}
+
+impl Cookie {
+    /// Create a cookie without attributes.
+    pub fn new(name: &str, value: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            value: value.to_string(),
+            expires: None,
+            max_age: None,
+            domain: None,
+            path: None,
+            secure: false,
+            http_only: false,
+            same_site: None,
+        }
+    }
+
+    /// Get a builder to set the attributes of a cookie.
+    pub fn builder(name: &str, value: &str) -> CookieBuilder {
+        CookieBuilder::new(name, value)
+    }
+
+    /// Parse the value of a `Set-Cookie` header.
+    ///
+    /// Attributes are parsed as user agents do, unknown attributes and attributes with a
+    /// value that is not valid are left out.
+    ///
+    /// # Errors:
+    /// When the cookie has no name or no `=` between its name and value.
+    pub fn parse(set_cookie: &str) -> Result<Self, HttpParseError> {
+        let mut attributes = set_cookie.split(';');
+        let pair = attributes.next().unwrap_or_default();
+        let mut cookie = Self::parse_pair(pair).ok_or_else(|| invalid(H_SET_COOKIE, set_cookie))?;
+        for attribute in attributes {
+            let (name, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+            let value = value.trim();
+            match name.trim().to_ascii_lowercase().as_str() {
+                "expires" => {
+                    // cookies often write dates with dashes, `Sun, 06-Nov-1994 08:49:37 GMT`
+                    let date = value
+                        .parse()
+                        .or_else(|_| value.replace('-', " ").parse())
+                        .ok();
+                    cookie.expires = date.or(cookie.expires);
+                }
+                "max-age" => cookie.max_age = parse_max_age(value).or(cookie.max_age),
+                "domain" if !value.is_empty() => {
+                    let domain = value.strip_prefix('.').unwrap_or(value);
+                    cookie.domain = Some(domain.to_ascii_lowercase());
+                }
+                "path" if value.starts_with('/') => cookie.path = Some(value.to_string()),
+                "secure" => cookie.secure = true,
+                "httponly" => cookie.http_only = true,
+                "samesite" => {
+                    cookie.same_site = match value.to_ascii_lowercase().as_str() {
+                        "strict" => Some(SameSite::Strict),
+                        "lax" => Some(SameSite::Lax),
+                        "none" => Some(SameSite::None),
+                        _ => cookie.same_site,
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(cookie)
+    }
+
+    /// Parse the value of a `Cookie` header, `name=value` pairs separated by `; `.
+    ///
+    /// # Errors:
+    /// When a pair has no name or no `=` between its name and value.
+    pub fn parse_list(cookie: &str) -> Result<Vec<Self>, HttpParseError> {
+        cookie
+            .split(';')
+            .filter(|pair| !pair.trim().is_empty())
+            .map(|pair| Self::parse_pair(pair).ok_or_else(|| invalid(H_COOKIE, cookie)))
+            .collect()
+    }
+
+    fn parse_pair(pair: &str) -> Option<Self> {
+        let (name, value) = pair.split_once('=')?;
+        let name = name.trim();
+        (!name.is_empty()).then(|| Self::new(name, value.trim()))
+    }
+
+    /// The name of the cookie.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The value of the cookie, quotes around it are kept.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// The date the cookie expires, `Max-Age` takes precedence over it.
+    pub fn expires(&self) -> Option<HttpDate> {
+        self.expires
+    }
+
+    /// The number of seconds until the cookie expires, `0` or less expires it at once.
+    pub fn max_age(&self) -> Option<i64> {
+        self.max_age
+    }
+
+    /// The domain the cookie is sent to along with its subdomains, in lower case.
+    pub fn domain(&self) -> Option<&str> {
+        self.domain.as_deref()
+    }
+
+    /// The path the cookie is sent to along with the paths below it.
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+
+    /// Whether the cookie is only sent over secure connections.
+    pub fn secure(&self) -> bool {
+        self.secure
+    }
+
+    /// Whether the cookie is hidden from scripts.
+    pub fn http_only(&self) -> bool {
+        self.http_only
+    }
+
+    /// Whether the cookie is sent along with cross-site requests.
+    pub fn same_site(&self) -> Option<SameSite> {
+        self.same_site
+    }
+}
+
+impl Display for Cookie {
+    /// Write the cookie as the value of a `Set-Cookie` header.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}", self.name, self.value)?;
+        if let Some(expires) = self.expires {
+            write!(f, "; Expires={}", expires)?;
+        }
+        if let Some(max_age) = self.max_age {
+            write!(f, "; Max-Age={}", max_age)?;
+        }
+        if let Some(domain) = &self.domain {
+            write!(f, "; Domain={}", domain)?;
+        }
+        if let Some(path) = &self.path {
+            write!(f, "; Path={}", path)?;
+        }
+        if self.secure {
+            write!(f, "; Secure")?;
+        }
+        if self.http_only {
+            write!(f, "; HttpOnly")?;
+        }
+        if let Some(same_site) = self.same_site {
+            write!(f, "; SameSite={}", same_site)?;
+        }
+        Ok(())
+    }
+}
+
+/// Builder for a cookie and its attributes.
+pub struct CookieBuilder {
+    cookie: Cookie,
+}
+
+impl CookieBuilder {
+    /// Create a builder for a cookie without attributes.
+    pub fn new(name: &str, value: &str) -> Self {
+        Self {
+            cookie: Cookie::new(name, value),
+        }
+    }
+
+    /// Set the date the cookie expires.
+    pub fn expires(mut self, date: HttpDate) -> Self {
+        self.cookie.expires = Some(date);
+        self
+    }
+
+    /// Set the number of seconds until the cookie expires.
+    pub fn max_age(mut self, seconds: i64) -> Self {
+        self.cookie.max_age = Some(seconds);
+        self
+    }
+
+    /// Set the domain the cookie is sent to, along with its subdomains.
+    pub fn domain(mut self, domain: &str) -> Self {
+        self.cookie.domain = Some(domain.to_ascii_lowercase());
+        self
+    }
+
+    /// Set the path the cookie is sent to, along with the paths below it.
+    pub fn path(mut self, path: &str) -> Self {
+        self.cookie.path = Some(path.to_string());
+        self
+    }
+
+    /// Set whether the cookie is only sent over secure connections.
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.cookie.secure = secure;
+        self
+    }
+
+    /// Set whether the cookie is hidden from scripts.
+    pub fn http_only(mut self, http_only: bool) -> Self {
+        self.cookie.http_only = http_only;
+        self
+    }
+
+    /// Set whether the cookie is sent along with cross-site requests.
+    pub fn same_site(mut self, same_site: SameSite) -> Self {
+        self.cookie.same_site = Some(same_site);
+        self
+    }
+
+    /// Construct the cookie.
+    pub fn build(self) -> Cookie {
+        self.cookie
+    }
+}
+
+impl HttpRequest {
+    /// The cookies this request sends, from every `Cookie` header.
+    ///
+    /// # Errors:
+    /// When a `Cookie` header is not formatted properly.
+    pub fn cookies(&self) -> Result<Vec<Cookie>, HttpParseError> {
+        let mut cookies = Vec::new();
+        for header in self.header_all(H_COOKIE) {
+            cookies.extend(Cookie::parse_list(&header.value)?);
+        }
+        Ok(cookies)
+    }
+
+    /// The first cookie this request sends with the given name.
+    ///
+    /// Cookies that are not formatted properly are left out.
+    pub fn cookie(&self, name: &str) -> Option<Cookie> {
+        self.header_all(H_COOKIE)
+            .into_iter()
+            .filter_map(|header| Cookie::parse_list(&header.value).ok())
+            .flatten()
+            .find(|cookie| cookie.name == name)
+    }
+}
+
+impl HttpRequestBuilder {
+    /// Send a cookie along with this request, all cookies share a single `Cookie` header.
+    pub fn cookie(mut self, name: &str, value: &str) -> Self {
+        let headers = self.headers.get_or_insert_with(HeaderMap::new);
+        let pair = format!("{}={}", name, value);
+        match headers.get(H_COOKIE) {
+            Some(header) => {
+                let joined = format!("{}; {}", header.value, pair);
+                headers.insert(H_COOKIE, joined);
+            }
+            None => headers.insert(H_COOKIE, pair),
+        }
+        self
+    }
+}
+
+impl HttpResponse {
+    /// The cookies this response sets, one for each `Set-Cookie` header.
+    ///
+    /// `Set-Cookie` headers that are not formatted properly are left out, as user agents
+    /// ignore them.
+    pub fn cookies(&self) -> Vec<Cookie> {
+        self.header_all(H_SET_COOKIE)
+            .into_iter()
+            .filter_map(|header| Cookie::parse(&header.value).ok())
+            .collect()
+    }
+
+    /// Set a cookie, along with any cookie already set.
+    pub fn add_cookie(&mut self, cookie: &Cookie) {
+        self.append_header(H_SET_COOKIE, cookie);
+    }
+}
+
+impl HttpResponseBuilder {
+    /// Set a cookie, each cookie gets a `Set-Cookie` header of its own.
+    pub fn cookie(self, cookie: &Cookie) -> Self {
+        self.header(H_SET_COOKIE, cookie)
+    }
+}
+
+/// A cookie kept by a [`CookieJar`].
+#[derive(Debug, Clone)]
+struct StoredCookie {
+    /// The cookie, with its domain and path filled in.
+    cookie: Cookie,
+    /// Cookies without a `Domain` attribute are only sent to the host that set them.
+    host_only: bool,
+    /// `None` for cookies that last until the jar is dropped.
+    expires_at: Option<HttpDate>,
+}
+
+impl StoredCookie {
+    fn is_expired(&self, now: HttpDate) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+/// Cookies kept by a client across requests.
+///
+/// The jar keeps the cookies responses set and gives back those to send to an URL,
+/// following the domain, path, secure and expiry rules of RFC 6265.
+///
+/// # Example:
+/// ```rust
+/// use http_parse::{Cookie, CookieJar, HttpResponseBuilder, HttpUrl};
+///
+/// let mut jar = CookieJar::new();
+/// let url = HttpUrl::parse("http://www.example.com/docs/index.html").unwrap();
+/// let response = HttpResponseBuilder::new()
+///     .cookie(&Cookie::builder("theme", "dark").domain("example.com").build())
+///     .cookie(&Cookie::new("session", "1234"))
+///     .build();
+/// jar.store(&url, &response);
+///
+/// let url = HttpUrl::parse("http://api.example.com/docs/").unwrap();
+/// assert_eq!(jar.cookie_header(&url), Some("theme=dark".to_string()));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CookieJar {
+    cookies: Vec<StoredCookie>,
+}
+
+impl CookieJar {
+    /// Create an empty jar.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of cookies kept, expired ones included until they are replaced.
+    pub fn len(&self) -> usize {
+        self.cookies.len()
+    }
+
+    /// Whether the jar keeps no cookie.
+    pub fn is_empty(&self) -> bool {
+        self.cookies.is_empty()
+    }
+
+    /// Keep the cookies a response to a request for `url` sets.
+    pub fn store(&mut self, url: &HttpUrl, response: &HttpResponse) {
+        for cookie in response.cookies() {
+            self.insert(url, cookie);
+        }
+    }
+
+    /// Keep a cookie set by a response to a request for `url`.
+    ///
+    /// The cookie replaces one with the same name, domain and path. It is ignored when its
+    /// domain does not match the host of `url`, or when it is secure and `url` is not.
+    /// A cookie that has already expired removes the cookie it replaces.
+    ///
+    /// A domain of a single label, such as `com`, is too broad to share a cookie with: the
+    /// cookie is only sent back to the host that set it when that host is the domain itself,
+    /// and ignored otherwise, as RFC 6265 does for public suffixes.
+    pub fn insert(&mut self, url: &HttpUrl, mut cookie: Cookie) {
+        let now = HttpDate::now();
+        let host = url.host().to_ascii_lowercase();
+        let host_only = match &cookie.domain {
+            Some(domain) if !domain.contains('.') && *domain == host => true,
+            Some(domain) if !domain.contains('.') => return,
+            Some(domain) if domain_matches(&host, domain) => false,
+            Some(_) => return,
+            None => true,
+        };
+        if cookie.secure && url.scheme() != "https" {
+            return;
+        }
+        if host_only {
+            cookie.domain = Some(host);
+        }
+        if cookie.path.is_none() {
+            cookie.path = Some(default_path(url_path(url)));
+        }
+        let expires_at = match cookie.max_age {
+            Some(seconds) if seconds <= 0 => Some(HttpDate::from_unix_seconds(0)),
+            Some(seconds) => Some(HttpDate::from_unix_seconds(
+                now.unix_seconds().saturating_add(seconds as u64),
+            )),
+            None => cookie.expires,
+        };
+
+        self.cookies.retain(|stored| {
+            stored.cookie.name != cookie.name
+                || stored.cookie.domain != cookie.domain
+                || stored.cookie.path != cookie.path
+        });
+        let stored = StoredCookie {
+            cookie,
+            host_only,
+            expires_at,
+        };
+        if !stored.is_expired(now) {
+            self.cookies.push(stored);
+        }
+    }
+
+    /// The cookies to send with a request for `url`, those with longer paths first.
+    pub fn cookies_for(&self, url: &HttpUrl) -> Vec<&Cookie> {
+        let now = HttpDate::now();
+        let host = url.host().to_ascii_lowercase();
+        let path = url_path(url);
+        let mut cookies: Vec<&Cookie> = self
+            .cookies
+            .iter()
+            .filter(|stored| {
+                let domain = stored.cookie.domain.as_deref().unwrap_or_default();
+                let domain_matches = if stored.host_only {
+                    host == domain
+                } else {
+                    domain_matches(&host, domain)
+                };
+                domain_matches
+                    && path_matches(path, stored.cookie.path.as_deref().unwrap_or("/"))
+                    && (!stored.cookie.secure || url.scheme() == "https")
+                    && !stored.is_expired(now)
+            })
+            .map(|stored| &stored.cookie)
+            .collect();
+        // stable, cookies with paths of the same length keep the order they were set in
+        cookies
+            .sort_by_key(|cookie| std::cmp::Reverse(cookie.path.as_ref().map_or(0, String::len)));
+        cookies
+    }
+
+    /// The value of the `Cookie` header to send with a request for `url`, if any.
+    pub fn cookie_header(&self, url: &HttpUrl) -> Option<String> {
+        let pairs: Vec<String> = self
+            .cookies_for(url)
+            .into_iter()
+            .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+            .collect();
+        (!pairs.is_empty()).then(|| pairs.join("; "))
+    }
+}
+
+fn invalid(header: &str, value: &str) -> HttpParseError {
+    ParseErrorKind::Header(format!("Invalid {} `{}`", header, value)).into()
+}
+
+/// Parse a `Max-Age`, a number of seconds that may be negative.
+fn parse_max_age(value: &str) -> Option<i64> {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    // ages too large to fit are as good as never expiring
+    Some(value.parse().unwrap_or(if value.starts_with('-') {
+        i64::MIN
+    } else {
+        i64::MAX
+    }))
+}
+
+/// The path of an URL, without its query or fragment.
+fn url_path(url: &HttpUrl) -> &str {
+    let path = url.path();
+    let end = path.find(['?', '#']).unwrap_or(path.len());
+    &path[..end]
+}
+
+/// The path a cookie without `Path` attribute is sent to, the "directory" of the URL.
+fn default_path(path: &str) -> String {
+    match path.rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(end) => path[..end].to_string(),
+    }
+}
+
+/// Whether `host` is `domain` or one of its subdomains, addresses only match themselves.
+fn domain_matches(host: &str, domain: &str) -> bool {
+    if host == domain {
+        return true;
+    }
+    let is_address = host.starts_with('[') || host.parse::<std::net::IpAddr>().is_ok();
+    !is_address
+        && host
+            .strip_suffix(domain)
+            .is_some_and(|subdomain| subdomain.ends_with('.'))
+}
+
+/// Whether `path` is `cookie_path` or below it.
+fn path_matches(path: &str, cookie_path: &str) -> bool {
+    path == cookie_path
+        || path
+            .strip_prefix(cookie_path)
+            .is_some_and(|rest| cookie_path.ends_with('/') || rest.starts_with('/'))
+}
@@ -0,0 +1,277 @@
+use std::{
+    fmt::Display,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::types::{HttpParseError, ParseErrorKind};
+
+const DAY_NAMES: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+const LONG_DAY_NAMES: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+const MONTH_NAMES: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+const SECONDS_PER_DAY: u64 = 86_400;
+/// `Fri, 31 Dec 9999 23:59:59 GMT`, the last date with a four digit year.
+const MAX_SECONDS: u64 = 253_402_300_799;
+
+/// A date as HTTP headers such as `Date`, `Last-Modified` or `Expires` carry it.
+///
+/// Dates are parsed in any of the three formats HTTP allows, IMF-fixdate
+/// (`Sun, 06 Nov 1994 08:49:37 GMT`), the obsolete RFC 850 format
+/// (`Sunday, 06-Nov-94 08:49:37 GMT`) and the asctime format (`Sun Nov  6 08:49:37 1994`),
+/// and always written as IMF-fixdate. Dates have a precision of one second and range from
+/// 1970 to 9999.
+///
+/// # Example:
+/// ```rust
+/// use http_parse::HttpDate;
+/// use std::time::{Duration, SystemTime, UNIX_EPOCH};
+///
+/// let date: HttpDate = "Sunday, 06-Nov-94 08:49:37 GMT".parse().unwrap();
+/// assert_eq!(date.to_string(), "Sun, 06 Nov 1994 08:49:37 GMT");
+/// assert_eq!(date, "Sun Nov  6 08:49:37 1994".parse().unwrap());
+///
+/// let time = SystemTime::from(date);
+/// assert_eq!(time, UNIX_EPOCH + Duration::from_secs(784111777));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HttpDate {
+    pub(crate) seconds: u64,
+}
+
+impl HttpDate {
+    /// The current date, truncated to the second.
+    pub fn now() -> Self {
+        Self::from(SystemTime::now())
+    }
+
+    /// Create a date from the number of seconds since the UNIX epoch.
+    ///
+    /// Dates past the year 9999 are set to its last second.
+    pub fn from_unix_seconds(seconds: u64) -> Self {
+        Self {
+            seconds: seconds.min(MAX_SECONDS),
+        }
+    }
+
+    /// The number of seconds since the UNIX epoch.
+    pub fn unix_seconds(&self) -> u64 {
+        self.seconds
+    }
+
+    fn from_parts(year: u64, month: u64, day: u64, time: (u64, u64, u64)) -> Option<Self> {
+        let (hour, minute, second) = time;
+        let valid = (1970..=9999).contains(&year)
+            && (1..=12).contains(&month)
+            && (1..=days_in_month(year, month)).contains(&day)
+            && hour < 24
+            && minute < 60
+            // leap seconds are allowed, they land on the next second
+            && second <= 60;
+        valid.then(|| {
+            let days = days_from_civil(year, month, day);
+            Self::from_unix_seconds(days * SECONDS_PER_DAY + hour * 3600 + minute * 60 + second)
+        })
+    }
+
+    /// `Sun, 06 Nov 1994 08:49:37 GMT`
+    fn parse_imf_fixdate(text: &str) -> Option<Self> {
+        let (day_name, rest) = text.split_once(", ")?;
+        let parts: Vec<&str> = rest.split(' ').collect();
+        let [day, month, year, time, "GMT"] = parts[..] else {
+            return None;
+        };
+        if !DAY_NAMES.contains(&day_name) {
+            return None;
+        }
+        Self::from_parts(
+            number(year, 4)?,
+            month_number(month)?,
+            number(day, 2)?,
+            time_of_day(time)?,
+        )
+    }
+
+    /// `Sunday, 06-Nov-94 08:49:37 GMT`
+    fn parse_rfc850(text: &str) -> Option<Self> {
+        let (day_name, rest) = text.split_once(", ")?;
+        let parts: Vec<&str> = rest.split(' ').collect();
+        let [date, time, "GMT"] = parts[..] else {
+            return None;
+        };
+        let parts: Vec<&str> = date.split('-').collect();
+        let [day, month, year] = parts[..] else {
+            return None;
+        };
+        if !LONG_DAY_NAMES.contains(&day_name) {
+            return None;
+        }
+        Self::from_parts(
+            full_year(number(year, 2)?),
+            month_number(month)?,
+            number(day, 2)?,
+            time_of_day(time)?,
+        )
+    }
+
+    /// `Sun Nov  6 08:49:37 1994`, days under 10 are padded with a space.
+    fn parse_asctime(text: &str) -> Option<Self> {
+        let (day_name, rest) = text.split_at_checked(3)?;
+        let rest = rest.strip_prefix(' ')?;
+        let (month, rest) = rest.split_at_checked(3)?;
+        let rest = rest.strip_prefix(' ')?;
+        let (day, rest) = rest.split_at_checked(2)?;
+        let parts: Vec<&str> = rest.split(' ').collect();
+        let ["", time, year] = parts[..] else {
+            return None;
+        };
+        if !DAY_NAMES.contains(&day_name) {
+            return None;
+        }
+        let day = match day.strip_prefix(' ') {
+            Some(day) => number(day, 1)?,
+            None => number(day, 2)?,
+        };
+        Self::from_parts(
+            number(year, 4)?,
+            month_number(month)?,
+            day,
+            time_of_day(time)?,
+        )
+    }
+}
+
+impl FromStr for HttpDate {
+    type Err = HttpParseError;
+
+    /// Parse a date in IMF-fixdate, RFC 850 or asctime format.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Self::parse_imf_fixdate(text)
+            .or_else(|| Self::parse_rfc850(text))
+            .or_else(|| Self::parse_asctime(text))
+            .ok_or_else(|| ParseErrorKind::Header(format!("Invalid date `{}`", text)).into())
+    }
+}
+
+impl Display for HttpDate {
+    /// Write the date as IMF-fixdate, `Sun, 06 Nov 1994 08:49:37 GMT`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let days = self.seconds / SECONDS_PER_DAY;
+        let time = self.seconds % SECONDS_PER_DAY;
+        let (year, month, day) = civil_from_days(days);
+        // the epoch was a Thursday
+        let day_name = DAY_NAMES[((days + 4) % 7) as usize];
+        write!(
+            f,
+            "{}, {:02} {} {} {:02}:{:02}:{:02} GMT",
+            day_name,
+            day,
+            MONTH_NAMES[month as usize - 1],
+            year,
+            time / 3600,
+            time % 3600 / 60,
+            time % 60
+        )
+    }
+}
+
+impl From<SystemTime> for HttpDate {
+    /// Times before the UNIX epoch are set to the epoch.
+    fn from(time: SystemTime) -> Self {
+        let seconds = time
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0);
+        Self::from_unix_seconds(seconds)
+    }
+}
+
+impl From<HttpDate> for SystemTime {
+    fn from(date: HttpDate) -> Self {
+        UNIX_EPOCH + Duration::from_secs(date.seconds)
+    }
+}
+
+/// Parse a number written with exactly `length` digits.
+fn number(text: &str, length: usize) -> Option<u64> {
+    if text.len() != length || !text.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok()
+}
+
+fn month_number(name: &str) -> Option<u64> {
+    MONTH_NAMES
+        .iter()
+        .position(|month| *month == name)
+        .map(|index| index as u64 + 1)
+}
+
+/// Parse `hh:mm:ss`.
+fn time_of_day(text: &str) -> Option<(u64, u64, u64)> {
+    let parts: Vec<&str> = text.split(':').collect();
+    let [hour, minute, second] = parts[..] else {
+        return None;
+    };
+    Some((number(hour, 2)?, number(minute, 2)?, number(second, 2)?))
+}
+
+/// A two digit year more than 50 years in the future is the latest past year with those digits.
+fn full_year(year: u64) -> u64 {
+    let (current, _, _) = civil_from_days(HttpDate::now().seconds / SECONDS_PER_DAY);
+    let year = current - current % 100 + year;
+    if year > current + 50 {
+        year - 100
+    } else {
+        year
+    }
+}
+
+fn is_leap_year(year: u64) -> bool {
+    year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400))
+}
+
+fn days_in_month(year: u64, month: u64) -> u64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// The number of days from the epoch to a date, for years from 1970 on.
+fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    // years start in March so the leap day falls at their end
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let year_of_era = year - era * 400;
+    let month_from_march = (month + 9) % 12;
+    let day_of_year = (153 * month_from_march + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The year, month and day a number of days after the epoch falls on.
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = (month_from_march + 2) % 12 + 1;
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
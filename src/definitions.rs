@@ -7,6 +7,7 @@ pub const DEFAULT_HTTPS_PORT: u16 = 443;
 impl StatusCode {
     pub const CONTINUE: Self = Self(S_CONTINUE, M_CONTINUE);
     pub const SWITCHING_PROTOCOLS: Self = Self(S_SWITCHING_PROTOCOLS, M_SWITCHING_PROTOCOLS);
+    pub const EARLY_HINTS: Self = Self(S_EARLY_HINTS, M_EARLY_HINTS);
     pub const OK: Self = Self(S_OK, M_OK);
     pub const CREATED: Self = Self(S_CREATED, M_CREATED);
     pub const ACCEPTED: Self = Self(S_ACCEPTED, M_ACCEPTED);
@@ -47,6 +48,10 @@ impl StatusCode {
         M_REQUESTED_RANGE_NOT_SATISFIABLE,
     );
     pub const EXPECTATION_FAILED: Self = Self(S_EXPECTATION_FAILED, M_EXPECTATION_FAILED);
+    pub const REQUEST_HEADER_FIELDS_TOO_LARGE: Self = Self(
+        S_REQUEST_HEADER_FIELDS_TOO_LARGE,
+        M_REQUEST_HEADER_FIELDS_TOO_LARGE,
+    );
     pub const INTERNAL_SERVER_ERROR: Self = Self(S_INTERNAL_SERVER_ERROR, M_INTERNAL_SERVER_ERROR);
     pub const NOT_IMPLEMENTED: Self = Self(S_NOT_IMPLEMENTED, M_NOT_IMPLEMENTED);
     pub const BAD_GATEWAY: Self = Self(S_BAD_GATEWAY, M_BAD_GATEWAY);
@@ -84,6 +89,7 @@ pub const H_CONTENT_LOCATION: &str = "Content-Location";
 pub const H_CONTENT_MD5: &str = "Content-MD5";
 pub const H_CONTENT_RANGE: &str = "Content-Range";
 pub const H_CONTENT_TYPE: &str = "Content-Type";
+pub const H_COOKIE: &str = "Cookie";
 pub const H_DATE: &str = "Date";
 pub const H_ETAG: &str = "ETag";
 pub const H_EXPECT: &str = "Expect";
@@ -91,11 +97,12 @@ pub const H_EXPIRES: &str = "Expires";
 pub const H_FROM: &str = "From";
 pub const H_HOST: &str = "Host";
 pub const H_IF_MATCH: &str = "If-Match";
-pub const H_IF_MODIFIED_SINCE: &str = "if-Modified-Since";
+pub const H_IF_MODIFIED_SINCE: &str = "If-Modified-Since";
 pub const H_IF_NONE_MATCH: &str = "If-None-Match";
 pub const H_IF_RANGE: &str = "If-Range";
 pub const H_IF_UNMODIFIED_SINCE: &str = "If-Unmodified-Since";
 pub const H_LAST_MODIFIED: &str = "Last-Modified";
+pub const H_LINK: &str = "Link";
 pub const H_LOCATION: &str = "Location";
 pub const H_MAX_FORWARDS: &str = "Max-Forwards";
 pub const H_PRAGMA: &str = "Pragma";
@@ -103,7 +110,9 @@ pub const H_PROXY_AUTHENTICATE: &str = "Proxy-Authenticate";
 pub const H_PROXY_AUTHORIZATION: &str = "Proxy-Authorization";
 pub const H_RANGE: &str = "Range";
 pub const H_REFERER: &str = "Referer";
+pub const H_RETRY_AFTER: &str = "Retry-After";
 pub const H_SERVER: &str = "Server";
+pub const H_SET_COOKIE: &str = "Set-Cookie";
 pub const H_TE: &str = "TE";
 pub const H_TRAILER: &str = "Trailer";
 pub const H_TRANSFER_ENCODING: &str = "Transfer-Encoding";
@@ -116,6 +125,7 @@ pub const H_WWW_AUTHENTICATE: &str = "WWW-Authenticate";
 
 pub const S_CONTINUE: usize = 100;
 pub const S_SWITCHING_PROTOCOLS: usize = 101;
+pub const S_EARLY_HINTS: usize = 103;
 pub const S_OK: usize = 200;
 pub const S_CREATED: usize = 201;
 pub const S_ACCEPTED: usize = 202;
@@ -148,6 +158,7 @@ pub const S_REQUEST_URI_TOO_LARGE: usize = 414;
 pub const S_UNSUPPORTED_MEDIA_TYPE: usize = 415;
 pub const S_REQUESTED_RANGE_NOT_SATISFIABLE: usize = 416;
 pub const S_EXPECTATION_FAILED: usize = 417;
+pub const S_REQUEST_HEADER_FIELDS_TOO_LARGE: usize = 431;
 pub const S_INTERNAL_SERVER_ERROR: usize = 500;
 pub const S_NOT_IMPLEMENTED: usize = 501;
 pub const S_BAD_GATEWAY: usize = 502;
@@ -157,6 +168,7 @@ pub const S_HTTP_VERSION_NOT_SUPPORTED: usize = 505;
 
 pub const M_CONTINUE: &str = "Continue";
 pub const M_SWITCHING_PROTOCOLS: &str = "Switching Protocols";
+pub const M_EARLY_HINTS: &str = "Early Hints";
 pub const M_OK: &str = "OK";
 pub const M_CREATED: &str = "Created";
 pub const M_ACCEPTED: &str = "Accepted";
@@ -183,12 +195,13 @@ pub const M_REQUEST_TIME_OUT: &str = "Request Time-out";
 pub const M_CONFLICT: &str = " Conflict";
 pub const M_GONE: &str = " Gone";
 pub const M_LENGTH_REQUIRED: &str = " Length Required";
-pub const M_PRECONDITION_FAILED: &str = " Precondition Failed";
+pub const M_PRECONDITION_FAILED: &str = "Precondition Failed";
 pub const M_REQUEST_ENTITY_TOO_LARGE: &str = " Request Entity Too Large";
 pub const M_REQUEST_URI_TOO_LARGE: &str = " Request-URI Too Large";
 pub const M_UNSUPPORTED_MEDIA_TYPE: &str = " Unsupported Media Type";
 pub const M_REQUESTED_RANGE_NOT_SATISFIABLE: &str = " Requested range not satisfiable";
 pub const M_EXPECTATION_FAILED: &str = " Expectation Failed";
+pub const M_REQUEST_HEADER_FIELDS_TOO_LARGE: &str = "Request Header Fields Too Large";
 pub const M_INTERNAL_SERVER_ERROR: &str = "Internal Server Error";
 pub const M_NOT_IMPLEMENTED: &str = "Not Implemented";
 pub const M_BAD_GATEWAY: &str = "Bad Gateway";
@@ -0,0 +1,578 @@
+use std::{
+    collections::hash_map::RandomState,
+    fmt::Display,
+    hash::{BuildHasher, Hasher},
+    str::FromStr,
+};
+
+use crate::{
+    auth::{parse_param, quote},
+    typed_headers::list,
+    types::{HttpParseError, ParseErrorKind},
+    Authorization, Challenge, HttpMethod, HttpRequest, HttpRequestBuilder, H_AUTHORIZATION,
+};
+
+/// The hash algorithm of HTTP Digest authentication.
+///
+/// The `-sess` variants hash the nonces into the secret, so that it changes with each nonce.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DigestAlgorithm {
+    /// The default when a challenge names no algorithm, kept for older devices.
+    #[default]
+    Md5,
+    Md5Sess,
+    Sha256,
+    Sha256Sess,
+}
+
+impl DigestAlgorithm {
+    /// The hash of `text`, in lower case hexadecimal.
+    pub fn hash(&self, text: &str) -> String {
+        match self {
+            DigestAlgorithm::Md5 | DigestAlgorithm::Md5Sess => hex(&md5(text.as_bytes())),
+            DigestAlgorithm::Sha256 | DigestAlgorithm::Sha256Sess => hex(&sha256(text.as_bytes())),
+        }
+    }
+
+    fn is_session(&self) -> bool {
+        matches!(self, DigestAlgorithm::Md5Sess | DigestAlgorithm::Sha256Sess)
+    }
+}
+
+impl FromStr for DigestAlgorithm {
+    type Err = HttpParseError;
+
+    /// Algorithm names are not case sensitive.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "MD5" => Ok(DigestAlgorithm::Md5),
+            "MD5-SESS" => Ok(DigestAlgorithm::Md5Sess),
+            "SHA-256" => Ok(DigestAlgorithm::Sha256),
+            "SHA-256-SESS" => Ok(DigestAlgorithm::Sha256Sess),
+            _ => {
+                Err(ParseErrorKind::Header(format!("Unsupported digest algorithm `{}`", s)).into())
+            }
+        }
+    }
+}
+
+impl Display for DigestAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DigestAlgorithm::Md5 => write!(f, "MD5"),
+            DigestAlgorithm::Md5Sess => write!(f, "MD5-sess"),
+            DigestAlgorithm::Sha256 => write!(f, "SHA-256"),
+            DigestAlgorithm::Sha256Sess => write!(f, "SHA-256-sess"),
+        }
+    }
+}
+
+/// The credentials of a `Digest` `Authorization` header, RFC 7616.
+///
+/// Only `qop=auth` is supported, or no `qop` at all for servers that follow RFC 2069.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DigestCredentials {
+    pub(crate) username: String,
+    pub(crate) realm: String,
+    pub(crate) nonce: String,
+    pub(crate) uri: String,
+    pub(crate) response: String,
+    pub(crate) algorithm: DigestAlgorithm,
+    pub(crate) qop: Option<String>,
+    pub(crate) cnonce: Option<String>,
+    pub(crate) nonce_count: Option<u32>,
+    pub(crate) opaque: Option<String>,
+}
+
+impl DigestCredentials {
+    /// The user name.
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    /// The realm the credentials are for.
+    pub fn realm(&self) -> &str {
+        &self.realm
+    }
+
+    /// The nonce the server sent in its challenge.
+    pub fn nonce(&self) -> &str {
+        &self.nonce
+    }
+
+    /// The request target the credentials were computed for.
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    /// The response, the hash that proves the client knows the password.
+    pub fn response(&self) -> &str {
+        &self.response
+    }
+
+    /// The hash algorithm.
+    pub fn algorithm(&self) -> DigestAlgorithm {
+        self.algorithm
+    }
+
+    /// The quality of protection, `auth` when present.
+    pub fn qop(&self) -> Option<&str> {
+        self.qop.as_deref()
+    }
+
+    /// The nonce chosen by the client, sent along with a `qop`.
+    pub fn cnonce(&self) -> Option<&str> {
+        self.cnonce.as_deref()
+    }
+
+    /// The number of requests the client made with the nonce, sent along with a `qop`.
+    ///
+    /// Servers reject counts they have already seen to prevent replays.
+    pub fn nonce_count(&self) -> Option<u32> {
+        self.nonce_count
+    }
+
+    /// The opaque value of the challenge, sent back as is.
+    pub fn opaque(&self) -> Option<&str> {
+        self.opaque.as_deref()
+    }
+
+    /// Compute the response for a request, with the fields of these credentials.
+    fn expected_response(&self, method: &HttpMethod, password: &str) -> String {
+        let algorithm = self.algorithm;
+        let mut secret = algorithm.hash(&format!("{}:{}:{}", self.username, self.realm, password));
+        if algorithm.is_session() {
+            let cnonce = self.cnonce.as_deref().unwrap_or_default();
+            secret = algorithm.hash(&format!("{}:{}:{}", secret, self.nonce, cnonce));
+        }
+        let request = algorithm.hash(&format!("{}:{}", method, self.uri));
+        match (&self.qop, &self.cnonce, self.nonce_count) {
+            (Some(qop), Some(cnonce), Some(count)) => algorithm.hash(&format!(
+                "{}:{}:{:08x}:{}:{}:{}",
+                secret, self.nonce, count, cnonce, qop, request
+            )),
+            _ => algorithm.hash(&format!("{}:{}:{}", secret, self.nonce, request)),
+        }
+    }
+
+    /// Parse the comma separated parameters of `Digest` credentials.
+    pub(crate) fn parse(text: &str) -> Option<Self> {
+        let mut params = Vec::new();
+        for element in list(&[text]) {
+            params.push(parse_param(element)?);
+        }
+        let get = |name: &str| {
+            params
+                .iter()
+                .find(|(param, _)| param == name)
+                .map(|(_, value)| value.clone())
+        };
+        let algorithm = match get("algorithm") {
+            Some(algorithm) => algorithm.parse().ok()?,
+            None => DigestAlgorithm::default(),
+        };
+        let nonce_count = match get("nc") {
+            // exactly 8 hexadecimal digits, `from_str_radix` would take a sign as well
+            Some(count)
+                if count.len() == 8 && count.bytes().all(|byte| byte.is_ascii_hexdigit()) =>
+            {
+                Some(u32::from_str_radix(&count, 16).ok()?)
+            }
+            Some(_) => return None,
+            None => None,
+        };
+        let credentials = Self {
+            username: get("username")?,
+            realm: get("realm")?,
+            nonce: get("nonce")?,
+            uri: get("uri")?,
+            response: get("response")?,
+            algorithm,
+            qop: get("qop"),
+            cnonce: get("cnonce"),
+            nonce_count,
+            opaque: get("opaque"),
+        };
+        let complete = credentials.cnonce.is_some() && credentials.nonce_count.is_some();
+        (credentials.qop.is_none() || complete).then_some(credentials)
+    }
+}
+
+impl Display for DigestCredentials {
+    /// Write the credentials as they follow `Digest` in the header.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "username={}, realm={}, uri={}, algorithm={}, nonce={}",
+            quote(&self.username),
+            quote(&self.realm),
+            quote(&self.uri),
+            self.algorithm,
+            quote(&self.nonce)
+        )?;
+        if let (Some(qop), Some(cnonce), Some(count)) = (&self.qop, &self.cnonce, self.nonce_count)
+        {
+            write!(
+                f,
+                ", nc={:08x}, cnonce={}, qop={}",
+                count,
+                quote(cnonce),
+                qop
+            )?;
+        }
+        write!(f, ", response={}", quote(&self.response))?;
+        if let Some(opaque) = &self.opaque {
+            write!(f, ", opaque={}", quote(opaque))?;
+        }
+        Ok(())
+    }
+}
+
+/// The client side of Digest authentication, answering the challenge of a server.
+///
+/// The client counts the requests it makes with the nonce of the challenge, a new client
+/// is needed when the server sends a new challenge, such as one with `stale=true`.
+///
+/// # Example:
+/// ```rust
+/// use http_parse::{
+///     DigestAlgorithm, DigestClient, HttpMethod, HttpRequestBuilder, TypedHeader, WwwAuthenticate,
+/// };
+///
+/// let header = WwwAuthenticate::decode(&[
+///     "Digest realm=\"device\", qop=\"auth\", algorithm=SHA-256, nonce=\"7ypf/xlj9XXw\"",
+/// ])
+/// .unwrap();
+/// let mut client = DigestClient::new(header.find("digest").unwrap(), "admin", "secret").unwrap();
+///
+/// let request = HttpRequestBuilder::new()
+///     .method(HttpMethod::Get)
+///     .path("/status")
+///     .digest_auth(&mut client)
+///     .build();
+/// let password = |_: &str| Some("secret".to_string());
+/// let credentials = request.verify_digest("device", DigestAlgorithm::Sha256, password);
+/// assert_eq!(credentials.unwrap().nonce_count(), Some(1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct DigestClient {
+    username: String,
+    password: String,
+    realm: String,
+    nonce: String,
+    opaque: Option<String>,
+    algorithm: DigestAlgorithm,
+    qop: bool,
+    nonce_count: u32,
+}
+
+impl DigestClient {
+    /// Prepare to answer a `Digest` challenge with a user name and password.
+    ///
+    /// # Errors:
+    /// When the challenge is not for `Digest`, misses its realm or nonce, or asks for an
+    /// algorithm or a quality of protection that is not supported.
+    pub fn new(
+        challenge: &Challenge,
+        username: &str,
+        password: &str,
+    ) -> Result<Self, HttpParseError> {
+        let missing = |what: &str| -> HttpParseError {
+            ParseErrorKind::Header(format!("Digest challenge without {}", what)).into()
+        };
+        if !challenge.scheme().eq_ignore_ascii_case("digest") {
+            return Err(ParseErrorKind::Header(format!(
+                "Expected a Digest challenge, got {}",
+                challenge.scheme()
+            ))
+            .into());
+        }
+        let algorithm = match challenge.get_param("algorithm") {
+            Some(algorithm) => algorithm.parse()?,
+            None => DigestAlgorithm::default(),
+        };
+        // a challenge offers a list of qualities of protection, `auth-int` alone is not supported
+        let qop = match challenge.get_param("qop") {
+            Some(qop) if qop.split(',').any(|qop| qop.trim() == "auth") => true,
+            Some(_) => return Err(missing("qop=auth")),
+            None => false,
+        };
+        Ok(Self {
+            username: username.to_string(),
+            password: password.to_string(),
+            realm: challenge
+                .realm()
+                .ok_or_else(|| missing("realm"))?
+                .to_string(),
+            nonce: challenge
+                .get_param("nonce")
+                .ok_or_else(|| missing("nonce"))?
+                .to_string(),
+            opaque: challenge.get_param("opaque").map(str::to_string),
+            algorithm,
+            qop,
+            nonce_count: 0,
+        })
+    }
+
+    /// The credentials for a request, with a random client nonce.
+    ///
+    /// The client nonce comes from the per-process keys of the standard library hasher,
+    /// which is not a cryptographically secure random number generator. Callers who need
+    /// client nonces that cannot be predicted should generate their own and use
+    /// [`DigestClient::authorize_with_cnonce`].
+    pub fn authorize(&mut self, method: &HttpMethod, uri: &str) -> Authorization {
+        let cnonce: String = (0..2)
+            .map(|_| format!("{:016x}", RandomState::new().build_hasher().finish()))
+            .collect();
+        self.authorize_with_cnonce(method, uri, &cnonce)
+    }
+
+    /// The credentials for a request, with the client nonce given.
+    ///
+    /// The nonce count goes up with each call.
+    pub fn authorize_with_cnonce(
+        &mut self,
+        method: &HttpMethod,
+        uri: &str,
+        cnonce: &str,
+    ) -> Authorization {
+        self.nonce_count = self.nonce_count.wrapping_add(1);
+        let mut credentials = DigestCredentials {
+            username: self.username.clone(),
+            realm: self.realm.clone(),
+            nonce: self.nonce.clone(),
+            uri: uri.to_string(),
+            response: String::new(),
+            algorithm: self.algorithm,
+            qop: self.qop.then(|| "auth".to_string()),
+            cnonce: self.qop.then(|| cnonce.to_string()),
+            nonce_count: self.qop.then_some(self.nonce_count),
+            opaque: self.opaque.clone(),
+        };
+        credentials.response = credentials.expected_response(method, &self.password);
+        Authorization::Digest(credentials)
+    }
+}
+
+impl Challenge {
+    /// A `Digest` challenge asking for `qop=auth`, the nonce is up to the server to choose
+    /// and check.
+    pub fn digest(realm: &str, nonce: &str, algorithm: DigestAlgorithm) -> Self {
+        Challenge::new("Digest")
+            .param("realm", realm)
+            .param("qop", "auth")
+            .param("algorithm", algorithm)
+            .param("nonce", nonce)
+    }
+}
+
+impl HttpRequest {
+    /// Check the `Digest` credentials of this request, `password` looks up the password of
+    /// a user name.
+    ///
+    /// The credentials have to use `algorithm` and carry `qop=auth` along with a client nonce
+    /// and a nonce count, as [`Challenge::digest`] asks for. Returns the credentials, whose
+    /// nonce and nonce count are left to the server to check against those it issued and
+    /// has seen.
+    ///
+    /// # Errors:
+    /// When the `Authorization` header is missing, not formatted properly or not for
+    /// `Digest`, the realm, target, algorithm or quality of protection are not the expected
+    /// ones, the user is not known or the response does not match.
+    pub fn verify_digest<F>(
+        &self,
+        realm: &str,
+        algorithm: DigestAlgorithm,
+        password: F,
+    ) -> Result<DigestCredentials, HttpParseError>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let rejected = |reason: &str| -> HttpParseError {
+            ParseErrorKind::Header(format!("Digest credentials rejected, {}", reason)).into()
+        };
+        let credentials = match self.typed_header::<Authorization>()? {
+            Some(Authorization::Digest(credentials)) => credentials,
+            Some(_) => return Err(rejected("not Digest")),
+            None => return Err(rejected(&format!("missing {}", H_AUTHORIZATION))),
+        };
+        if credentials.realm != realm {
+            return Err(rejected("wrong realm"));
+        }
+        if credentials.uri != self.raw_target() {
+            return Err(rejected("wrong target"));
+        }
+        if credentials.algorithm != algorithm {
+            return Err(rejected("wrong algorithm"));
+        }
+        if credentials.qop.as_deref() != Some("auth") {
+            return Err(rejected("missing qop=auth"));
+        }
+        let password = password(&credentials.username).ok_or_else(|| rejected("unknown user"))?;
+        let expected = credentials.expected_response(&self.method, &password);
+        if !constant_time_eq(
+            expected.as_bytes(),
+            credentials.response.to_ascii_lowercase().as_bytes(),
+        ) {
+            return Err(rejected("wrong response"));
+        }
+        Ok(credentials)
+    }
+}
+
+impl HttpRequestBuilder {
+    /// Authenticate with `Digest` credentials, for the method and path set before.
+    ///
+    /// The client nonce is chosen by [`DigestClient::authorize`].
+    pub fn digest_auth(self, client: &mut DigestClient) -> Self {
+        let method = self.method.clone().unwrap_or(HttpMethod::Get);
+        let uri = self.url.clone().unwrap_or_else(|| "/".to_string());
+        let authorization = client.authorize(&method, &uri);
+        self.typed_header(&authorization)
+    }
+}
+
+/// Compare without leaking how many bytes match through timing.
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .zip(right)
+            .fold(0, |difference, (left, right)| difference | (left ^ right))
+            == 0
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Pad a message to whole 64 byte blocks, ending with its length in bits.
+fn pad(data: &[u8], big_endian: bool) -> Vec<u8> {
+    let bits = (data.len() as u64).wrapping_mul(8);
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    if big_endian {
+        message.extend_from_slice(&bits.to_be_bytes());
+    } else {
+        message.extend_from_slice(&bits.to_le_bytes());
+    }
+    message
+}
+
+/// MD5, RFC 1321. Broken for signatures, still what many devices use for Digest.
+pub(crate) fn md5(data: &[u8]) -> [u8; 16] {
+    const SHIFTS: [u32; 16] = [7, 12, 17, 22, 5, 9, 14, 20, 4, 11, 16, 23, 6, 10, 15, 21];
+    const K: [u32; 64] = [
+        0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613,
+        0xfd469501, 0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193,
+        0xa679438e, 0x49b40821, 0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d,
+        0x02441453, 0xd8a1e681, 0xe7d3fbc8, 0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed,
+        0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a, 0xfffa3942, 0x8771f681, 0x6d9d6122,
+        0xfde5380c, 0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70, 0x289b7ec6, 0xeaa127fa,
+        0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665, 0xf4292244,
+        0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+        0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, 0xf7537e82, 0xbd3af235, 0x2ad7d2bb,
+        0xeb86d391,
+    ];
+    let mut state: [u32; 4] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476];
+    for block in pad(data, false).chunks(64) {
+        let words: Vec<u32> = block
+            .chunks(4)
+            .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+            .collect();
+        let [mut a, mut b, mut c, mut d] = state;
+        for (index, constant) in K.iter().enumerate() {
+            let (mixed, word) = match index / 16 {
+                0 => ((b & c) | (!b & d), index),
+                1 => ((d & b) | (!d & c), 5 * index + 1),
+                2 => (b ^ c ^ d, 3 * index + 5),
+                _ => (c ^ (b | !d), 7 * index),
+            };
+            let mixed = mixed
+                .wrapping_add(a)
+                .wrapping_add(*constant)
+                .wrapping_add(words[word % 16]);
+            a = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(mixed.rotate_left(SHIFTS[index / 16 * 4 + index % 4]));
+        }
+        for (value, added) in state.iter_mut().zip([a, b, c, d]) {
+            *value = value.wrapping_add(added);
+        }
+    }
+    let mut digest = [0; 16];
+    for (bytes, value) in digest.chunks_mut(4).zip(state) {
+        bytes.copy_from_slice(&value.to_le_bytes());
+    }
+    digest
+}
+
+/// SHA-256, FIPS 180-4.
+pub(crate) fn sha256(data: &[u8]) -> [u8; 32] {
+    const K: [u32; 64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4,
+        0xab1c5ed5, 0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe,
+        0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f,
+        0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7,
+        0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc,
+        0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b,
+        0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116,
+        0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7,
+        0xc67178f2,
+    ];
+    let mut state: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+    for block in pad(data, true).chunks(64) {
+        let mut schedule = [0u32; 64];
+        for (word, bytes) in schedule.iter_mut().zip(block.chunks(4)) {
+            *word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+        for index in 16..64 {
+            let low = schedule[index - 15];
+            let high = schedule[index - 2];
+            let sigma0 = low.rotate_right(7) ^ low.rotate_right(18) ^ (low >> 3);
+            let sigma1 = high.rotate_right(17) ^ high.rotate_right(19) ^ (high >> 10);
+            schedule[index] = schedule[index - 16]
+                .wrapping_add(sigma0)
+                .wrapping_add(schedule[index - 7])
+                .wrapping_add(sigma1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        for (constant, word) in K.iter().zip(schedule) {
+            let sum1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let choice = (e & f) ^ (!e & g);
+            let first = h
+                .wrapping_add(sum1)
+                .wrapping_add(choice)
+                .wrapping_add(*constant)
+                .wrapping_add(word);
+            let sum0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let majority = (a & b) ^ (a & c) ^ (b & c);
+            let second = sum0.wrapping_add(majority);
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(first);
+            d = c;
+            c = b;
+            b = a;
+            a = first.wrapping_add(second);
+        }
+        for (value, added) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *value = value.wrapping_add(added);
+        }
+    }
+    let mut digest = [0; 32];
+    for (bytes, value) in digest.chunks_mut(4).zip(state) {
+        bytes.copy_from_slice(&value.to_be_bytes());
+    }
+    digest
+}
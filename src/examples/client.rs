@@ -1,8 +1,8 @@
 use std::{io::Write, net::TcpStream};
 
 use http_parse::{
-    HttpMethod, HttpParser, HttpRequestBuilder, HttpUrl, StatusCode, H_CONTENT_LENGTH,
-    H_CONTENT_RANGE, H_HOST, H_RANGE, H_USER_AGENT, S_PARTIAL_CONTENT,
+    ContentLength, HttpMethod, HttpParser, HttpRequestBuilder, HttpUrl, Range, StatusCode, H_HOST,
+    H_USER_AGENT,
 };
 
 const MAX_CHUNK_SIZE: usize = 1_000_000; // 1 MB
@@ -20,7 +20,8 @@ impl Client {
         println!("{}", request);
         client.write_all(&request.into_bytes())?;
         let mut parser = HttpParser::from_reader(&mut client);
-        let response = parser.response_head_only()?;
+        parser.set_request_method(HttpMethod::Head);
+        let response = parser.response()?;
         print!("{}", response);
         if response.status_code() != StatusCode::OK {
             eprint!(
@@ -29,10 +30,9 @@ impl Client {
             );
             return Ok(0);
         }
-        if let Some(header) = response.header(H_CONTENT_LENGTH) {
-            Ok(header.value::<usize>().unwrap_or(0))
-        } else {
-            Ok(0)
+        match response.typed_header::<ContentLength>() {
+            Ok(Some(length)) => Ok(length.length() as usize),
+            _ => Ok(0),
         }
     }
     fn one_shot_download(url: &HttpUrl) -> std::io::Result<()> {
@@ -45,7 +45,8 @@ impl Client {
             .header(H_HOST, url.host())
             .build();
         client.write_all(&request.into_bytes())?;
-        let response = HttpParser::from_reader(&mut client).response()?;
+        let mut parser = HttpParser::from_reader(&mut client);
+        let (response, mut body) = parser.response_streaming()?;
         if response.status_code() != StatusCode::OK {
             eprint!(
                 "Unexpected status code `{}` from server",
@@ -54,7 +55,7 @@ impl Client {
             return Ok(());
         }
         let mut out_file = std::fs::File::create(file)?;
-        out_file.write_all(response.data())?;
+        std::io::copy(&mut body, &mut out_file)?;
         Ok(())
     }
 
@@ -66,11 +67,11 @@ impl Client {
         let mut out_file = std::fs::File::create(file)?;
         let mut total_written = 0;
 
-        let range_value = format!("bytes={}-{}", start_byte_index, chunk_size);
+        let mut range = Range::bytes(start_byte_index, Some(chunk_size as u64 - 1));
         let mut request = HttpRequestBuilder::new()
             .path(url.path())
             .header(H_HOST, url.host())
-            .header(H_RANGE, range_value)
+            .typed_header(&range)
             .header(H_USER_AGENT, "Mozilla/5.0 (WD TEST)")
             .build();
         println!("{}", request);
@@ -78,55 +79,44 @@ impl Client {
             let mut client = TcpStream::connect(url.address())?;
             client.write_all(&request.into_bytes())?;
             let response = HttpParser::from_reader(&mut client).response()?;
-            let status_code = response.status_code();
-            if status_code != S_PARTIAL_CONTENT {
-                eprint!(
-                    "Expected code STATUS CODE`{}`, instead got `{}: {}`",
-                    S_PARTIAL_CONTENT,
-                    status_code,
-                    response.status_msg()
-                );
-                return Ok(());
-            }
-            let range_header = response.header(H_CONTENT_RANGE);
-            if range_header.is_none() {
-                eprint!("Server did not provide a Content-Range header that's necessary for downloaded.");
+            let content_range = match response.validate_range(&range) {
+                Ok(content_range) => content_range,
+                Err(e) => {
+                    eprint!(
+                        "Unexpected answer `{}: {}` to a range request: {}",
+                        response.status_code(),
+                        response.status_msg(),
+                        e
+                    );
+                    return Ok(());
+                }
+            };
+            let (Some((_, last)), Some(complete_length)) =
+                (content_range.range(), content_range.complete_length())
+            else {
+                eprintln!("Server did not provide the complete length. Currently not supported.");
                 return Ok(());
-            }
-            let range_value = range_header.unwrap().value::<String>().unwrap();
-            let tokens = range_value
-                .replace("bytes ", "")
-                .split_terminator(['-', '/'])
-                .map(|str_value: &str| str_value.parse())
-                .filter(|parse_result| parse_result.is_ok())
-                .flatten()
-                .collect::<Vec<usize>>();
-            if tokens.len() < 3 {
-                eprintln!("We received less than 3 elements in range. Currently not supported.");
-                return Ok(());
-            }
+            };
+            let complete_length = complete_length as usize;
 
             let req_data = response.data();
             let body_length = req_data.len();
             out_file.write_all(req_data)?;
             total_written += body_length;
-            println!("Downloaded: {} / {} ", total_written, tokens[2]);
-            if total_written >= tokens[2] {
+            println!("Downloaded: {} / {} ", total_written, complete_length);
+            if total_written >= complete_length {
                 print!("Done downloading");
                 return Ok(());
             }
-            let bytes_left = tokens[2] - total_written;
+            let bytes_left = complete_length - total_written;
             chunk_size = std::cmp::min(MAX_CHUNK_SIZE, bytes_left);
-            start_byte_index = tokens[1] + 1;
+            start_byte_index = last + 1;
 
-            request.put_header(
-                H_RANGE,
-                format!(
-                    "bytes={}-{}",
-                    start_byte_index,
-                    start_byte_index + chunk_size
-                ),
+            range = Range::bytes(
+                start_byte_index,
+                Some(start_byte_index + chunk_size as u64 - 1),
             );
+            request.put_typed_header(&range);
         }
     }
 
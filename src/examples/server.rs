@@ -7,7 +7,7 @@ use std::{
     sync::Arc,
 };
 
-use http_parse::{HttpParser, HttpResponseBuilder};
+use http_parse::{HttpParser, HttpResponseBuilder, ParseErrorKind};
 
 pub struct Server {
     inner: TcpListener,
@@ -36,25 +36,51 @@ impl Server {
     }
 }
 
-fn handle_connection(mut client: std::net::TcpStream) -> Result<(), std::io::Error> {
+fn handle_connection(client: std::net::TcpStream) -> Result<(), std::io::Error> {
     let client_addr = client.peer_addr()?;
-    let mut parser = HttpParser::from_reader(&mut client);
-    let request = parser.request()?;
-    print!(
-        "====> Request from: {}:{} <====\n{}",
-        client_addr.ip(),
-        client_addr.port(),
-        request
-    );
-    let mut response = HttpResponseBuilder::new()
-        .header("Content-Type", "text/plain")
-        .header("Content-Length", 11)
-        .build();
+    // read and write through shared references so requests can be answered as they arrive
+    let mut reader = &client;
+    let mut parser = HttpParser::from_reader(&mut reader);
+    for request in parser.requests() {
+        let request = match request {
+            Ok(request) => request,
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    ParseErrorKind::Io(_) | ParseErrorKind::UnexpectedEof
+                ) =>
+            {
+                return Err(e.into())
+            }
+            Err(e) => {
+                // the connection cannot be read past a malformed request, answer and close it
+                let response = HttpResponseBuilder::new()
+                    .status(e.suggested_status())
+                    .header("Connection", "close")
+                    .header("Content-Length", 0)
+                    .date()
+                    .build();
+                (&client).write_all(&response.into_bytes())?;
+                return Ok(());
+            }
+        };
+        print!(
+            "====> Request from: {}:{} <====\n{}",
+            client_addr.ip(),
+            client_addr.port(),
+            request
+        );
+        let mut response = HttpResponseBuilder::new()
+            .header("Content-Type", "text/plain")
+            .header("Content-Length", 11)
+            .date()
+            .build();
 
-    response.put_header("Content-Type", "text/plain");
-    response.put_header("Content-Length", 11);
-    response.add_data("Hello world".as_bytes());
-    client.write_all(&response.into_bytes())?;
+        response.put_header("Content-Type", "text/plain");
+        response.put_header("Content-Length", 11);
+        response.add_data("Hello world".as_bytes());
+        (&client).write_all(&response.into_bytes())?;
+    }
     Ok(())
 }
 
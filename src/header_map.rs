@@ -0,0 +1,224 @@
+use std::{
+    collections::{hash_map::RandomState, HashMap},
+    fmt::Display,
+    hash::{BuildHasher, Hasher},
+};
+
+use crate::{HttpHeader, HttpParseError, TypedHeader};
+
+/// The headers of a message, in the order they were added.
+///
+/// Names are compared ignoring ASCII case and keep the casing they were added with.
+/// A name can be repeated, as `Set-Cookie` or `Via` often are: [`HeaderMap::append`] adds
+/// another header while [`HeaderMap::insert`] replaces every header with that name.
+///
+/// # Example:
+/// ```rust
+/// use http_parse::HeaderMap;
+///
+/// let mut headers = HeaderMap::new();
+/// headers.insert("Content-Type", "text/plain");
+/// headers.append("Via", "1.1 proxy-a");
+/// headers.append("via", "1.1 proxy-b");
+///
+/// assert_eq!(headers.get("content-type").unwrap().name(), "Content-Type");
+/// let via: Vec<String> = headers.get_all("VIA").iter().map(|h| h.value().unwrap()).collect();
+/// assert_eq!(via, vec!["1.1 proxy-a", "1.1 proxy-b"]);
+///
+/// headers.insert("Via", "1.1 proxy-c");
+/// assert_eq!(headers.get_all("Via").len(), 1);
+/// assert_eq!(headers.len(), 2);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HeaderMap {
+    entries: Vec<HttpHeader>,
+    /// The positions in `entries` of the headers whose names hash alike, ignoring case.
+    index: HashMap<u64, Vec<usize>>,
+    hasher: RandomState,
+}
+
+impl HeaderMap {
+    /// Create an empty map of headers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of headers, counting every repeated name.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether there are no headers.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Whether there is a header with the given name.
+    pub fn contains(&self, name: &str) -> bool {
+        self.positions(name).next().is_some()
+    }
+
+    /// The first header with the given name.
+    pub fn get(&self, name: &str) -> Option<&HttpHeader> {
+        self.positions(name)
+            .next()
+            .map(|index| &self.entries[index])
+    }
+
+    /// Every header with the given name, in the order they were added.
+    pub fn get_all(&self, name: &str) -> Vec<&HttpHeader> {
+        self.positions(name)
+            .map(|index| &self.entries[index])
+            .collect()
+    }
+
+    /// Set the value of a header, replacing every header with the same name.
+    ///
+    /// The header keeps the place of the first one it replaces.
+    pub fn insert<T: Display>(&mut self, name: &str, value: T) {
+        let positions: Vec<usize> = self.positions(name).collect();
+        let Some(&first) = positions.first() else {
+            self.push(HttpHeader::new(name, value));
+            return;
+        };
+        let repeated = positions.len() > 1;
+        self.entries[first].value = value.to_string();
+        if repeated {
+            let mut seen = false;
+            self.entries.retain(|header| {
+                let keep = !header.name.eq_ignore_ascii_case(name) || !seen;
+                seen |= header.name.eq_ignore_ascii_case(name);
+                keep
+            });
+            self.reindex();
+        }
+    }
+
+    /// Add a header, after any header with the same name.
+    pub fn append<T: Display>(&mut self, name: &str, value: T) {
+        self.push(HttpHeader::new(name, value));
+    }
+
+    /// Remove every header with the given name, returning them.
+    pub fn remove(&mut self, name: &str) -> Vec<HttpHeader> {
+        if !self.contains(name) {
+            return Vec::new();
+        }
+        let (removed, kept) = std::mem::take(&mut self.entries)
+            .into_iter()
+            .partition(|header| header.name.eq_ignore_ascii_case(name));
+        self.entries = kept;
+        self.reindex();
+        removed
+    }
+
+    /// Decode a typed header from every header with its name.
+    ///
+    /// Returns `None` when the header is absent.
+    ///
+    /// # Errors:
+    /// When the header values are not formatted properly.
+    pub fn typed<T: TypedHeader>(&self) -> Result<Option<T>, HttpParseError> {
+        let values: Vec<&str> = self
+            .positions(T::name())
+            .map(|index| self.entries[index].value.as_str())
+            .collect();
+        if values.is_empty() {
+            return Ok(None);
+        }
+        T::decode(&values).map(Some)
+    }
+
+    /// Set a typed header, replacing every header with its name.
+    pub fn insert_typed<T: TypedHeader>(&mut self, header: &T) {
+        let mut values = header.encode().into_iter();
+        match values.next() {
+            Some(first) => self.insert(T::name(), first),
+            None => {
+                self.remove(T::name());
+            }
+        }
+        for value in values {
+            self.append(T::name(), value);
+        }
+    }
+
+    /// Iterate over the headers in the order they were added.
+    pub fn iter(&self) -> std::slice::Iter<'_, HttpHeader> {
+        self.entries.iter()
+    }
+
+    fn push(&mut self, header: HttpHeader) {
+        let hash = self.hash(&header.name);
+        self.index.entry(hash).or_default().push(self.entries.len());
+        self.entries.push(header);
+    }
+
+    fn reindex(&mut self) {
+        self.index.clear();
+        for index in 0..self.entries.len() {
+            let hash = self.hash(&self.entries[index].name);
+            self.index.entry(hash).or_default().push(index);
+        }
+    }
+
+    /// The positions of the headers with the given name, names that only share a hash are
+    /// left out.
+    fn positions<'a>(&'a self, name: &'a str) -> impl Iterator<Item = usize> + 'a {
+        self.index
+            .get(&self.hash(name))
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(move |index| self.entries[*index].name.eq_ignore_ascii_case(name))
+    }
+
+    /// Hash a name ignoring ASCII case, without allocating a lowercase copy.
+    fn hash(&self, name: &str) -> u64 {
+        let mut hasher = self.hasher.build_hasher();
+        for byte in name.bytes() {
+            hasher.write_u8(byte.to_ascii_lowercase());
+        }
+        hasher.finish()
+    }
+}
+
+impl PartialEq for HeaderMap {
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries
+    }
+}
+
+impl FromIterator<HttpHeader> for HeaderMap {
+    fn from_iter<I: IntoIterator<Item = HttpHeader>>(iter: I) -> Self {
+        let mut headers = Self::new();
+        headers.extend(iter);
+        headers
+    }
+}
+
+impl Extend<HttpHeader> for HeaderMap {
+    fn extend<I: IntoIterator<Item = HttpHeader>>(&mut self, iter: I) {
+        for header in iter {
+            self.push(header);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a HeaderMap {
+    type Item = &'a HttpHeader;
+    type IntoIter = std::slice::Iter<'a, HttpHeader>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter()
+    }
+}
+
+impl IntoIterator for HeaderMap {
+    type Item = HttpHeader;
+    type IntoIter = std::vec::IntoIter<HttpHeader>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
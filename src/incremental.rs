@@ -0,0 +1,594 @@
+use crate::{
+    body::{BodyDecoder, BodyEvent, Framing},
+    borrowed::{HeadLine, HeadScanner},
+    types::{HttpParseError, ParseErrorKind},
+    HttpChunkExtension, HttpHeader, HttpMethod, HttpRequest, HttpRequestRef, HttpResponse,
+    HttpResponseRef, ParseMode, ParserConfig, S_SWITCHING_PROTOCOLS,
+};
+
+/// The outcome of feeding bytes to a [`RequestParser`] or [`ResponseParser`].
+#[derive(Debug, PartialEq)]
+pub enum ParseStatus<T> {
+    /// Every byte given was consumed and more are needed to finish the message.
+    Partial,
+    /// The message is complete.
+    /// The `usize` is the number of bytes used from the last slice fed to the parser,
+    /// any bytes after those belong to the next message.
+    Complete(T, usize),
+}
+
+/// A push based (sans-IO) parser for HTTP Requests.
+///
+/// Rather than reading from a stream, the parser is handed byte slices as they become
+/// available and keeps whatever state it needs between calls.
+/// Once a request is complete the parser is ready to parse the next one.
+///
+/// # Example:
+/// ```rust
+/// use http_parse::{HttpMethod, ParseStatus, RequestParser};
+///
+/// let mut parser = RequestParser::new();
+/// assert_eq!(parser.feed(b"GET / HTTP/1.1\r\nHo").unwrap(), ParseStatus::Partial);
+/// match parser.feed(b"st: example.com\r\n\r\nGET").unwrap() {
+///     ParseStatus::Complete(request, consumed) => {
+///         assert_eq!(request.method(), HttpMethod::Get);
+///         // the trailing `GET` belongs to the next request
+///         assert_eq!(consumed, 19);
+///     }
+///     ParseStatus::Partial => unreachable!(),
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct RequestParser {
+    message: MessageParser<HttpRequest>,
+}
+
+impl RequestParser {
+    /// Create a parser for HTTP Requests.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a parser for HTTP Requests that applies the limits in `config`.
+    pub fn with_config(config: ParserConfig) -> Self {
+        Self {
+            message: MessageParser::new(config),
+        }
+    }
+
+    /// Feed the next slice of bytes to the parser.
+    ///
+    /// # Errors:
+    /// When the data provided is not formatted properly.
+    pub fn feed(&mut self, input: &[u8]) -> Result<ParseStatus<HttpRequest>, HttpParseError> {
+        self.message.feed(input, parse_request_head)
+    }
+
+    /// Signal that no more bytes will be fed.
+    ///
+    /// Returns `None` when there was no request in progress,
+    /// or the request when it can be completed without further input.
+    ///
+    /// # Errors:
+    /// When the request was cut short.
+    pub fn finish(&mut self) -> Result<Option<HttpRequest>, HttpParseError> {
+        self.message.finish(parse_request_head)
+    }
+
+    /// Feed bytes until the request head is complete, the body is left to the decoder returned.
+    pub(crate) fn feed_head(
+        &mut self,
+        input: &[u8],
+    ) -> Result<ParseStatus<(HttpRequest, BodyDecoder)>, HttpParseError> {
+        self.message.feed_head(input, parse_request_head)
+    }
+
+    /// Signal that no more bytes will be fed while waiting for the request head.
+    pub(crate) fn finish_head(
+        &mut self,
+    ) -> Result<Option<(HttpRequest, BodyDecoder)>, HttpParseError> {
+        self.message.finish_head(parse_request_head)
+    }
+}
+
+/// A push based (sans-IO) parser for HTTP Responses.
+///
+/// See [`RequestParser`] for details on how bytes are fed to the parser.
+///
+/// Whether a response has a body depends on the request it answers, tell the parser the
+/// method of that request with [`ResponseParser::set_request_method`].
+///
+/// Interim `1xx` responses, such as `100 Continue` or `103 Early Hints`, are not returned on
+/// their own, they are collected into the final response, see [`HttpResponse::interim`].
+/// `101 Switching Protocols` is a final response.
+///
+/// # Example:
+/// ```rust
+/// use http_parse::{ParseStatus, ResponseParser, H_LINK};
+///
+/// let text = b"HTTP/1.1 103 Early Hints\r\nLink: </style.css>; rel=preload\r\n\r\n\
+/// HTTP/1.1 204 No Content\r\n\r\n";
+/// let mut parser = ResponseParser::new();
+/// let ParseStatus::Complete(response, _) = parser.feed(text).unwrap() else {
+///     panic!("the response is complete");
+/// };
+/// assert_eq!(response.status_code(), 204);
+/// let hints = response.interim();
+/// assert!(hints[0].header(H_LINK).is_some());
+/// ```
+#[derive(Debug, Default)]
+pub struct ResponseParser {
+    message: MessageParser<HttpResponse>,
+    request_method: Option<HttpMethod>,
+    interim: Vec<HttpResponse>,
+}
+
+impl ResponseParser {
+    /// Create a parser for HTTP Responses.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a parser for HTTP Responses that applies the limits in `config`.
+    pub fn with_config(config: ParserConfig) -> Self {
+        Self {
+            message: MessageParser::new(config),
+            request_method: None,
+            interim: Vec::new(),
+        }
+    }
+
+    /// Set the method of the request the next responses answer.
+    ///
+    /// Responses to `HEAD` and successful responses to `CONNECT` have no body, whatever
+    /// their headers say.
+    pub fn set_request_method(&mut self, method: HttpMethod) {
+        self.request_method = Some(method);
+    }
+
+    /// Feed the next slice of bytes to the parser.
+    ///
+    /// # Errors:
+    /// When the data provided is not formatted properly.
+    pub fn feed(&mut self, input: &[u8]) -> Result<ParseStatus<HttpResponse>, HttpParseError> {
+        self.collect_interim(input, |message, input, method| {
+            message.feed(input, |head, config| {
+                parse_response_head(head, config, method)
+            })
+        })
+    }
+
+    /// Signal that no more bytes will be fed.
+    ///
+    /// Returns `None` when there was no response in progress,
+    /// or the response when it can be completed without further input,
+    /// such as a response delimited by closing the connection.
+    ///
+    /// # Errors:
+    /// When the response was cut short.
+    pub fn finish(&mut self) -> Result<Option<HttpResponse>, HttpParseError> {
+        let method = self.request_method.as_ref();
+        let finished = self
+            .message
+            .finish(|head, config| parse_response_head(head, config, method));
+        match finished {
+            Ok(Some(mut response)) if !is_interim(&response) => {
+                response.interim = std::mem::take(&mut self.interim);
+                Ok(Some(response))
+            }
+            // interim responses promise a final response
+            Ok(_) if !self.interim.is_empty() => {
+                self.interim.clear();
+                Err(ParseErrorKind::UnexpectedEof.into())
+            }
+            finished => finished,
+        }
+    }
+
+    /// Feed bytes until the response head is complete, the body is left to the decoder returned.
+    pub(crate) fn feed_head(
+        &mut self,
+        input: &[u8],
+    ) -> Result<ParseStatus<(HttpResponse, BodyDecoder)>, HttpParseError> {
+        self.collect_interim(input, |message, input, method| {
+            message.feed_head(input, |head, config| {
+                parse_response_head(head, config, method)
+            })
+        })
+    }
+
+    /// Signal that no more bytes will be fed while waiting for the response head.
+    pub(crate) fn finish_head(
+        &mut self,
+    ) -> Result<Option<(HttpResponse, BodyDecoder)>, HttpParseError> {
+        let method = self.request_method.as_ref();
+        let finished = self
+            .message
+            .finish_head(|head, config| parse_response_head(head, config, method));
+        match finished {
+            Ok(Some((mut response, decoder))) if !is_interim(&response) => {
+                response.interim = std::mem::take(&mut self.interim);
+                Ok(Some((response, decoder)))
+            }
+            Ok(_) if !self.interim.is_empty() => {
+                self.interim.clear();
+                Err(ParseErrorKind::UnexpectedEof.into())
+            }
+            finished => finished,
+        }
+    }
+
+    /// Feed `input` with `feed` until a final response is complete,
+    /// keeping the interim responses found ahead of it.
+    fn collect_interim<T, F>(
+        &mut self,
+        input: &[u8],
+        feed: F,
+    ) -> Result<ParseStatus<T>, HttpParseError>
+    where
+        T: ParsedResponse,
+        F: Fn(
+            &mut MessageParser<HttpResponse>,
+            &[u8],
+            Option<&HttpMethod>,
+        ) -> Result<ParseStatus<T>, HttpParseError>,
+    {
+        let mut consumed = 0;
+        loop {
+            let status = feed(
+                &mut self.message,
+                &input[consumed..],
+                self.request_method.as_ref(),
+            );
+            match status {
+                Ok(ParseStatus::Complete(mut parsed, used)) => {
+                    consumed += used;
+                    if !is_interim(parsed.response()) {
+                        parsed.response().interim = std::mem::take(&mut self.interim);
+                        return Ok(ParseStatus::Complete(parsed, consumed));
+                    }
+                    let limit = self.message.config.max_interim_responses;
+                    if self.interim.len() >= limit {
+                        self.interim.clear();
+                        return Err(ParseErrorKind::TooManyInterimResponses(limit).into());
+                    }
+                    self.interim.push(parsed.into_response());
+                }
+                Ok(ParseStatus::Partial) => return Ok(ParseStatus::Partial),
+                Err(e) => {
+                    self.interim.clear();
+                    return Err(e);
+                }
+            }
+        }
+    }
+}
+
+/// A response returned by the response parser, alone or with the decoder of its body.
+trait ParsedResponse {
+    fn response(&mut self) -> &mut HttpResponse;
+    fn into_response(self) -> HttpResponse;
+}
+
+impl ParsedResponse for HttpResponse {
+    fn response(&mut self) -> &mut HttpResponse {
+        self
+    }
+
+    fn into_response(self) -> HttpResponse {
+        self
+    }
+}
+
+impl ParsedResponse for (HttpResponse, BodyDecoder) {
+    fn response(&mut self) -> &mut HttpResponse {
+        &mut self.0
+    }
+
+    fn into_response(self) -> HttpResponse {
+        self.0
+    }
+}
+
+/// Access to the parts of a message the body decoder fills in.
+pub(crate) trait Message {
+    fn body_parts(&mut self) -> BodyParts<'_>;
+}
+
+/// The parts of a message the body decoder fills in.
+pub(crate) struct BodyParts<'a> {
+    body: &'a mut Vec<u8>,
+    chunks: &'a mut Vec<(usize, usize)>,
+    extensions: &'a mut Vec<Vec<HttpChunkExtension>>,
+    trailers: &'a mut Vec<HttpHeader>,
+    chunked: &'a mut bool,
+}
+
+impl Message for HttpRequest {
+    fn body_parts(&mut self) -> BodyParts<'_> {
+        BodyParts {
+            body: &mut self.body,
+            chunks: &mut self.chunks,
+            extensions: &mut self.extensions,
+            trailers: &mut self.trailers,
+            chunked: &mut self.chunked,
+        }
+    }
+}
+
+impl Message for HttpResponse {
+    fn body_parts(&mut self) -> BodyParts<'_> {
+        BodyParts {
+            body: &mut self.body,
+            chunks: &mut self.chunks,
+            extensions: &mut self.extensions,
+            trailers: &mut self.trailers,
+            chunked: &mut self.chunked,
+        }
+    }
+}
+
+/// The state shared by the request and response parsers.
+#[derive(Debug)]
+struct MessageParser<M> {
+    config: ParserConfig,
+    head: HeadBuffer,
+    message: Option<(M, BodyDecoder)>,
+    /// The bytes and line feeds of the head of the message in progress.
+    head_end: (usize, usize),
+}
+
+impl<M> Default for MessageParser<M> {
+    fn default() -> Self {
+        Self::new(ParserConfig::default())
+    }
+}
+
+impl<M> MessageParser<M> {
+    fn new(config: ParserConfig) -> Self {
+        Self {
+            config,
+            head: HeadBuffer::default(),
+            message: None,
+            head_end: (0, 0),
+        }
+    }
+}
+
+impl<M: Message> MessageParser<M> {
+    fn feed<F>(&mut self, input: &[u8], parse_head: F) -> Result<ParseStatus<M>, HttpParseError>
+    where
+        F: Fn(&[u8], &ParserConfig) -> Result<(M, Framing), HttpParseError>,
+    {
+        let mut consumed = 0;
+        if self.message.is_none() {
+            match self.feed_head(input, parse_head)? {
+                ParseStatus::Partial => return Ok(ParseStatus::Partial),
+                ParseStatus::Complete(message, used) => {
+                    self.message = Some(message);
+                    consumed = used;
+                }
+            }
+        }
+        if !self.read_body(input, &mut consumed)? {
+            return Ok(ParseStatus::Partial);
+        }
+        Ok(ParseStatus::Complete(self.take(), consumed))
+    }
+
+    fn finish<F>(&mut self, parse_head: F) -> Result<Option<M>, HttpParseError>
+    where
+        F: Fn(&[u8], &ParserConfig) -> Result<(M, Framing), HttpParseError>,
+    {
+        if self.message.is_none() {
+            match self.finish_head(parse_head)? {
+                Some(message) => self.message = Some(message),
+                None => return Ok(None),
+            }
+        }
+        let complete = match &mut self.message {
+            Some((_, decoder)) => decoder.finish(),
+            None => true,
+        };
+        if !complete {
+            self.message = None;
+            return Err(ParseErrorKind::UnexpectedEof.into());
+        }
+        Ok(Some(self.take()))
+    }
+
+    fn feed_head<F>(
+        &mut self,
+        input: &[u8],
+        parse_head: F,
+    ) -> Result<ParseStatus<(M, BodyDecoder)>, HttpParseError>
+    where
+        F: Fn(&[u8], &ParserConfig) -> Result<(M, Framing), HttpParseError>,
+    {
+        match self.head.push(input, &self.config) {
+            Ok(Some(used)) => Ok(ParseStatus::Complete(self.parse_head(parse_head)?, used)),
+            Ok(None) => Ok(ParseStatus::Partial),
+            Err(e) => {
+                self.head.clear();
+                Err(e)
+            }
+        }
+    }
+
+    fn finish_head<F>(&mut self, parse_head: F) -> Result<Option<(M, BodyDecoder)>, HttpParseError>
+    where
+        F: Fn(&[u8], &ParserConfig) -> Result<(M, Framing), HttpParseError>,
+    {
+        if self.head.is_empty() {
+            return Ok(None);
+        }
+        // Be forgiving with a head that is missing only its final empty line.
+        if self.config.mode == ParseMode::Strict || !self.head.ends_with_line() {
+            self.head.clear();
+            return Err(ParseErrorKind::UnexpectedEof.into());
+        }
+        self.parse_head(parse_head).map(Some)
+    }
+
+    fn parse_head<F>(&mut self, parse_head: F) -> Result<(M, BodyDecoder), HttpParseError>
+    where
+        F: Fn(&[u8], &ParserConfig) -> Result<(M, Framing), HttpParseError>,
+    {
+        let parsed = parse_head(self.head.bytes(), &self.config).map_err(|e| {
+            let (bytes, lines) = self.head.skipped;
+            e.shifted(bytes, lines)
+        });
+        self.head_end = self.head.end();
+        self.head.clear();
+        let (message, framing) = parsed?;
+        Ok((message, BodyDecoder::new(framing, &self.config)?))
+    }
+
+    /// Decode as much of the body as `input` holds, returns whether the body is complete.
+    fn read_body(&mut self, input: &[u8], consumed: &mut usize) -> Result<bool, HttpParseError> {
+        let Some((message, decoder)) = self.message.as_mut() else {
+            return Ok(false);
+        };
+        let parts = message.body_parts();
+        loop {
+            let (used, event) = match decoder.decode(&input[*consumed..]) {
+                Ok(decoded) => decoded,
+                Err(e) => {
+                    self.message = None;
+                    let (bytes, lines) = self.head_end;
+                    return Err(e.shifted(bytes, lines));
+                }
+            };
+            *consumed += used;
+            match event {
+                BodyEvent::Data(data) => parts.body.extend_from_slice(data),
+                BodyEvent::ChunkStart(size) => {
+                    parts.extensions.push(decoder.chunk_extensions().to_vec());
+                    *parts.chunked = true;
+                    match size {
+                        // last chunk 0 data
+                        0 => parts.chunks.push((0, 0)),
+                        size => parts
+                            .chunks
+                            .push((parts.body.len(), parts.body.len() + size)),
+                    }
+                }
+                BodyEvent::NeedMore => return Ok(false),
+                BodyEvent::Done => {
+                    *parts.trailers = decoder.trailers().to_vec();
+                    return Ok(true);
+                }
+            }
+        }
+    }
+
+    fn take(&mut self) -> M {
+        let (message, _) = self.message.take().expect("message in progress");
+        message
+    }
+}
+
+/// Collects the bytes of a message head up to and including the empty line that ends it.
+#[derive(Debug, Default)]
+struct HeadBuffer {
+    bytes: Vec<u8>,
+    line_start: usize,
+    scanner: HeadScanner,
+    /// The bytes and lines of the empty lines skipped ahead of the start line.
+    skipped: (usize, usize),
+}
+
+impl HeadBuffer {
+    /// Append bytes from `input` until the end of the head.
+    ///
+    /// Returns the number of bytes used from `input` once the head is complete.
+    fn push(
+        &mut self,
+        input: &[u8],
+        config: &ParserConfig,
+    ) -> Result<Option<usize>, HttpParseError> {
+        let mut position = 0;
+        while let Some(index) = input[position..].iter().position(|byte| *byte == b'\n') {
+            let end = position + index + 1;
+            self.bytes.extend_from_slice(&input[position..end]);
+            position = end;
+            let line = self
+                .scanner
+                .line(&self.bytes[self.line_start..], config)
+                .map_err(|e| self.locate(e))?;
+            match line {
+                HeadLine::End => return Ok(Some(end)),
+                HeadLine::Skip => {
+                    self.skipped = (self.skipped.0 + self.bytes.len(), self.skipped.1 + 1);
+                    self.bytes.clear();
+                }
+                HeadLine::Field => {}
+            }
+            self.line_start = self.bytes.len();
+        }
+        self.bytes.extend_from_slice(&input[position..]);
+        self.scanner
+            .partial(&self.bytes[self.line_start..], config)
+            .map_err(|e| self.locate(e))?;
+        Ok(None)
+    }
+
+    /// Locate an error found in the current line from the start of the message.
+    fn locate(&self, e: HttpParseError) -> HttpParseError {
+        let (bytes, lines) = self.skipped;
+        e.within(&self.bytes, self.line_start).shifted(bytes, lines)
+    }
+
+    /// The bytes and line feeds of the message in the head, with the empty lines skipped
+    /// ahead of it.
+    fn end(&self) -> (usize, usize) {
+        let line_feeds = self.bytes.iter().filter(|byte| **byte == b'\n').count();
+        (
+            self.skipped.0 + self.bytes.len(),
+            self.skipped.1 + line_feeds,
+        )
+    }
+
+    fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn ends_with_line(&self) -> bool {
+        self.line_start == self.bytes.len()
+    }
+
+    fn clear(&mut self) {
+        self.bytes.clear();
+        self.line_start = 0;
+        self.scanner = HeadScanner::default();
+        self.skipped = (0, 0);
+    }
+}
+
+fn parse_request_head(
+    head: &[u8],
+    config: &ParserConfig,
+) -> Result<(HttpRequest, Framing), HttpParseError> {
+    let request = HttpRequestRef::parse_head(head, config)?;
+    let framing = Framing::for_request(request.headers(), request.version(), config)?;
+    Ok((request.to_owned(), framing))
+}
+
+/// Whether `response` is an interim response, `101 Switching Protocols` ends the exchange.
+fn is_interim(response: &HttpResponse) -> bool {
+    (100..200).contains(&response.status_code) && response.status_code != S_SWITCHING_PROTOCOLS
+}
+
+fn parse_response_head(
+    head: &[u8],
+    config: &ParserConfig,
+    method: Option<&HttpMethod>,
+) -> Result<(HttpResponse, Framing), HttpParseError> {
+    let response = HttpResponseRef::parse_head(head, config)?;
+    let framing = Framing::for_response(&response, method, config)?;
+    Ok((response.to_owned(), framing))
+}
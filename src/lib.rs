@@ -59,16 +59,72 @@
 //! }
 //! ```
 //!
+//! # Features:
+//! `tokio` adds [`AsyncHttpParser`] and `write_to` on requests and responses, for use with
+//! `tokio::io::AsyncRead` and `tokio::io::AsyncWrite`.
+#[cfg(feature = "tokio")]
+mod async_parser;
+mod auth;
+mod body;
+mod borrowed;
+mod conditional;
+mod config;
+mod cookie;
+mod date;
 #[allow(unused)]
 mod definitions;
+mod digest;
+mod header_map;
+mod incremental;
+mod multipart;
+mod negotiation;
 #[allow(unused)]
 mod parser;
+mod range;
+mod target;
+mod typed_headers;
 #[allow(unused)]
 mod types;
 
+#[cfg(feature = "tokio")]
+pub use async_parser::AsyncHttpParser;
+pub use auth::{Authorization, Challenge, ProxyAuthenticate, WwwAuthenticate};
+pub use body::BodyReader;
+pub use borrowed::HttpHeaderRef;
+pub use borrowed::HttpRequestRef;
+pub use borrowed::HttpResponseRef;
+pub use conditional::{
+    ETag, IfMatch, IfModifiedSince, IfNoneMatch, IfRange, IfUnmodifiedSince, Precondition,
+    Validators,
+};
+pub use config::ParseMode;
+pub use config::ParserConfig;
+pub use config::{
+    DEFAULT_MAX_HEADERS, DEFAULT_MAX_HEADER_SIZE, DEFAULT_MAX_INTERIM_RESPONSES,
+    DEFAULT_MAX_START_LINE_LENGTH,
+};
+pub use cookie::{Cookie, CookieBuilder, CookieJar, SameSite};
+pub use date::HttpDate;
 pub use definitions::*;
+pub use digest::{DigestAlgorithm, DigestClient, DigestCredentials};
+pub use header_map::HeaderMap;
+pub use incremental::ParseStatus;
+pub use incremental::RequestParser;
+pub use incremental::ResponseParser;
+pub use negotiation::{
+    Accept, AcceptCharset, AcceptEncoding, AcceptLanguage, MediaRange, QualityValue,
+};
 pub use parser::HttpParser;
+pub use parser::{Requests, Responses};
+pub use range::{ByteRange, ContentRange, Range};
+pub use target::RequestTarget;
+pub use typed_headers::TypedHeader;
+pub use typed_headers::{
+    Allow, Connection, ContentLength, ContentType, Date, Expires, Host, LastModified, Location,
+    RetryAfter, TransferEncoding,
+};
 
+pub use types::HttpChunkExtension;
 pub use types::HttpHeader;
 pub use types::HttpMethod;
 pub use types::HttpRequest;
@@ -76,7 +132,7 @@ pub use types::HttpResponse;
 pub use types::HttpUrl;
 pub use types::HttpVersion;
 
-pub use types::HttpParseError;
 pub use types::HttpRequestBuilder;
 pub use types::HttpResponseBuilder;
 pub use types::HttpUrlBuilder;
+pub use types::{HttpParseError, ParseErrorKind};
@@ -0,0 +1,257 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    io::{Cursor, Error, ErrorKind, Read, Seek, SeekFrom},
+};
+
+use crate::{
+    types::{HttpParseError, ParseErrorKind},
+    ContentLength, ContentRange, ContentType, HeaderMap, HttpResponse, HttpResponseBuilder,
+    StatusCode, TypedHeader, H_CONTENT_RANGE, H_CONTENT_TYPE,
+};
+
+/// The media type of a response that carries several ranges.
+const MULTIPART_BYTERANGES: &str = "multipart/byteranges";
+
+impl HttpResponseBuilder {
+    /// Make this response the answer to a range request for `resource`.
+    ///
+    /// `ranges` are the ranges to send, as [`Range::resolve`](crate::Range::resolve) gives
+    /// them. A single range is sent as is with its `Content-Range`, several ranges as a
+    /// `multipart/byteranges` body whose parts carry `content_type` and their own
+    /// `Content-Range`. Ranges are cut to the end of the resource, those past it or inverted
+    /// are left out and when none is left the response is a `416 Range Not Satisfiable`.
+    ///
+    /// The status, `Content-Type`, `Content-Range`, `Content-Length` and body are set.
+    ///
+    /// # Example:
+    /// ```rust
+    /// use http_parse::{ByteRange, HttpResponseBuilder, Range};
+    ///
+    /// let resource = b"The quick brown fox jumps over the lazy dog";
+    /// let requested = Range::new(&[ByteRange::FromTo(4, 8), ByteRange::Last(3)]);
+    /// let ranges = requested.resolve(resource.len() as u64).unwrap();
+    /// let response = HttpResponseBuilder::new()
+    ///     .byte_ranges(resource, &ranges, "text/plain")
+    ///     .build();
+    ///
+    /// assert_eq!(response.status_code(), 206);
+    /// let parts = response.byte_ranges().unwrap();
+    /// assert_eq!(parts[0].1, b"quick");
+    /// assert_eq!(parts[1].1, b"dog");
+    /// ```
+    pub fn byte_ranges(self, resource: &[u8], ranges: &[ContentRange], content_type: &str) -> Self {
+        // the resource is held in memory already
+        self.byte_ranges_from(&mut Cursor::new(resource), ranges, content_type, usize::MAX)
+            .expect("reading from memory does not fail")
+    }
+
+    /// Make this response the answer to a range request for the resource `reader` reads.
+    ///
+    /// See [`HttpResponseBuilder::byte_ranges`], only the ranges sent are read and they are
+    /// held in memory, `max_size` bounds the number of bytes read for all of them.
+    ///
+    /// # Errors:
+    /// When seeking or reading in the resource produces any error, or the ranges sent add
+    /// up to more than `max_size` bytes, with `std::io::ErrorKind::InvalidInput`.
+    pub fn byte_ranges_from<R: Read + Seek>(
+        self,
+        reader: &mut R,
+        ranges: &[ContentRange],
+        content_type: &str,
+        max_size: usize,
+    ) -> std::io::Result<Self> {
+        let length = reader.seek(SeekFrom::End(0))?;
+        let mut parts = Vec::new();
+        let mut total: usize = 0;
+        for range in ranges {
+            let in_resource = |(first, last): &(u64, u64)| first <= last && *first < length;
+            let Some((first, last)) = range.range.filter(in_resource) else {
+                continue;
+            };
+            let last = last.min(length - 1);
+            let size = usize::try_from(last - first + 1)
+                .ok()
+                .filter(|size| total.checked_add(*size).is_some_and(|sum| sum <= max_size))
+                .ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Ranges larger than {} bytes", max_size),
+                    )
+                })?;
+            total += size;
+            let mut part = vec![0; size];
+            reader.seek(SeekFrom::Start(first))?;
+            reader.read_exact(&mut part)?;
+            parts.push((ContentRange::bytes(first, last, Some(length)), part));
+        }
+
+        let mut response = self;
+        let headers = response.headers.get_or_insert_with(HeaderMap::new);
+        let body = match &parts[..] {
+            [] => {
+                response.status_code = Some(StatusCode::REQUESTED_RANGE_NOT_SATISFIABLE);
+                headers.insert_typed(&ContentRange::unsatisfied(length));
+                Vec::new()
+            }
+            [(range, part)] => {
+                response.status_code = Some(StatusCode::PARTIAL_CONTENT);
+                headers.insert(H_CONTENT_TYPE, content_type);
+                headers.insert_typed(range);
+                part.clone()
+            }
+            parts => {
+                response.status_code = Some(StatusCode::PARTIAL_CONTENT);
+                let boundary = boundary(parts.iter().map(|(_, part)| part.as_slice()));
+                let multipart = ContentType::new(MULTIPART_BYTERANGES).param("boundary", &boundary);
+                headers.insert_typed(&multipart);
+                headers.remove(H_CONTENT_RANGE);
+                let mut body = Vec::new();
+                for (range, part) in parts {
+                    body.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+                    body.extend_from_slice(
+                        format!("{}: {}\r\n", H_CONTENT_TYPE, content_type).as_bytes(),
+                    );
+                    let content_range = range.encode().join("");
+                    body.extend_from_slice(
+                        format!("{}: {}\r\n\r\n", H_CONTENT_RANGE, content_range).as_bytes(),
+                    );
+                    body.extend_from_slice(part);
+                    body.extend_from_slice(b"\r\n");
+                }
+                body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+                body
+            }
+        };
+        headers.insert_typed(&ContentLength::new(body.len() as u64));
+        response.data = Some(body);
+        Ok(response)
+    }
+}
+
+impl HttpResponse {
+    /// The ranges this `206 Partial Content` response carries, along with their bytes.
+    ///
+    /// A `multipart/byteranges` body is split into its parts, otherwise the whole body is the
+    /// range its `Content-Range` header gives.
+    ///
+    /// # Errors:
+    /// When a `Content-Range` is missing or not formatted properly, or the multipart body is
+    /// not formatted properly.
+    pub fn byte_ranges(&self) -> Result<Vec<(ContentRange, Vec<u8>)>, HttpParseError> {
+        let content_type = self.typed_header::<ContentType>()?;
+        let boundary = content_type
+            .as_ref()
+            .filter(|content_type| content_type.mime() == MULTIPART_BYTERANGES)
+            .map(|content_type| content_type.get_param("boundary"));
+        match boundary {
+            None => {
+                let range = required_range(self.header_map())?;
+                Ok(vec![(range, self.body.clone())])
+            }
+            Some(None) => Err(ParseErrorKind::Header(format!(
+                "Missing boundary in {} `{}`",
+                H_CONTENT_TYPE, MULTIPART_BYTERANGES
+            ))
+            .into()),
+            Some(Some(boundary)) => split_parts(&self.body, boundary),
+        }
+    }
+}
+
+fn required_range(headers: &HeaderMap) -> Result<ContentRange, HttpParseError> {
+    headers.typed::<ContentRange>()?.ok_or_else(|| {
+        ParseErrorKind::Header(format!("Missing {} in a partial response", H_CONTENT_RANGE)).into()
+    })
+}
+
+/// Split a `multipart/byteranges` body into its ranges.
+///
+/// The preamble before the first delimiter and the epilogue after the last are skipped.
+fn split_parts(
+    body: &[u8],
+    boundary: &str,
+) -> Result<Vec<(ContentRange, Vec<u8>)>, HttpParseError> {
+    let invalid = |reason: &str| -> HttpParseError {
+        ParseErrorKind::Body(format!("Invalid {} body, {}", MULTIPART_BYTERANGES, reason)).into()
+    };
+    let delimiter = format!("--{}", boundary);
+    let delimiter = delimiter.as_bytes();
+    let mut rest = match find(body, delimiter) {
+        Some(start) => &body[start + delimiter.len()..],
+        None => return Err(invalid("no delimiter")),
+    };
+    let mut parts = Vec::new();
+    loop {
+        if rest.starts_with(b"--") {
+            break;
+        }
+        // the delimiter line may end with whitespace
+        let line_end = find(rest, b"\n").ok_or_else(|| invalid("part cut short"))?;
+        if !rest[..line_end].trim_ascii().is_empty() {
+            return Err(invalid("text after a delimiter"));
+        }
+        rest = &rest[line_end + 1..];
+
+        // headers end on an empty line, whichever line ending comes first
+        let head_end = match rest {
+            [b'\r', b'\n', ..] => Some((0, 2)),
+            [b'\n', ..] => Some((0, 1)),
+            _ => [&b"\r\n\r\n"[..], b"\n\n"]
+                .into_iter()
+                .filter_map(|end| find(rest, end).map(|start| (start, start + end.len())))
+                .min(),
+        }
+        .ok_or_else(|| invalid("part headers cut short"))?;
+        let mut headers = HeaderMap::new();
+        for line in rest[..head_end.0].split(|byte| *byte == b'\n') {
+            let line = String::from_utf8_lossy(line.trim_ascii()).to_string();
+            if line.is_empty() {
+                continue;
+            }
+            let (name, value) = line
+                .split_once(':')
+                .ok_or_else(|| invalid("part header without a colon"))?;
+            headers.append(name.trim(), value.trim());
+        }
+        let range = required_range(&headers)?;
+        rest = &rest[head_end.1..];
+
+        // the line ending ahead of a delimiter belongs to it, not to the part
+        let next_delimiter =
+            find(rest, delimiter).ok_or_else(|| invalid("missing closing delimiter"))?;
+        let end = match &rest[..next_delimiter] {
+            [.., b'\r', b'\n'] => next_delimiter - 2,
+            [.., b'\n'] => next_delimiter - 1,
+            _ => return Err(invalid("delimiter not on a line of its own")),
+        };
+        let next = next_delimiter + delimiter.len();
+        parts.push((range, rest[..end].to_vec()));
+        rest = &rest[next..];
+    }
+    if parts.is_empty() {
+        return Err(invalid("no part"));
+    }
+    Ok(parts)
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// A random boundary that none of the parts contains.
+fn boundary<'a>(parts: impl Iterator<Item = &'a [u8]> + Clone) -> String {
+    loop {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_usize(0);
+        let boundary = format!("BYTERANGES_{:016x}", hasher.finish());
+        if parts
+            .clone()
+            .all(|part| find(part, boundary.as_bytes()).is_none())
+        {
+            return boundary;
+        }
+    }
+}
@@ -0,0 +1,544 @@
+use std::fmt::Display;
+
+use crate::{
+    borrowed::{is_token, next_parameter},
+    typed_headers::{invalid, list},
+    types::{quote_if_needed, HttpParseError},
+    ContentType, HttpRequest, StatusCode, TypedHeader, H_ACCEPT, H_ACCEPT_CHARSET,
+    H_ACCEPT_ENCODING, H_ACCEPT_LANGUAGE,
+};
+
+/// The weight of a preference, `q=1` if not given, in thousandths.
+const DEFAULT_QUALITY: u16 = 1000;
+
+/// A value of an `Accept-*` header along with its weight, such as `gzip;q=0.8`.
+///
+/// Weights are kept in thousandths, `q=0.8` is `800`. A weight of `0` means "not acceptable".
+#[derive(Debug, Clone, PartialEq)]
+pub struct QualityValue {
+    pub(crate) value: String,
+    pub(crate) quality: u16,
+}
+
+impl QualityValue {
+    /// Create a weighted value, the weight is in thousandths and capped at `1000`.
+    pub fn new(value: &str, quality: u16) -> Self {
+        Self {
+            value: value.to_string(),
+            quality: quality.min(DEFAULT_QUALITY),
+        }
+    }
+
+    /// The value, such as `gzip`, `en-US` or `*`.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// The weight, in thousandths.
+    pub fn quality(&self) -> u16 {
+        self.quality
+    }
+
+    fn parse(element: &str) -> Option<Self> {
+        let (value, params) = element.split_at(element.find(';').unwrap_or(element.len()));
+        let value = value.trim();
+        let mut quality = DEFAULT_QUALITY;
+        for (name, param) in parse_params(params)? {
+            if name.eq_ignore_ascii_case("q") {
+                quality = parse_quality(param.as_deref()?)?;
+            }
+        }
+        (!value.is_empty()).then(|| Self::new(value, quality))
+    }
+}
+
+impl Display for QualityValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.value, weight(self.quality))
+    }
+}
+
+/// A media range of an `Accept` header, such as `text/*`, `text/html;level=1` or `*/*;q=0.1`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaRange {
+    pub(crate) mime: String,
+    pub(crate) params: Vec<(String, String)>,
+    pub(crate) quality: u16,
+}
+
+impl MediaRange {
+    /// Create a media range with a weight in thousandths, capped at `1000`.
+    pub fn new(mime: &str, quality: u16) -> Self {
+        Self {
+            mime: mime.to_ascii_lowercase(),
+            params: Vec::new(),
+            quality: quality.min(DEFAULT_QUALITY),
+        }
+    }
+
+    /// Add a media type parameter, only media types with that parameter match the range.
+    pub fn param<T: Display>(mut self, name: &str, value: T) -> Self {
+        self.params
+            .push((name.to_ascii_lowercase(), value.to_string()));
+        self
+    }
+
+    /// The media range, in lower case, such as `text/*`.
+    pub fn mime(&self) -> &str {
+        &self.mime
+    }
+
+    /// The media type parameters the range asks for.
+    pub fn params(&self) -> &[(String, String)] {
+        &self.params
+    }
+
+    /// The weight, in thousandths.
+    pub fn quality(&self) -> u16 {
+        self.quality
+    }
+
+    /// How specifically this range matches a media type, `None` if it does not match it.
+    ///
+    /// `*/*` is the least specific, followed by `type/*`, `type/subtype` and
+    /// `type/subtype` with parameters.
+    fn specificity(&self, content_type: &ContentType) -> Option<usize> {
+        let (kind, subtype) = self.mime.split_once('/')?;
+        let matches_type = match (kind, subtype) {
+            ("*", "*") => Some(0),
+            (kind, "*") => content_type
+                .mime
+                .split_once('/')
+                .is_some_and(|(other, _)| other == kind)
+                .then_some(1),
+            _ => (content_type.mime == self.mime).then_some(2),
+        }?;
+        let matches_params = self.params.iter().all(|(name, value)| {
+            content_type
+                .get_param(name)
+                .is_some_and(|other| other.eq_ignore_ascii_case(value))
+        });
+        matches_params.then_some(matches_type + self.params.len())
+    }
+
+    fn parse(element: &str) -> Option<Self> {
+        let (mime, params) = element.split_at(element.find(';').unwrap_or(element.len()));
+        let (kind, subtype) = mime.trim().split_once('/')?;
+        let valid = is_token(kind.as_bytes())
+            && is_token(subtype.as_bytes())
+            && (kind != "*" || subtype == "*");
+        if !valid {
+            return None;
+        }
+        let mut range = Self::new(mime.trim(), DEFAULT_QUALITY);
+        for (name, param) in parse_params(params)? {
+            let param = param?;
+            if name.eq_ignore_ascii_case("q") {
+                // parameters after the weight are extensions, they are left out
+                range.quality = parse_quality(&param)?;
+                break;
+            }
+            range = range.param(&name, param);
+        }
+        Some(range)
+    }
+}
+
+impl Display for MediaRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.mime)?;
+        for (name, value) in &self.params {
+            write!(f, ";{}={}", name, quote_if_needed(value))?;
+        }
+        write!(f, "{}", weight(self.quality))
+    }
+}
+
+/// `Accept`, the media types the client prefers in a response.
+///
+/// # Example:
+/// ```rust
+/// use http_parse::{Accept, TypedHeader};
+///
+/// let accept = Accept::decode(&["text/*;q=0.5, text/html, application/json;q=0"]).unwrap();
+/// assert_eq!(accept.negotiate(&["application/json", "text/plain"]), Some("text/plain"));
+/// assert_eq!(accept.negotiate(&["text/plain", "text/html"]), Some("text/html"));
+/// assert_eq!(accept.negotiate(&["application/json", "image/png"]), None);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Accept(pub(crate) Vec<MediaRange>);
+
+impl Accept {
+    /// Create the header from its media ranges.
+    pub fn new(ranges: &[MediaRange]) -> Self {
+        Self(ranges.to_vec())
+    }
+
+    /// The media ranges, in the order they were sent.
+    pub fn ranges(&self) -> &[MediaRange] {
+        &self.0
+    }
+
+    /// The weight of a media type, from the most specific range that matches it.
+    ///
+    /// Media types that match no range, or that are not valid, weigh `0`.
+    pub fn quality(&self, media_type: &str) -> u16 {
+        let Ok(content_type) = ContentType::decode(&[media_type]) else {
+            return 0;
+        };
+        self.0
+            .iter()
+            .filter_map(|range| Some((range.specificity(&content_type)?, range.quality)))
+            // the first of the most specific ranges wins
+            .rev()
+            .max_by_key(|(specificity, _)| *specificity)
+            .map_or(0, |(_, quality)| quality)
+    }
+
+    /// The acceptable media type with the highest weight, the first one on ties.
+    pub fn negotiate<'a>(&self, available: &[&'a str]) -> Option<&'a str> {
+        best(available, |media_type| self.quality(media_type))
+    }
+}
+
+impl TypedHeader for Accept {
+    fn name() -> &'static str {
+        H_ACCEPT
+    }
+
+    fn decode(values: &[&str]) -> Result<Self, HttpParseError> {
+        list(values)
+            .into_iter()
+            .map(MediaRange::parse)
+            .collect::<Option<Vec<MediaRange>>>()
+            .map(Self)
+            .ok_or_else(|| invalid::<Self>(values))
+    }
+
+    fn encode(&self) -> Vec<String> {
+        let ranges: Vec<String> = self.0.iter().map(MediaRange::to_string).collect();
+        vec![ranges.join(", ")]
+    }
+}
+
+/// `Accept-Language`, the natural languages the client prefers in a response.
+///
+/// Ranges match the language tags they are a prefix of, `en` matches `en-US`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AcceptLanguage(pub(crate) Vec<QualityValue>);
+
+impl AcceptLanguage {
+    /// Create the header from its language ranges.
+    pub fn new(ranges: &[QualityValue]) -> Self {
+        Self(ranges.to_vec())
+    }
+
+    /// The language ranges, in the order they were sent.
+    pub fn ranges(&self) -> &[QualityValue] {
+        &self.0
+    }
+
+    /// The weight of a language tag, from the longest range that matches it.
+    pub fn quality(&self, tag: &str) -> u16 {
+        self.0
+            .iter()
+            .filter_map(|range| {
+                let matches = range.value == "*"
+                    || range.value.eq_ignore_ascii_case(tag)
+                    || tag
+                        .get(..range.value.len())
+                        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(&range.value))
+                        && tag.as_bytes().get(range.value.len()) == Some(&b'-');
+                let length = if range.value == "*" {
+                    0
+                } else {
+                    range.value.len()
+                };
+                matches.then_some((length, range.quality))
+            })
+            .rev()
+            .max_by_key(|(length, _)| *length)
+            .map_or(0, |(_, quality)| quality)
+    }
+
+    /// The acceptable language with the highest weight, the first one on ties.
+    pub fn negotiate<'a>(&self, available: &[&'a str]) -> Option<&'a str> {
+        best(available, |tag| self.quality(tag))
+    }
+}
+
+impl TypedHeader for AcceptLanguage {
+    fn name() -> &'static str {
+        H_ACCEPT_LANGUAGE
+    }
+
+    fn decode(values: &[&str]) -> Result<Self, HttpParseError> {
+        let ranges = decode_weighted::<Self>(values)?;
+        let valid = ranges.iter().all(|range| {
+            range.value == "*"
+                || range
+                    .value
+                    .split('-')
+                    .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_alphanumeric()))
+        });
+        if !valid {
+            return Err(invalid::<Self>(values));
+        }
+        Ok(Self(ranges))
+    }
+
+    fn encode(&self) -> Vec<String> {
+        encode_weighted(&self.0)
+    }
+}
+
+/// `Accept-Encoding`, the content codings the client accepts in a response.
+///
+/// `identity`, no coding at all, is acceptable unless it is refused explicitly, with
+/// `identity;q=0` or with `*;q=0` when `identity` is not listed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AcceptEncoding(pub(crate) Vec<QualityValue>);
+
+impl AcceptEncoding {
+    /// Create the header from its codings.
+    pub fn new(codings: &[QualityValue]) -> Self {
+        Self(codings.to_vec())
+    }
+
+    /// The codings, in the order they were sent.
+    pub fn codings(&self) -> &[QualityValue] {
+        &self.0
+    }
+
+    /// The weight of a content coding.
+    pub fn quality(&self, coding: &str) -> u16 {
+        let default = if coding.eq_ignore_ascii_case("identity") {
+            DEFAULT_QUALITY
+        } else {
+            0
+        };
+        exact_or_wildcard(&self.0, coding).unwrap_or(default)
+    }
+
+    /// The acceptable coding with the highest weight, the first one on ties.
+    pub fn negotiate<'a>(&self, available: &[&'a str]) -> Option<&'a str> {
+        best(available, |coding| self.quality(coding))
+    }
+}
+
+impl TypedHeader for AcceptEncoding {
+    fn name() -> &'static str {
+        H_ACCEPT_ENCODING
+    }
+
+    /// An empty `Accept-Encoding` is valid, it accepts `identity` only.
+    fn decode(values: &[&str]) -> Result<Self, HttpParseError> {
+        decode_tokens::<Self>(values).map(Self)
+    }
+
+    fn encode(&self) -> Vec<String> {
+        encode_weighted(&self.0)
+    }
+}
+
+/// `Accept-Charset`, the charsets the client accepts in a textual response.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AcceptCharset(pub(crate) Vec<QualityValue>);
+
+impl AcceptCharset {
+    /// Create the header from its charsets.
+    pub fn new(charsets: &[QualityValue]) -> Self {
+        Self(charsets.to_vec())
+    }
+
+    /// The charsets, in the order they were sent.
+    pub fn charsets(&self) -> &[QualityValue] {
+        &self.0
+    }
+
+    /// The weight of a charset.
+    pub fn quality(&self, charset: &str) -> u16 {
+        exact_or_wildcard(&self.0, charset).unwrap_or(0)
+    }
+
+    /// The acceptable charset with the highest weight, the first one on ties.
+    pub fn negotiate<'a>(&self, available: &[&'a str]) -> Option<&'a str> {
+        best(available, |charset| self.quality(charset))
+    }
+}
+
+impl TypedHeader for AcceptCharset {
+    fn name() -> &'static str {
+        H_ACCEPT_CHARSET
+    }
+
+    fn decode(values: &[&str]) -> Result<Self, HttpParseError> {
+        decode_tokens::<Self>(values).map(Self)
+    }
+
+    fn encode(&self) -> Vec<String> {
+        encode_weighted(&self.0)
+    }
+}
+
+impl HttpRequest {
+    /// Pick the media type to respond with among `available`, the server's preference first.
+    ///
+    /// Without an `Accept` header any media type is acceptable and the first one is picked.
+    ///
+    /// # Errors:
+    /// `StatusCode::NOT_ACCEPTABLE` when none is acceptable, `StatusCode::BAD_REQUEST` when
+    /// the header is not formatted properly.
+    ///
+    /// # Example:
+    /// ```rust
+    /// use http_parse::{HttpRequestBuilder, StatusCode};
+    ///
+    /// let request = HttpRequestBuilder::new()
+    ///     .header("Accept", "application/json, text/*;q=0.5")
+    ///     .header("Accept-Language", "fr-CH, fr;q=0.9, en;q=0.8")
+    ///     .build();
+    /// assert_eq!(request.negotiate_media_type(&["text/html", "application/json"]), Ok("application/json"));
+    /// assert_eq!(request.negotiate_language(&["en-US", "fr-FR"]), Ok("fr-FR"));
+    /// assert_eq!(request.negotiate_media_type(&["image/png"]), Err(StatusCode::NOT_ACCEPTABLE));
+    /// ```
+    pub fn negotiate_media_type<'a>(&self, available: &[&'a str]) -> Result<&'a str, StatusCode> {
+        negotiate(self, available, Accept::negotiate)
+    }
+
+    /// Pick the language to respond with among `available`, the server's preference first.
+    ///
+    /// Without an `Accept-Language` header any language is acceptable.
+    ///
+    /// # Errors:
+    /// `StatusCode::NOT_ACCEPTABLE` when none is acceptable, `StatusCode::BAD_REQUEST` when
+    /// the header is not formatted properly.
+    pub fn negotiate_language<'a>(&self, available: &[&'a str]) -> Result<&'a str, StatusCode> {
+        negotiate(self, available, AcceptLanguage::negotiate)
+    }
+
+    /// Pick the content coding to respond with among `available`, the server's preference
+    /// first. Include `identity` to fall back on sending the content as is.
+    ///
+    /// Without an `Accept-Encoding` header any coding is acceptable.
+    ///
+    /// # Errors:
+    /// `StatusCode::NOT_ACCEPTABLE` when none is acceptable, `StatusCode::BAD_REQUEST` when
+    /// the header is not formatted properly.
+    pub fn negotiate_encoding<'a>(&self, available: &[&'a str]) -> Result<&'a str, StatusCode> {
+        negotiate(self, available, AcceptEncoding::negotiate)
+    }
+
+    /// Pick the charset to respond with among `available`, the server's preference first.
+    ///
+    /// Without an `Accept-Charset` header any charset is acceptable.
+    ///
+    /// # Errors:
+    /// `StatusCode::NOT_ACCEPTABLE` when none is acceptable, `StatusCode::BAD_REQUEST` when
+    /// the header is not formatted properly.
+    pub fn negotiate_charset<'a>(&self, available: &[&'a str]) -> Result<&'a str, StatusCode> {
+        negotiate(self, available, AcceptCharset::negotiate)
+    }
+}
+
+fn negotiate<'a, T: TypedHeader>(
+    request: &HttpRequest,
+    available: &[&'a str],
+    pick: fn(&T, &[&'a str]) -> Option<&'a str>,
+) -> Result<&'a str, StatusCode> {
+    let picked = match request.typed_header::<T>() {
+        Ok(Some(header)) => pick(&header, available),
+        Ok(None) => available.first().copied(),
+        Err(_) => return Err(StatusCode::BAD_REQUEST),
+    };
+    picked.ok_or(StatusCode::NOT_ACCEPTABLE)
+}
+
+/// The option with the highest weight above `0`, the first one on ties.
+fn best<'a>(available: &[&'a str], quality: impl Fn(&str) -> u16) -> Option<&'a str> {
+    let mut best = None;
+    let mut best_quality = 0;
+    for option in available {
+        let quality = quality(option);
+        if quality > best_quality {
+            best = Some(*option);
+            best_quality = quality;
+        }
+    }
+    best
+}
+
+/// The weight of the value matching `value` exactly, or else of `*`.
+fn exact_or_wildcard(values: &[QualityValue], value: &str) -> Option<u16> {
+    let find = |wanted: &str| {
+        values
+            .iter()
+            .find(|item| item.value.eq_ignore_ascii_case(wanted))
+            .map(|item| item.quality)
+    };
+    find(value).or_else(|| find("*"))
+}
+
+fn decode_weighted<T: TypedHeader>(values: &[&str]) -> Result<Vec<QualityValue>, HttpParseError> {
+    list(values)
+        .into_iter()
+        .map(QualityValue::parse)
+        .collect::<Option<Vec<QualityValue>>>()
+        .ok_or_else(|| invalid::<T>(values))
+}
+
+fn decode_tokens<T: TypedHeader>(values: &[&str]) -> Result<Vec<QualityValue>, HttpParseError> {
+    let items = decode_weighted::<T>(values)?;
+    if items.iter().any(|item| !is_token(item.value.as_bytes())) {
+        return Err(invalid::<T>(values));
+    }
+    Ok(items)
+}
+
+fn encode_weighted(values: &[QualityValue]) -> Vec<String> {
+    let values: Vec<String> = values.iter().map(QualityValue::to_string).collect();
+    vec![values.join(", ")]
+}
+
+/// Parse `;name[=value]` parameters up to the end of `text`.
+fn parse_params(text: &str) -> Option<Vec<(String, Option<String>)>> {
+    let mut params = Vec::new();
+    let mut text = text.as_bytes().trim_ascii();
+    while !text.is_empty() {
+        params.push(next_parameter(&mut text)?);
+        text = text.trim_ascii_start();
+    }
+    Some(params)
+}
+
+/// Parse a weight, `0` to `1` with up to three decimals, into thousandths.
+fn parse_quality(text: &str) -> Option<u16> {
+    let (whole, decimals) = text.split_once('.').unwrap_or((text, ""));
+    let valid = matches!(whole, "0" | "1")
+        && decimals.len() <= 3
+        && decimals.bytes().all(|byte| byte.is_ascii_digit());
+    if !valid {
+        return None;
+    }
+    let thousandths = format!("{:0<3}", decimals).parse::<u16>().ok()?;
+    let quality = if whole == "1" {
+        1000 + thousandths
+    } else {
+        thousandths
+    };
+    (quality <= DEFAULT_QUALITY).then_some(quality)
+}
+
+/// The `;q=` parameter for a weight, left out for the default weight.
+fn weight(quality: u16) -> String {
+    if quality == DEFAULT_QUALITY {
+        return String::new();
+    }
+    let decimals = format!("{:03}", quality);
+    let decimals = decimals.trim_end_matches('0');
+    if decimals.is_empty() {
+        ";q=0".to_string()
+    } else {
+        format!(";q=0.{}", decimals)
+    }
+}
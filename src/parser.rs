@@ -1,11 +1,9 @@
-use std::{
-    io::{BufRead, BufReader, ErrorKind, Read},
-    str::ParseBoolError,
-};
+use std::io::{BufRead, BufReader, ErrorKind, Read};
 
 use crate::{
-    types::HttpParseError, HttpHeader, HttpMethod, HttpRequest, HttpResponse, HttpVersion,
-    H_CONTENT_LENGTH, H_TRANSFER_ENCODING,
+    types::{HttpParseError, ParseErrorKind},
+    BodyReader, HttpMethod, HttpRequest, HttpResponse, ParseStatus, ParserConfig, RequestParser,
+    ResponseParser,
 };
 
 /// A Parser for HTTP content.
@@ -14,6 +12,13 @@ use crate::{
 ///
 /// The parser supports parsing Responses from any structure that implements the `std::io::Read`` trait.
 ///
+/// A parser can be used for every message on a connection, bytes read past the end of one
+/// message are kept for the next one. See [`HttpParser::requests`] and [`HttpParser::responses`].
+///
+/// The reader may be non-blocking. When it has no bytes ready the parser returns an error for
+/// which [`HttpParseError::is_would_block`] holds, and keeps what it parsed of the message so far.
+/// Calling the same method again once the reader is ready continues where it stopped.
+///
 /// # Example:
 /// ```no_run
 ///   use std::io::Cursor;
@@ -28,16 +33,33 @@ use crate::{
 ///
 pub struct HttpParser<'a, R> {
     reader: BufReader<&'a mut R>,
+    // kept between calls, a message cut short by `WouldBlock` continues where it stopped
+    request_parser: RequestParser,
+    response_parser: ResponseParser,
 }
 
 impl<'a, R: Read> HttpParser<'a, R> {
     /// Create a HTTP Parser from a reader that implements `std::io::Read`.
     pub fn from_reader(reader: &'a mut R) -> Self {
+        Self::with_config(reader, ParserConfig::default())
+    }
+
+    /// Create a HTTP Parser from a reader that applies the limits in `config`.
+    pub fn with_config(reader: &'a mut R, config: ParserConfig) -> Self {
         Self {
             reader: BufReader::new(reader),
+            request_parser: RequestParser::with_config(config),
+            response_parser: ResponseParser::with_config(config),
         }
     }
 
+    /// Set the method of the request the next responses answer.
+    ///
+    /// See [`ResponseParser::set_request_method`].
+    pub fn set_request_method(&mut self, method: HttpMethod) {
+        self.response_parser.set_request_method(method);
+    }
+
     /// Parse a `HttpResponse` by reading bytes in this reader/stream.
     ///
     /// The Response parsed through this methods includes:
@@ -46,6 +68,9 @@ impl<'a, R: Read> HttpParser<'a, R> {
     /// `StatusCode`
     /// `body data` and more.
     ///
+    /// A stream that ends before a response starts produces `ParseErrorKind::UnexpectedEof`,
+    /// use [`HttpParser::next_response`] to tell it apart from a truncated response.
+    ///
     /// # Errors:
     /// When reading from the Reader produces any error or the data provided is not formatted properly.
     pub fn response(&mut self) -> Result<HttpResponse, HttpParseError> {
@@ -66,47 +91,63 @@ impl<'a, R: Read> HttpParser<'a, R> {
         self.parse_response(false)
     }
 
-    fn parse_response(&mut self, include_data: bool) -> Result<HttpResponse, HttpParseError> {
-        let mut buffer = Vec::with_capacity(100);
-        let _ = self.reader.read_until(b' ', &mut buffer)?;
-        let version = Self::parse_version(&buffer)?;
-        buffer.clear();
+    /// Parse the head of a `HttpResponse` and return a reader for its body.
+    ///
+    /// The body is decoded while it is read rather than collected into the response,
+    /// see [`BodyReader`] for details.
+    ///
+    /// # Errors:
+    /// When reading from the Reader produces any error or the data provided is not formatted properly.
+    pub fn response_streaming(
+        &mut self,
+    ) -> Result<(HttpResponse, BodyReader<'_, BufReader<&'a mut R>>), HttpParseError> {
+        let (response, decoder) = Self::drive(
+            &mut self.reader,
+            &mut self.response_parser,
+            ResponseParser::feed_head,
+            ResponseParser::finish_head,
+        )?
+        .ok_or(ParseErrorKind::UnexpectedEof)?;
+        Ok((response, BodyReader::new(&mut self.reader, decoder)))
+    }
 
-        let _ = self.reader.read_until(b' ', &mut buffer)?;
-        let status_code = Self::parse_status_code(&buffer)?;
-        buffer.clear();
+    /// Parse the next `HttpResponse` on this reader/stream, head and body.
+    ///
+    /// Returns `None` when the stream ends cleanly before another response starts.
+    ///
+    /// # Errors:
+    /// When reading from the Reader produces any error, the data provided is not formatted
+    /// properly or the stream ends in the middle of a response.
+    pub fn next_response(&mut self) -> Result<Option<HttpResponse>, HttpParseError> {
+        Self::drive(
+            &mut self.reader,
+            &mut self.response_parser,
+            ResponseParser::feed,
+            ResponseParser::finish,
+        )
+    }
 
-        let _ = self.reader.read_until(b'\n', &mut buffer)?;
-        let message = String::from_utf8_lossy(&buffer).trim().to_owned();
-        buffer.clear();
+    /// An iterator over the responses on this reader/stream, see [`Responses`].
+    pub fn responses(&mut self) -> Responses<'_, 'a, R> {
+        Responses {
+            parser: self,
+            done: false,
+        }
+    }
 
-        // let headers = self.parse_headers();
-        let mut headers = Vec::new();
-        self.parse_headers_two(&mut headers)?;
-        let body = Vec::new();
-        let chunks = Vec::new();
-        let mut response = HttpResponse {
-            version,
-            status_code,
-            status_msg: message,
-            headers,
-            body,
-            chunks,
-            chunked: false,
-        };
+    fn parse_response(&mut self, include_data: bool) -> Result<HttpResponse, HttpParseError> {
         if include_data {
-            let encoding_header = response.header(H_TRANSFER_ENCODING).cloned();
-            let content_header = response.header(H_CONTENT_LENGTH).cloned();
-
-            self.extract_body_data(
-                encoding_header,
-                content_header,
-                &mut response.chunks,
-                &mut response.body,
-            )?;
-
-            response.chunked = !response.chunks.is_empty();
+            return self
+                .next_response()?
+                .ok_or_else(|| ParseErrorKind::UnexpectedEof.into());
         }
+        let (response, _) = Self::drive(
+            &mut self.reader,
+            &mut self.response_parser,
+            ResponseParser::feed_head,
+            ResponseParser::finish_head,
+        )?
+        .ok_or(ParseErrorKind::UnexpectedEof)?;
         Ok(response)
     }
 
@@ -118,6 +159,9 @@ impl<'a, R: Read> HttpParser<'a, R> {
     /// `Requested URL`
     /// `body data` and more.
     ///
+    /// A stream that ends before a request starts produces `ParseErrorKind::UnexpectedEof`,
+    /// use [`HttpParser::next_request`] to tell it apart from a truncated request.
+    ///
     /// # Errors:
     /// When reading from the Reader produces any error or the data provided is not formatted properly.
     pub fn request(&mut self) -> Result<HttpRequest, HttpParseError> {
@@ -137,244 +181,156 @@ impl<'a, R: Read> HttpParser<'a, R> {
     pub fn request_head_only(&mut self) -> Result<HttpRequest, HttpParseError> {
         self.parse_request(false)
     }
-    pub fn parse_request(&mut self, include_data: bool) -> Result<HttpRequest, HttpParseError> {
-        let mut buffer = Vec::with_capacity(100);
-        let _ = self.reader.read_until(b' ', &mut buffer)?;
-        let method = Self::parse_method(&buffer)?;
-        buffer.clear();
-
-        let _ = self.reader.read_until(b' ', &mut buffer)?;
-
-        let url = String::from_utf8_lossy(&buffer).trim().to_owned();
-        buffer.clear();
-
-        let _ = self.reader.read_until(b'\n', &mut buffer)?;
-
-        let version = Self::parse_version(&buffer)?;
-        // let headers = self.parse_headers();
-        let mut headers = Vec::new();
-        self.parse_headers_two(&mut headers)?;
-
-        let body = Vec::new();
-        let chunks = Vec::new();
-
-        let mut request = HttpRequest {
-            method,
-            url,
-            version,
-            headers,
-            body,
-            chunked: false,
-            chunks,
-        };
-        if include_data {
-            let encoding_header = request.header(H_TRANSFER_ENCODING).cloned();
-            let content_header = request.header(H_CONTENT_LENGTH).cloned();
-
-            self.extract_body_data(
-                encoding_header,
-                content_header,
-                &mut request.chunks,
-                &mut request.body,
-            )?;
-
-            request.chunked = !request.chunks.is_empty();
-        }
-        Ok(request)
-    }
 
-    fn extract_body_data(
+    /// Parse the head of a `HttpRequest` and return a reader for its body.
+    ///
+    /// The body is decoded while it is read rather than collected into the request,
+    /// see [`BodyReader`] for details.
+    ///
+    /// # Errors:
+    /// When reading from the Reader produces any error or the data provided is not formatted properly.
+    pub fn request_streaming(
         &mut self,
-        encoding_header: Option<HttpHeader>,
-        content_header: Option<HttpHeader>,
-        chunks: &mut Vec<(usize, usize)>,
-        body: &mut Vec<u8>,
-    ) -> Result<(), HttpParseError> {
-        let mut chunked = false;
-        encoding_header.inspect(|h| {
-            if !h.value.contains("identity") {
-                chunked = true;
-            }
-        });
-        if chunked {
-            self.read_chunked_body(body, chunks)?;
-        } else if let Some(header) = content_header {
-            match header.value::<usize>() {
-                Ok(length) => {
-                    body.resize_with(length, || 0);
-                    self.reader.read_exact(body)?;
-                }
-                Err(_e) => Err(HttpParseError::Header(header.to_string()))?,
-            };
-        }
-
-        Ok(())
+    ) -> Result<(HttpRequest, BodyReader<'_, BufReader<&'a mut R>>), HttpParseError> {
+        let (request, decoder) = Self::drive(
+            &mut self.reader,
+            &mut self.request_parser,
+            RequestParser::feed_head,
+            RequestParser::finish_head,
+        )?
+        .ok_or(ParseErrorKind::UnexpectedEof)?;
+        Ok((request, BodyReader::new(&mut self.reader, decoder)))
     }
 
-    fn read_chunked_body(
-        &mut self,
-        body: &mut Vec<u8>,
-        chunks: &mut Vec<(usize, usize)>,
-    ) -> Result<(), std::io::Error> {
-        let mut buff = Vec::with_capacity(16);
-        while let Ok(n) = self.reader.read_until(b'\n', &mut buff) {
-            // done reading
-            if n == 0 {
-                buff.clear();
-                // done reading
-                break;
-            }
-
-            // parse hex byte numbers contained in chunk
-            let digits_str = String::from_utf8_lossy(buff.trim_ascii()).to_string();
-            match usize::from_str_radix(&digits_str, 16) {
-                Ok(chunk_size) => {
-                    if chunk_size == 0 {
-                        let _ = self.reader.read_until(b'\n', &mut buff);
-                        break;
-                    } else {
-                        let mut chunk_buff = vec![0; chunk_size];
-                        self.reader.read_exact(&mut chunk_buff)?;
-
-                        chunks.push((body.len(), body.len() + chunk_buff.len()));
-                        body.extend_from_slice(&chunk_buff);
-                    }
-                }
-                Err(_) => break, // invalid reading of body for now just exit loop
-            }
-            // ignore new line after chunk
-            let _ = self.reader.read_until(b'\n', &mut buff);
-            buff.clear();
-        }
-        // last chunk 0 data
-        if !chunks.is_empty() {
-            chunks.push((0, 0));
-        }
-        Ok(())
+    /// Parse the next `HttpRequest` on this reader/stream, head and body.
+    ///
+    /// Returns `None` when the stream ends cleanly before another request starts.
+    ///
+    /// # Errors:
+    /// When reading from the Reader produces any error, the data provided is not formatted
+    /// properly or the stream ends in the middle of a request.
+    pub fn next_request(&mut self) -> Result<Option<HttpRequest>, HttpParseError> {
+        Self::drive(
+            &mut self.reader,
+            &mut self.request_parser,
+            RequestParser::feed,
+            RequestParser::finish,
+        )
     }
 
-    fn parse_method(method: &[u8]) -> Result<HttpMethod, HttpParseError> {
-        match method.trim_ascii() {
-            b"GET" => Ok(HttpMethod::Get),
-            b"POST" => Ok(HttpMethod::Post),
-            b"PUT" => Ok(HttpMethod::Put),
-            b"HEAD" => Ok(HttpMethod::Head),
-            b"OPTIONS" => Ok(HttpMethod::Options),
-            b"DELETE" => Ok(HttpMethod::Delete),
-            b"TRACE" => Ok(HttpMethod::Trace),
-            _ => Err(HttpParseError::Method(
-                String::from_utf8_lossy(method).to_string(),
-            )),
+    /// An iterator over the requests on this reader/stream, see [`Requests`].
+    pub fn requests(&mut self) -> Requests<'_, 'a, R> {
+        Requests {
+            parser: self,
+            done: false,
         }
     }
 
-    fn parse_version(version: &[u8]) -> Result<HttpVersion, HttpParseError> {
-        match version.trim_ascii() {
-            b"HTTP/1.0" => Ok(HttpVersion::Http10),
-            b"HTTP/1.1" => Ok(HttpVersion::Http11),
-            b"HTTP/2" => Ok(HttpVersion::Http2),
-            b"HTTP/3" => Ok(HttpVersion::Http3),
-            _ => Err(HttpParseError::Version(
-                String::from_utf8_lossy(version.trim_ascii()).to_string(),
-            )),
-        }
+    /// The bytes read from the stream that were not used by a message yet.
+    ///
+    /// These are the start of the next message when the peer pipelines its messages.
+    pub fn buffered(&self) -> &[u8] {
+        self.reader.buffer()
     }
 
-    fn parse_status_code(status_code: &[u8]) -> Result<usize, HttpParseError> {
-        let code_string = String::from_utf8_lossy(status_code);
-        match code_string.trim().parse::<usize>() {
-            Ok(value) => Ok(value),
-            _ => Err(HttpParseError::StatusCode(
-                String::from_utf8_lossy(status_code).to_string(),
-            )),
+    pub fn parse_request(&mut self, include_data: bool) -> Result<HttpRequest, HttpParseError> {
+        if include_data {
+            return self
+                .next_request()?
+                .ok_or_else(|| ParseErrorKind::UnexpectedEof.into());
         }
+        let (request, _) = Self::drive(
+            &mut self.reader,
+            &mut self.request_parser,
+            RequestParser::feed_head,
+            RequestParser::finish_head,
+        )?
+        .ok_or(ParseErrorKind::UnexpectedEof)?;
+        Ok(request)
     }
 
-    fn parse_headers(&mut self) -> Vec<HttpHeader> {
-        let mut headers = Vec::with_capacity(100);
-        let mut line = String::new();
-        while self.reader.read_line(&mut line).is_ok() {
-            // empty line between request and body, we are done
-            if line.trim().is_empty() {
+    /// Feed the bytes available in the reader to a push parser until it produces a message.
+    ///
+    /// Returns `None` when the stream ends before a message starts.
+    fn drive<P, T>(
+        reader: &mut BufReader<&'a mut R>,
+        parser: &mut P,
+        feed: fn(&mut P, &[u8]) -> Result<ParseStatus<T>, HttpParseError>,
+        finish: fn(&mut P) -> Result<Option<T>, HttpParseError>,
+    ) -> Result<Option<T>, HttpParseError> {
+        loop {
+            let available = match reader.fill_buf() {
+                Ok(available) => available,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            };
+            if available.is_empty() {
                 break;
-            } else {
-                if let Some(index) = line.find(':') {
-                    if index < line.len() {
-                        let name = line[0..index].to_string().trim().to_string();
-                        let value = line[index + 1..line.len()]
-                            .trim_ascii_start()
-                            .replace(['\r', '\n'], "")
-                            .to_string();
-                        headers.push(HttpHeader { name, value });
-                    }
+            }
+            let length = available.len();
+            match feed(parser, available)? {
+                ParseStatus::Partial => reader.consume(length),
+                ParseStatus::Complete(message, used) => {
+                    reader.consume(used);
+                    return Ok(Some(message));
                 }
-                line.clear();
             }
         }
-        headers
+        finish(parser)
     }
+}
 
-    fn parse_headers_two(&mut self, headers: &mut Vec<HttpHeader>) -> Result<(), HttpParseError> {
-        while !self.is_line_end()? {
-            let mut name = Vec::new();
-            let mut value = Vec::new();
-            let name_len = self.reader.read_until(b':', &mut name)?;
-            self.skip_matching(|byte| (byte as char).is_whitespace())?;
-            let value_len = self.reader.read_until(b'\n', &mut value)?;
-            headers.push(HttpHeader::new(
-                String::from_utf8_lossy(&name[0..name_len - 1]),
-                String::from_utf8_lossy(&value[0..value_len - 2]),
-            ));
-        }
-        self.skip_next_line();
-        Ok(())
-    }
+/// An iterator over the requests read from one connection by a [`HttpParser`].
+///
+/// Ends when the stream ends between two requests. A stream that ends in the middle of a
+/// request yields `ParseErrorKind::UnexpectedEof`, the iterator ends after the first error.
+///
+/// # Example:
+/// ```rust
+/// use std::io::Cursor;
+/// use http_parse::HttpParser;
+///
+/// let text = "GET /a HTTP/1.1\r\nHost: a\r\n\r\nGET /b HTTP/1.1\r\nHost: a\r\n\r\n";
+/// let mut reader = Cursor::new(text.as_bytes());
+/// let mut parser = HttpParser::from_reader(&mut reader);
+/// let count = parser.requests().map(|request| request.unwrap()).count();
+/// assert_eq!(count, 2);
+/// ```
+pub struct Requests<'p, 'a, R> {
+    parser: &'p mut HttpParser<'a, R>,
+    done: bool,
+}
 
-    fn skip_matching<F>(&mut self, f: F) -> std::io::Result<usize>
-    where
-        F: Fn(u8) -> bool,
-    {
-        let mut read = 0;
-        loop {
-            let (done, used) = {
-                let available = match self.reader.fill_buf() {
-                    Ok(n) => n,
-                    Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
-                    Err(e) => return Err(e),
-                };
-                match available.iter().position(|byte| !f(*byte)) {
-                    Some(index) => (true, index),
-                    None => (false, 0),
-                }
-            };
-            self.reader.consume(used);
-            read += used;
-            if done || used == 0 {
-                return Ok(read);
-            }
-        }
-    }
+impl<R: Read> Iterator for Requests<'_, '_, R> {
+    type Item = Result<HttpRequest, HttpParseError>;
 
-    fn is_line_end(&mut self) -> std::io::Result<bool> {
-        if self.reader.buffer().len() >= 2 {
-            Ok(self.reader.buffer().starts_with(b"\r\n"))
-        } else {
-            loop {
-                match self.reader.fill_buf() {
-                    Ok(available) => {
-                        return Ok(available.is_empty() || available.starts_with(b"\r\n"))
-                    }
-                    Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
-                    Err(e) => return Err(e),
-                };
-            }
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
         }
+        let next = self.parser.next_request().transpose();
+        self.done = !matches!(next, Some(Ok(_)));
+        next
     }
+}
+
+/// An iterator over the responses read from one connection by a [`HttpParser`].
+///
+/// See [`Requests`] for details.
+pub struct Responses<'p, 'a, R> {
+    parser: &'p mut HttpParser<'a, R>,
+    done: bool,
+}
+
+impl<R: Read> Iterator for Responses<'_, '_, R> {
+    type Item = Result<HttpResponse, HttpParseError>;
 
-    fn skip_next_line(&mut self) -> std::io::Result<()> {
-        if self.is_line_end()? {
-            self.reader.consume(2);
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
         }
-        Ok(())
+        let next = self.parser.next_response().transpose();
+        self.done = !matches!(next, Some(Ok(_)));
+        next
     }
 }
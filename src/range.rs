@@ -0,0 +1,326 @@
+use crate::{
+    typed_headers::{invalid, list, parse_number, single},
+    types::{HttpParseError, ParseErrorKind},
+    HttpResponse, TypedHeader, H_CONTENT_RANGE, H_RANGE, S_PARTIAL_CONTENT,
+};
+
+/// One of the byte ranges of a `Range` header.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ByteRange {
+    /// `first-last`, both included.
+    FromTo(u64, u64),
+    /// `first-`, from `first` to the end.
+    From(u64),
+    /// `-length`, the last `length` bytes.
+    Last(u64),
+}
+
+impl ByteRange {
+    /// The bytes this range covers in a representation of `length` bytes, as a
+    /// `Content-Range` to send them with.
+    ///
+    /// Ranges past the last byte are cut to it. Returns `None` when the range is not
+    /// satisfiable, it starts past the last byte or asks for no byte at all.
+    pub fn resolve(&self, length: u64) -> Option<ContentRange> {
+        let (first, last) = match *self {
+            ByteRange::FromTo(first, last) => (first, last.min(length.checked_sub(1)?)),
+            ByteRange::From(first) => (first, length.checked_sub(1)?),
+            ByteRange::Last(0) => return None,
+            ByteRange::Last(suffix) => (length.saturating_sub(suffix), length.checked_sub(1)?),
+        };
+        (first <= last).then(|| ContentRange::bytes(first, last, Some(length)))
+    }
+
+    fn parse(text: &str) -> Option<Self> {
+        let (first, last) = text.split_once('-')?;
+        match (first.trim(), last.trim()) {
+            ("", suffix) => Some(ByteRange::Last(parse_number(suffix)?)),
+            (first, "") => Some(ByteRange::From(parse_number(first)?)),
+            (first, last) => {
+                let (first, last) = (parse_number(first)?, parse_number(last)?);
+                (first <= last).then_some(ByteRange::FromTo(first, last))
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ByteRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ByteRange::FromTo(first, last) => write!(f, "{}-{}", first, last),
+            ByteRange::From(first) => write!(f, "{}-", first),
+            ByteRange::Last(suffix) => write!(f, "-{}", suffix),
+        }
+    }
+}
+
+/// `Range`, the parts of a representation a request asks for.
+///
+/// Written `bytes=` followed by comma separated ranges, `first-last`, `first-` for
+/// everything from `first` on or `-length` for the last `length` bytes.
+///
+/// # Example:
+/// ```rust
+/// use http_parse::{ByteRange, ContentRange, Range, TypedHeader};
+///
+/// let range = Range::decode(&["bytes=0-99, 1000-, -50"]).unwrap();
+/// assert_eq!(range.ranges()[2], ByteRange::Last(50));
+///
+/// let parts = range.resolve(500).unwrap();
+/// assert_eq!(parts[0], ContentRange::bytes(0, 99, Some(500)));
+/// assert_eq!(parts[1], ContentRange::bytes(450, 499, Some(500)));
+/// assert_eq!(Range::bytes(600, None).resolve(500), None);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Range(pub(crate) Vec<ByteRange>);
+
+impl Range {
+    /// Ask for the bytes from `first` to `last` included, or to the end without `last`.
+    pub fn bytes(first: u64, last: Option<u64>) -> Self {
+        match last {
+            Some(last) => Self(vec![ByteRange::FromTo(first, last)]),
+            None => Self(vec![ByteRange::From(first)]),
+        }
+    }
+
+    /// Ask for the last `length` bytes.
+    pub fn suffix(length: u64) -> Self {
+        Self(vec![ByteRange::Last(length)])
+    }
+
+    /// Ask for several ranges at once, the response then holds a part for each of them.
+    pub fn new(ranges: &[ByteRange]) -> Self {
+        Self(ranges.to_vec())
+    }
+
+    /// The ranges asked for, in order.
+    pub fn ranges(&self) -> &[ByteRange] {
+        &self.0
+    }
+
+    /// The satisfiable ranges in a representation of `length` bytes, in order.
+    ///
+    /// Returns `None` when no range is satisfiable, a server answers with
+    /// `416 Range Not Satisfiable` and [`ContentRange::unsatisfied`].
+    pub fn resolve(&self, length: u64) -> Option<Vec<ContentRange>> {
+        let ranges: Vec<ContentRange> = self
+            .0
+            .iter()
+            .filter_map(|range| range.resolve(length))
+            .collect();
+        (!ranges.is_empty()).then_some(ranges)
+    }
+
+    /// Whether a part sent back answers the ranges asked for.
+    ///
+    /// The part has to start and end within ranges asked for. Servers may coalesce ranges
+    /// that overlap or are close to each other, so a part that spans several ranges along
+    /// with the gaps between them is accepted, but not one that lies in a gap.
+    pub fn is_satisfied_by(&self, content_range: &ContentRange) -> bool {
+        let Some((first, last)) = content_range.range else {
+            return false;
+        };
+        // without the complete length ranges can only be cut at the last byte sent
+        let length = content_range
+            .complete_length
+            .unwrap_or(last.saturating_add(1));
+        let resolved: Vec<(u64, u64)> = self
+            .0
+            .iter()
+            .filter_map(|range| match (range, content_range.complete_length) {
+                (ByteRange::Last(_), None) => Some((0, last)),
+                (ByteRange::FromTo(start, end), None) => Some((*start, *end)),
+                (range, _) => range.resolve(length)?.range,
+            })
+            .collect();
+        let requested = |byte: u64| {
+            resolved
+                .iter()
+                .any(|(start, end)| (*start..=*end).contains(&byte))
+        };
+        requested(first) && requested(last)
+    }
+}
+
+impl TypedHeader for Range {
+    fn name() -> &'static str {
+        H_RANGE
+    }
+
+    fn decode(values: &[&str]) -> Result<Self, HttpParseError> {
+        let value = single::<Self>(values)?;
+        let parse = || {
+            let (unit, ranges) = value.split_once('=')?;
+            if !unit.trim().eq_ignore_ascii_case("bytes") {
+                return None;
+            }
+            let ranges = list(&[ranges])
+                .into_iter()
+                .map(ByteRange::parse)
+                .collect::<Option<Vec<ByteRange>>>()?;
+            (!ranges.is_empty()).then_some(Self(ranges))
+        };
+        parse().ok_or_else(|| invalid::<Self>(values))
+    }
+
+    fn encode(&self) -> Vec<String> {
+        let ranges: Vec<String> = self.0.iter().map(ByteRange::to_string).collect();
+        vec![format!("bytes={}", ranges.join(", "))]
+    }
+}
+
+/// `Content-Range`, the part of a representation a `206 Partial Content` response carries.
+///
+/// Written `bytes first-last/complete`, where the complete length can be `*` when unknown.
+/// A `416 Range Not Satisfiable` response sends `bytes */complete` instead, with no range.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContentRange {
+    pub(crate) range: Option<(u64, u64)>,
+    pub(crate) complete_length: Option<u64>,
+}
+
+impl ContentRange {
+    /// Create a byte range from `first` to `last`, both included.
+    pub fn bytes(first: u64, last: u64, complete_length: Option<u64>) -> Self {
+        Self {
+            range: Some((first, last)),
+            complete_length,
+        }
+    }
+
+    /// The `bytes */complete` form, for a range that cannot be satisfied.
+    pub fn unsatisfied(complete_length: u64) -> Self {
+        Self {
+            range: None,
+            complete_length: Some(complete_length),
+        }
+    }
+
+    /// The first and last bytes in the range, both included, `None` when unsatisfied.
+    pub fn range(&self) -> Option<(u64, u64)> {
+        self.range
+    }
+
+    /// The length of the whole representation, when known.
+    pub fn complete_length(&self) -> Option<u64> {
+        self.complete_length
+    }
+
+    /// The number of bytes in the range.
+    ///
+    /// An inverted range holds no byte, a range of every `u64` offset is cut to `u64::MAX`.
+    pub fn length(&self) -> u64 {
+        self.range.map_or(0, |(first, last)| {
+            last.checked_sub(first)
+                .map_or(0, |distance| distance.saturating_add(1))
+        })
+    }
+
+    /// The number of bytes in the range, `None` when it is inverted or does not fit.
+    fn checked_length(&self) -> Option<u64> {
+        let (first, last) = self.range?;
+        last.checked_sub(first)?.checked_add(1)
+    }
+}
+
+impl TypedHeader for ContentRange {
+    fn name() -> &'static str {
+        H_CONTENT_RANGE
+    }
+
+    fn decode(values: &[&str]) -> Result<Self, HttpParseError> {
+        let value = single::<Self>(values)?;
+        let parse = || {
+            let (unit, range) = value.split_once(' ')?;
+            if !unit.eq_ignore_ascii_case("bytes") {
+                return None;
+            }
+            let (range, complete) = range.trim().split_once('/')?;
+            if range == "*" {
+                return Some(Self::unsatisfied(parse_number(complete)?));
+            }
+            let (first, last) = range.split_once('-')?;
+            let complete_length = match complete {
+                "*" => None,
+                complete => Some(parse_number(complete)?),
+            };
+            let (first, last) = (parse_number(first)?, parse_number(last)?);
+            // the length of the range has to fit in a `u64` as well
+            let fits = complete_length.is_none_or(|length| last < length)
+                && (first, last) != (0, u64::MAX);
+            (first <= last && fits).then(|| Self::bytes(first, last, complete_length))
+        };
+        parse().ok_or_else(|| invalid::<Self>(values))
+    }
+
+    fn encode(&self) -> Vec<String> {
+        let complete = match self.complete_length {
+            Some(length) => length.to_string(),
+            None => "*".to_string(),
+        };
+        match self.range {
+            Some((first, last)) => vec![format!("bytes {}-{}/{}", first, last, complete)],
+            None => vec![format!("bytes */{}", complete)],
+        }
+    }
+}
+
+impl HttpResponse {
+    /// Check that this response is the `206 Partial Content` answer to a request for
+    /// `requested`, returning the part it carries.
+    ///
+    /// Only responses with a single part are checked, a `multipart/byteranges` response has
+    /// no `Content-Range` of its own and is rejected, its parts are split and checked with
+    /// [`HttpResponse::byte_ranges`] and [`Range::is_satisfied_by`] instead.
+    ///
+    /// # Errors:
+    /// When the status is not `206`, the `Content-Range` header is missing, not formatted
+    /// properly or outside the ranges requested, or the body length does not match it.
+    ///
+    /// # Example:
+    /// ```rust
+    /// use http_parse::{ContentRange, HttpResponseBuilder, Range, StatusCode};
+    ///
+    /// let response = HttpResponseBuilder::new()
+    ///     .status(StatusCode::PARTIAL_CONTENT)
+    ///     .typed_header(&ContentRange::bytes(0, 4, Some(10)))
+    ///     .body(b"hello")
+    ///     .build();
+    /// let part = response.validate_range(&Range::bytes(0, Some(4))).unwrap();
+    /// assert_eq!(part.range(), Some((0, 4)));
+    /// assert!(response.validate_range(&Range::suffix(5)).is_err());
+    /// ```
+    pub fn validate_range(&self, requested: &Range) -> Result<ContentRange, HttpParseError> {
+        if self.status_code != S_PARTIAL_CONTENT {
+            return Err(ParseErrorKind::StatusCode(format!(
+                "Expected {} for a range request, got {}",
+                S_PARTIAL_CONTENT, self.status_code
+            ))
+            .into());
+        }
+        let content_range = self.typed_header::<ContentRange>()?.ok_or_else(|| {
+            HttpParseError::from(ParseErrorKind::Header(format!(
+                "Missing {} in a partial response",
+                H_CONTENT_RANGE
+            )))
+        })?;
+        if !requested.is_satisfied_by(&content_range) {
+            let sent = content_range.encode().join("");
+            let requested = requested.encode().join("");
+            return Err(ParseErrorKind::Header(format!(
+                "Partial response `{}` outside the requested `{}`",
+                sent, requested
+            ))
+            .into());
+        }
+        if content_range.checked_length() != Some(self.body.len() as u64) {
+            return Err(ParseErrorKind::Body(format!(
+                "Partial response of {} bytes for a range of {}",
+                self.body.len(),
+                content_range.length()
+            ))
+            .into());
+        }
+        Ok(content_range)
+    }
+}
@@ -0,0 +1,242 @@
+use std::fmt::Display;
+
+use crate::{
+    types::{HttpParseError, ParseErrorKind},
+    HttpMethod, HttpUrl, HttpUrlBuilder,
+};
+
+/// The target of a HTTP Request, in one of the four forms of RFC 9112 section 3.2.
+///
+/// # Example:
+/// ```rust
+/// use http_parse::{HttpMethod, RequestTarget};
+///
+/// let target = RequestTarget::parse("/search?q=http%20parse&page=2", &HttpMethod::Get).unwrap();
+/// assert_eq!(target.path(), "/search");
+/// assert_eq!(
+///     target.query_pairs(),
+///     vec![
+///         ("q".to_string(), "http parse".to_string()),
+///         ("page".to_string(), "2".to_string())
+///     ]
+/// );
+/// let url = target.to_url(Some("example.com:8080")).unwrap();
+/// assert_eq!(url.port(), Some(8080));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum RequestTarget {
+    /// An absolute path with an optional query, `/index.html?lang=en`.
+    Origin { path: String, query: Option<String> },
+    /// A complete URI, used for requests sent to proxies, `http://example.com/index.html`.
+    Absolute {
+        scheme: String,
+        authority: String,
+        path: String,
+        query: Option<String>,
+    },
+    /// A host and port, used only by `CONNECT` requests, `example.com:443`.
+    Authority { host: String, port: u16 },
+    /// The whole server rather than a resource, used only by `OPTIONS` requests, `*`.
+    Asterisk,
+}
+
+impl RequestTarget {
+    /// Parse the target of a request made with `method`.
+    ///
+    /// # Errors:
+    /// When the target is not one of the forms allowed for `method`.
+    pub fn parse(target: &str, method: &HttpMethod) -> Result<Self, HttpParseError> {
+        let invalid = || HttpParseError::from(ParseErrorKind::Url(target.to_string()));
+        if *method == HttpMethod::Connect {
+            let (host, port) = split_authority(target).ok_or_else(invalid)?;
+            return Ok(RequestTarget::Authority {
+                host: host.to_string(),
+                port: port.ok_or_else(invalid)?,
+            });
+        }
+        if target == "*" {
+            return match method {
+                HttpMethod::Options => Ok(RequestTarget::Asterisk),
+                _ => Err(invalid()),
+            };
+        }
+        // a fragment is never sent, ignore one sent anyway
+        let target = target.split('#').next().unwrap_or_default();
+        if target.starts_with('/') {
+            let (path, query) = split_query(target);
+            return Ok(RequestTarget::Origin { path, query });
+        }
+        let (scheme, rest) = target.split_once("://").ok_or_else(invalid)?;
+        let valid_scheme = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c));
+        let end = rest.find(['/', '?']).unwrap_or(rest.len());
+        let authority = &rest[..end];
+        if !valid_scheme || split_authority(authority).is_none() {
+            return Err(invalid());
+        }
+        let (mut path, query) = split_query(&rest[end..]);
+        if path.is_empty() {
+            path.push('/');
+        }
+        Ok(RequestTarget::Absolute {
+            scheme: scheme.to_ascii_lowercase(),
+            authority: authority.to_string(),
+            path,
+            query,
+        })
+    }
+
+    /// The path of this target, `*` for the asterisk form and empty for the authority form.
+    pub fn path(&self) -> &str {
+        match self {
+            RequestTarget::Origin { path, .. } | RequestTarget::Absolute { path, .. } => path,
+            RequestTarget::Authority { .. } => "",
+            RequestTarget::Asterisk => "*",
+        }
+    }
+
+    /// The raw query of this target, without the `?`.
+    pub fn query(&self) -> Option<&str> {
+        match self {
+            RequestTarget::Origin { query, .. } | RequestTarget::Absolute { query, .. } => {
+                query.as_deref()
+            }
+            _ => None,
+        }
+    }
+
+    /// The `key=value` pairs of the query, percent-decoded and in order.
+    ///
+    /// A `+` is decoded as a space, as HTML forms encode it.
+    pub fn query_pairs(&self) -> Vec<(String, String)> {
+        let Some(query) = self.query() else {
+            return Vec::new();
+        };
+        query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (percent_decode(key), percent_decode(value))
+            })
+            .collect()
+    }
+
+    /// Build the URL this target refers to.
+    ///
+    /// `host` is the value of the `Host` header, it names the server of the origin and
+    /// asterisk forms. The scheme of those forms is not sent, `http` is assumed.
+    ///
+    /// # Errors:
+    /// When the host is needed but missing, or either host is invalid.
+    pub fn to_url(&self, host: Option<&str>) -> Result<HttpUrl, HttpParseError> {
+        let missing = || {
+            HttpParseError::from(ParseErrorKind::Url(format!(
+                "No host for target `{}`",
+                self
+            )))
+        };
+        let (scheme, authority) = match self {
+            RequestTarget::Absolute {
+                scheme, authority, ..
+            } => (scheme.as_str(), authority.as_str()),
+            RequestTarget::Authority { .. } => ("http", ""),
+            _ => ("http", host.ok_or_else(missing)?),
+        };
+        let mut builder = HttpUrlBuilder::new().scheme(scheme).path(self.path());
+        if let RequestTarget::Authority { host, port } = self {
+            builder = builder.host(host).port(*port);
+        } else {
+            let (host, port) = split_authority(authority).ok_or_else(missing)?;
+            builder = builder.host(host);
+            if let Some(port) = port {
+                builder = builder.port(port);
+            }
+        }
+        for (key, value) in self.query_pairs() {
+            builder = builder.param(&key, &value);
+        }
+        Ok(builder.build())
+    }
+}
+
+impl Display for RequestTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RequestTarget::Origin { path, .. } => write!(f, "{}", path)?,
+            RequestTarget::Absolute {
+                scheme,
+                authority,
+                path,
+                ..
+            } => write!(f, "{}://{}{}", scheme, authority, path)?,
+            RequestTarget::Authority { host, port } => return write!(f, "{}:{}", host, port),
+            RequestTarget::Asterisk => return write!(f, "*"),
+        }
+        match self.query() {
+            Some(query) => write!(f, "?{}", query),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Split a path from its query.
+fn split_query(target: &str) -> (String, Option<String>) {
+    match target.split_once('?') {
+        Some((path, query)) => (path.to_string(), Some(query.to_string())),
+        None => (target.to_string(), None),
+    }
+}
+
+/// Split an authority, `host[:port]`, into its host and port.
+///
+/// User information ahead of the host is dropped, IPv6 hosts keep their brackets.
+pub(crate) fn split_authority(authority: &str) -> Option<(&str, Option<u16>)> {
+    let authority = authority.rsplit('@').next().unwrap_or_default();
+    let port_start = match authority.rfind(']') {
+        Some(end) => authority[end..].find(':').map(|colon| end + colon),
+        None => authority.rfind(':'),
+    };
+    let (host, port) = match port_start {
+        Some(colon) => (&authority[..colon], Some(&authority[colon + 1..])),
+        None => (authority, None),
+    };
+    let valid_host = !host.is_empty()
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-._~%!$&'()*+,;=[]:".contains(c));
+    if !valid_host {
+        return None;
+    }
+    match port {
+        Some(port) => Some((host, Some(port.parse().ok()?))),
+        None => Some((host, None)),
+    }
+}
+
+/// Decode the `%XX` escapes of a query component, and `+` as a space.
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = bytes
+            .get(index + 1..index + 3)
+            .filter(|hex| bytes[index] == b'%' && hex.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (escaped, bytes[index]) {
+            (Some(byte), _) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            (None, byte) => {
+                decoded.push(if byte == b'+' { b' ' } else { byte });
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
@@ -0,0 +1,86 @@
+use http_parse::{
+    AsyncHttpParser, HttpMethod, HttpParser, HttpResponseBuilder, ParseErrorKind, ParseMode,
+    ParserConfig, StatusCode,
+};
+use std::io::Cursor;
+use tokio::io::AsyncWriteExt;
+
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+#[test]
+fn test_async_matches_blocking_parser() {
+    let text = "POST /upload HTTP/1.1\r\nHost: a\r\nTransfer-Encoding: chunked\r\n\r\n4;name=x\r\nWiki\r\n0\r\nExpires: never\r\n\r\n\
+                GET /next HTTP/1.1\r\nHost: a\r\n\r\n";
+    let mut reader = Cursor::new(text.as_bytes());
+    let blocking: Vec<_> = HttpParser::from_reader(&mut reader)
+        .requests()
+        .map(|request| request.unwrap())
+        .collect();
+
+    let asynchronous = block_on(async {
+        let mut reader = text.as_bytes();
+        let mut parser = AsyncHttpParser::from_reader(&mut reader);
+        let mut requests = Vec::new();
+        while let Some(request) = parser.next_request().await.unwrap() {
+            requests.push(request);
+        }
+        requests
+    });
+    assert_eq!(asynchronous, blocking);
+    assert_eq!(asynchronous[0].trailers().len(), 1);
+}
+
+#[test]
+fn test_async_response_split_across_reads() {
+    block_on(async {
+        let (mut client, mut server) = tokio::io::duplex(8);
+        let writer = tokio::spawn(async move {
+            for piece in ["HTTP/1.1 200 OK\r\nContent-", "Length: 5\r\n\r\nhel", "lo"] {
+                server.write_all(piece.as_bytes()).await.unwrap();
+            }
+        });
+        let mut parser = AsyncHttpParser::from_reader(&mut client);
+        let response = parser.response().await.unwrap();
+        writer.await.unwrap();
+        assert_eq!(response.status_code(), 200);
+        assert_eq!(response.data(), b"hello");
+    });
+}
+
+#[test]
+fn test_async_errors_and_head_requests() {
+    block_on(async {
+        let mut reader: &[u8] = b"GET / HTTP/1.1\nHost: a\r\n\r\n";
+        let config = ParserConfig::new().mode(ParseMode::Strict);
+        let mut parser = AsyncHttpParser::with_config(&mut reader, config);
+        let error = parser.request().await.unwrap_err();
+        assert_eq!(error.kind(), &ParseErrorKind::BareLineFeed);
+
+        let mut reader: &[u8] = b"HTTP/1.1 200 OK\r\nContent-Length: 20\r\n\r\n";
+        let mut parser = AsyncHttpParser::from_reader(&mut reader);
+        parser.set_request_method(HttpMethod::Head);
+        let response = parser.response().await.unwrap();
+        assert!(response.data().is_empty());
+        assert!(parser.next_response().await.unwrap().is_none());
+    });
+}
+
+#[test]
+fn test_async_write_to() {
+    let response = HttpResponseBuilder::new()
+        .status(StatusCode::OK)
+        .header("Content-Length", 2)
+        .body(b"ok")
+        .build();
+    let written = block_on(async {
+        let mut output = Vec::new();
+        response.write_to(&mut output).await.unwrap();
+        output
+    });
+    assert_eq!(written, response.into_bytes());
+}
@@ -1,7 +1,15 @@
 use http_parse::{
-    HttpHeader, HttpMethod, HttpParser, HttpUrl, HttpVersion, StatusCode, H_TRANSFER_ENCODING,
+    Accept, AcceptCharset, AcceptEncoding, AcceptLanguage, Allow, Authorization, ByteRange,
+    Challenge, Connection, ContentLength, ContentRange, ContentType, Cookie, CookieJar, Date,
+    DigestAlgorithm, DigestClient, ETag, Expires, HeaderMap, Host, HttpDate, HttpHeader,
+    HttpMethod, HttpParseError, HttpParser, HttpRequest, HttpRequestBuilder, HttpRequestRef,
+    HttpResponseBuilder, HttpResponseRef, HttpUrl, HttpVersion, IfMatch, IfModifiedSince,
+    IfNoneMatch, IfRange, IfUnmodifiedSince, LastModified, MediaRange, ParseErrorKind, ParseMode,
+    ParseStatus, ParserConfig, Precondition, ProxyAuthenticate, QualityValue, Range, RequestParser,
+    RequestTarget, ResponseParser, RetryAfter, SameSite, StatusCode, TransferEncoding, TypedHeader,
+    Validators, WwwAuthenticate, H_IF_MODIFIED_SINCE, H_LINK, H_TRANSFER_ENCODING,
 };
-use std::io::Cursor;
+use std::io::{Cursor, Read};
 
 #[test]
 fn test_response() {
@@ -112,3 +120,2187 @@ fn test_url() {
     assert_eq!(localhost_url.query("start"), Some(&"56".to_owned()));
     assert_eq!(localhost_url.file(), Some("video.mp4"));
 }
+
+#[test]
+fn test_push_response_byte_by_byte() {
+    let response_text="HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nTransfer-Encoding: chunked\r\n\r\n7\r\nMozilla\r\n11\r\nDeveloper Network\r\n0\r\n\r\n";
+
+    let mut parser = ResponseParser::new();
+    let bytes = response_text.as_bytes();
+    for byte in &bytes[..bytes.len() - 1] {
+        assert_eq!(
+            parser.feed(std::slice::from_ref(byte)).unwrap(),
+            ParseStatus::Partial
+        );
+    }
+    match parser.feed(&bytes[bytes.len() - 1..]).unwrap() {
+        ParseStatus::Complete(response, consumed) => {
+            assert_eq!(consumed, 1);
+            assert_eq!(response.data(), b"MozillaDeveloper Network");
+            assert_eq!(response.into_bytes(), bytes);
+        }
+        ParseStatus::Partial => panic!("response should be complete"),
+    }
+}
+
+#[test]
+fn test_push_pipelined_requests() {
+    let requests = b"POST /a HTTP/1.1\r\nContent-Length: 5\r\n\r\nhelloGET /b HTTP/1.1\r\n\r\n";
+    let mut parser = RequestParser::new();
+
+    let ParseStatus::Complete(first, consumed) = parser.feed(requests).unwrap() else {
+        panic!("first request should be complete");
+    };
+    assert_eq!(first.method(), HttpMethod::Post);
+    assert_eq!(first.data(), b"hello");
+
+    let ParseStatus::Complete(second, rest) = parser.feed(&requests[consumed..]).unwrap() else {
+        panic!("second request should be complete");
+    };
+    assert_eq!(second.method(), HttpMethod::Get);
+    assert_eq!(consumed + rest, requests.len());
+    assert!(parser.finish().unwrap().is_none());
+}
+
+#[test]
+fn test_push_truncated_body() {
+    let mut parser = RequestParser::new();
+    let status = parser
+        .feed(b"POST / HTTP/1.1\r\nContent-Length: 10\r\n\r\nhel")
+        .unwrap();
+    assert_eq!(status, ParseStatus::Partial);
+    assert!(parser.finish().is_err());
+}
+
+#[test]
+fn test_borrowed_response_chunked() {
+    let response_text = b"HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nTransfer-Encoding: chunked\r\n\r\n7\r\nMozilla\r\n11\r\nDeveloper Network\r\n0\r\n\r\n";
+
+    assert_eq!(
+        HttpResponseRef::parse(&response_text[..response_text.len() - 2]).unwrap(),
+        ParseStatus::Partial
+    );
+    let ParseStatus::Complete(response, consumed) = HttpResponseRef::parse(response_text).unwrap()
+    else {
+        panic!("response should be complete");
+    };
+    assert_eq!(consumed, response_text.len());
+    assert_eq!(response.status_msg(), b"OK");
+    assert_eq!(
+        response.header("content-type").map(|header| header.value()),
+        Some(&b"text/plain"[..])
+    );
+    assert_eq!(response.data(), &[&b"Mozilla"[..], b"Developer Network"]);
+
+    let owned = response.to_owned();
+    assert_eq!(owned.data(), b"MozillaDeveloper Network");
+    assert_eq!(owned.into_bytes(), response_text);
+}
+
+#[test]
+fn test_borrowed_request_to_owned() {
+    let request_text = b"GET /index.html HTTP/1.1\r\nHost: developer.mozilla.org\r\n\r\n";
+    let ParseStatus::Complete(request, consumed) = HttpRequestRef::parse(request_text).unwrap()
+    else {
+        panic!("request should be complete");
+    };
+    assert_eq!(consumed, request_text.len());
+    assert_eq!(request.target(), b"/index.html");
+    assert!(request.data().is_empty());
+
+    let owned = request.to_owned();
+    assert_eq!(owned.method(), HttpMethod::Get);
+    assert_eq!(
+        owned.header("host"),
+        Some(&HttpHeader::new("Host", "developer.mozilla.org"))
+    );
+    assert_eq!(owned.into_bytes(), request_text);
+}
+
+#[test]
+fn test_streaming_body_leaves_next_message() {
+    let text = "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n7\r\nMozilla\r\n11\r\nDeveloper Network\r\n0\r\n\r\nHTTP/1.1 204 No Content\r\n\r\n";
+    let mut reader = Cursor::new(text.as_bytes());
+    let mut parser = HttpParser::from_reader(&mut reader);
+
+    let (response, mut body) = parser.response_streaming().unwrap();
+    assert_eq!(response.status_code(), 200);
+    assert!(response.data().is_empty());
+
+    let mut data = Vec::new();
+    let mut buffer = [0; 3];
+    loop {
+        let read = body.read(&mut buffer).unwrap();
+        if read == 0 {
+            break;
+        }
+        data.extend_from_slice(&buffer[..read]);
+    }
+    assert!(body.is_done());
+    assert_eq!(data, b"MozillaDeveloper Network");
+
+    let next = parser.response().unwrap();
+    assert_eq!(next.status_code(), 204);
+}
+
+#[test]
+fn test_streaming_body_truncated() {
+    let text = "POST / HTTP/1.1\r\nContent-Length: 10\r\n\r\nshort";
+    let mut reader = Cursor::new(text.as_bytes());
+    let mut parser = HttpParser::from_reader(&mut reader);
+    let (_, mut body) = parser.request_streaming().unwrap();
+
+    let error = body.read_to_end(&mut Vec::new()).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn test_limit_start_line_before_line_end() {
+    let config = ParserConfig::new().max_start_line_length(16);
+    let mut parser = RequestParser::with_config(config);
+    let result = parser.feed(b"GET /a-very-long-target-that-never-ends");
+    assert!(matches!(
+        kind(result),
+        Err(ParseErrorKind::StartLineTooLong(16))
+    ));
+}
+
+#[test]
+fn test_limit_header_size() {
+    let config = ParserConfig::new().max_header_size(10);
+    let request = b"GET / HTTP/1.1\r\nHost: developer.mozilla.org\r\n\r\n";
+    let result = HttpRequestRef::parse_with_config(request, &config);
+    assert!(matches!(
+        kind(result),
+        Err(ParseErrorKind::HeaderTooLarge(10))
+    ));
+}
+
+#[test]
+fn test_limit_body_size() {
+    let config = ParserConfig::new().max_body_size(1024);
+    let text = "POST / HTTP/1.1\r\nContent-Length: 99999999999\r\n\r\n";
+    let mut reader = Cursor::new(text.as_bytes());
+    let result = HttpParser::with_config(&mut reader, config).request();
+    assert!(matches!(
+        kind(result),
+        Err(ParseErrorKind::BodyTooLarge(1024))
+    ));
+
+    let chunked = b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nabc\r\n3\r\n";
+    let mut parser = RequestParser::with_config(ParserConfig::new().max_body_size(4));
+    assert!(matches!(
+        kind(parser.feed(chunked)),
+        Err(ParseErrorKind::BodyTooLarge(4))
+    ));
+}
+
+fn strict_request(text: &[u8]) -> Result<ParseStatus<HttpRequestRef<'_>>, HttpParseError> {
+    HttpRequestRef::parse_with_config(text, &ParserConfig::new().mode(ParseMode::Strict))
+}
+
+fn kind<T>(result: Result<T, HttpParseError>) -> Result<T, ParseErrorKind> {
+    result.map_err(|e| e.kind().clone())
+}
+
+#[test]
+fn test_strict_mode_errors() {
+    assert!(matches!(
+        kind(strict_request(b"GET / HTTP/1.1\r\nHo st: a\r\n\r\n")),
+        Err(ParseErrorKind::InvalidHeaderName(_))
+    ));
+    assert!(matches!(
+        kind(strict_request(b"GET / HTTP/1.1\r\nHost : a\r\n\r\n")),
+        Err(ParseErrorKind::WhitespaceBeforeColon(_))
+    ));
+    assert!(matches!(
+        kind(strict_request(b"GET / HTTP/1.1\r\nHost\r\n\r\n")),
+        Err(ParseErrorKind::MissingColon(_))
+    ));
+    assert!(matches!(
+        kind(strict_request(b"GET / HTTP/1.1\r\nX-A: a\r\n b\r\n\r\n")),
+        Err(ParseErrorKind::ObsoleteLineFolding(_))
+    ));
+    assert!(matches!(
+        kind(strict_request(b"GET / HTTP/1.1\nHost: a\r\n\r\n")),
+        Err(ParseErrorKind::BareLineFeed)
+    ));
+    assert!(matches!(
+        kind(strict_request(b"GET  / HTTP/1.1\r\n\r\n")),
+        Err(ParseErrorKind::Url(_))
+    ));
+}
+
+#[test]
+fn test_lenient_mode_tolerances() {
+    let text =
+        b"\r\nGET  /  HTTP/1.1\nHost:  example.com\r\nnot a header\r\nX-Folded: one\r\n  two\n\n";
+    let ParseStatus::Complete(request, consumed) = HttpRequestRef::parse(text).unwrap() else {
+        panic!("request should be complete");
+    };
+    assert_eq!(consumed, text.len());
+    assert_eq!(request.target(), b"/");
+    assert_eq!(request.headers().len(), 2);
+
+    let owned = request.to_owned();
+    assert_eq!(
+        owned.header("host"),
+        Some(&HttpHeader::new("Host", "example.com"))
+    );
+    assert_eq!(
+        owned.header("x-folded"),
+        Some(&HttpHeader::new("X-Folded", "one two"))
+    );
+}
+
+#[test]
+fn test_lenient_mode_rejects_whitespace_before_colon() {
+    let smuggled = b"POST / HTTP/1.1\r\nTransfer-Encoding : chunked\r\n\r\n0\r\n\r\n";
+    assert!(matches!(
+        kind(RequestParser::new().feed(smuggled)),
+        Err(ParseErrorKind::WhitespaceBeforeColon(_))
+    ));
+    assert!(matches!(
+        kind(HttpRequestRef::parse(
+            b"GET / HTTP/1.1\r\nContent-Length\t: 0\r\n\r\n"
+        )),
+        Err(ParseErrorKind::WhitespaceBeforeColon(_))
+    ));
+}
+
+#[test]
+fn test_lenient_mode_does_not_fold_ignored_lines() {
+    let text = b"GET / HTTP/1.1\r\nA: 1\r\nbogus\r\n  more\r\n\r\n";
+    let ParseStatus::Complete(request, _) = HttpRequestRef::parse(text).unwrap() else {
+        panic!("request should be complete");
+    };
+    let owned = request.to_owned();
+    assert_eq!(owned.header("a"), Some(&HttpHeader::new("A", "1")));
+}
+
+#[test]
+fn test_parsing_never_panics() {
+    let samples: [&[u8]; 3] = [
+        b"POST /a?b=c HTTP/1.1\r\nHost: a\r\nTransfer-Encoding: chunked\r\n\r\n5;x=y\r\nhello\r\n0\r\nT: v\r\n\r\n",
+        b"HTTP/1.1 200 OK\r\nContent-Length: 3\r\nX: a\r\n b\r\n\r\nabc",
+        b"GET / HTTP/1.1\nA:\n:b\n \n\n",
+    ];
+    let mut seed: u32 = 0x2545_f491;
+    let mut random = move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        seed as usize
+    };
+    for mode in [ParseMode::Strict, ParseMode::Lenient] {
+        let config = ParserConfig::new().mode(mode);
+        for sample in samples {
+            for _ in 0..500 {
+                let mut input = sample.to_vec();
+                for _ in 0..1 + random() % 4 {
+                    let index = random() % input.len();
+                    input[index] = b"\r\n :;\t0aZ\xff"[random() % 10];
+                }
+                input.truncate(random() % (input.len() + 1));
+
+                let _ = HttpRequestRef::parse_with_config(&input, &config);
+                let _ = HttpResponseRef::parse_with_config(&input, &config);
+                let mut requests = RequestParser::with_config(config);
+                let mut responses = ResponseParser::with_config(config);
+                for piece in input.chunks(1 + random() % 7) {
+                    let _ = requests.feed(piece);
+                    let _ = responses.feed(piece);
+                }
+                let _ = requests.finish();
+                let _ = responses.finish();
+            }
+        }
+    }
+}
+
+#[test]
+fn test_smuggling_framing_rejected() {
+    assert!(matches!(
+        kind(HttpRequestRef::parse(
+            b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\nContent-Length: 3\r\n\r\n0\r\n\r\n"
+        )),
+        Err(ParseErrorKind::ConflictingFraming)
+    ));
+    assert!(matches!(
+        kind(HttpRequestRef::parse(
+            b"POST / HTTP/1.1\r\nContent-Length: 3\r\nContent-Length: 4\r\n\r\nabcd"
+        )),
+        Err(ParseErrorKind::ConflictingContentLength(_))
+    ));
+    assert!(matches!(
+        kind(HttpRequestRef::parse(
+            b"POST / HTTP/1.1\r\nContent-Length: +3\r\n\r\nabc"
+        )),
+        Err(ParseErrorKind::InvalidContentLength(_))
+    ));
+    assert!(matches!(
+        kind(HttpRequestRef::parse(
+            b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked, gzip\r\n\r\n"
+        )),
+        Err(ParseErrorKind::ChunkedNotFinal(_))
+    ));
+    assert!(matches!(
+        kind(HttpRequestRef::parse(
+            b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\nTransfer-Encoding: chunked\r\n\r\n"
+        )),
+        Err(ParseErrorKind::InvalidTransferEncoding(_))
+    ));
+    assert!(matches!(
+        kind(HttpRequestRef::parse(
+            b"POST / HTTP/1.0\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\n"
+        )),
+        Err(ParseErrorKind::InvalidTransferEncoding(_))
+    ));
+    assert!(matches!(
+        kind(strict_request(
+            b"POST / HTTP/1.1\r\nTransfer-Encoding: identity\r\n\r\n"
+        )),
+        Err(ParseErrorKind::InvalidTransferEncoding(_))
+    ));
+}
+
+#[test]
+fn test_framing_accepted_variants() {
+    let text = b"POST / HTTP/1.1\r\nContent-Length: 3, 3\r\nContent-Length: 3\r\n\r\nabc";
+    let ParseStatus::Complete(request, used) = HttpRequestRef::parse(text).unwrap() else {
+        panic!("request should be complete");
+    };
+    assert_eq!(used, text.len());
+    assert_eq!(request.data(), &[b"abc"]);
+
+    let text = b"POST / HTTP/1.1\r\nTransfer-Encoding: gzip,\r\nTransfer-Encoding: CHUNKED\r\n\r\n0\r\n\r\n";
+    assert!(matches!(
+        HttpRequestRef::parse(text),
+        Ok(ParseStatus::Complete(_, used)) if used == text.len()
+    ));
+}
+
+#[test]
+fn test_response_read_until_close() {
+    let text = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: gzip\r\n\r\nall of it";
+    let mut parser = ResponseParser::new();
+    assert_eq!(parser.feed(text).unwrap(), ParseStatus::Partial);
+    let response = parser.finish().unwrap().unwrap();
+    assert_eq!(response.data(), b"all of it");
+
+    let mut reader = Cursor::new(&text[..]);
+    let mut parser = HttpParser::from_reader(&mut reader);
+    let (_, mut body) = parser.response_streaming().unwrap();
+    let mut data = Vec::new();
+    body.read_to_end(&mut data).unwrap();
+    assert_eq!(data, b"all of it");
+}
+
+#[test]
+fn test_chunk_extensions_and_trailers() {
+    let text = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n\
+5;name=val ; flag\r\nhello\r\n0;note=\"a \\\"b\\\"\"\r\nExpires: never\r\nDigest: abc\r\n\r\n";
+    let mut parser = ResponseParser::new();
+    let ParseStatus::Complete(response, used) = parser.feed(text).unwrap() else {
+        panic!("response should be complete");
+    };
+    assert_eq!(used, text.len());
+    assert_eq!(response.data(), b"hello");
+    let extensions = response.chunk_extensions(0);
+    assert_eq!(extensions.len(), 2);
+    assert_eq!(extensions[0].name(), "name");
+    assert_eq!(extensions[0].value(), Some("val"));
+    assert_eq!(extensions[1].value(), None);
+    assert_eq!(response.chunk_extensions(1)[0].value(), Some("a \"b\""));
+    assert_eq!(
+        response.trailers(),
+        vec![
+            &HttpHeader::new("Expires", "never"),
+            &HttpHeader::new("Digest", "abc")
+        ]
+    );
+
+    // the borrowed view sees the same body and the bytes round trip
+    let ParseStatus::Complete(borrowed, _) = HttpResponseRef::parse(text).unwrap() else {
+        panic!("response should be complete");
+    };
+    assert_eq!(borrowed.trailers()[1].value(), b"abc");
+    assert_eq!(borrowed.to_owned(), response);
+    let bytes = response.into_bytes();
+    assert_eq!(
+        ResponseParser::new().feed(&bytes).unwrap(),
+        ParseStatus::Complete(response, bytes.len())
+    );
+}
+
+#[test]
+fn test_streaming_trailers() {
+    let text = b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n3;x=1\r\nabc\r\n0\r\nChecksum: 42\r\n\r\n";
+    let mut reader = Cursor::new(&text[..]);
+    let mut parser = HttpParser::from_reader(&mut reader);
+    let (_, mut body) = parser.request_streaming().unwrap();
+    let mut first = [0u8; 1];
+    body.read_exact(&mut first).unwrap();
+    assert_eq!(body.chunk_extensions()[0].value(), Some("1"));
+    let mut rest = Vec::new();
+    body.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, b"bc");
+    assert_eq!(body.trailers(), &[HttpHeader::new("Checksum", 42)]);
+}
+
+#[test]
+fn test_builder_trailers() {
+    let request = HttpRequest::builder()
+        .method(HttpMethod::Post)
+        .header("Content-Length", 4)
+        .body(b"data")
+        .trailer("Checksum", "e3b0")
+        .build();
+    assert_eq!(
+        request.into_bytes(),
+        b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n4\r\ndata\r\n0\r\nChecksum: e3b0\r\n\r\n"
+    );
+
+    assert!(matches!(
+        kind(strict_request(
+            b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n1;=x\r\na\r\n0\r\n\r\n"
+        )),
+        Err(ParseErrorKind::Body(_))
+    ));
+}
+
+#[test]
+fn test_parser_keeps_pipelined_requests() {
+    let text = b"POST /a HTTP/1.1\r\nContent-Length: 2\r\n\r\nhi\r\nGET /b HTTP/1.1\r\n\r\nGET /c HTTP/1.1\r\n\r\n";
+    let mut reader = Cursor::new(&text[..]);
+    let mut parser = HttpParser::from_reader(&mut reader);
+    let first = parser.next_request().unwrap().unwrap();
+    assert_eq!(first.data(), b"hi");
+    assert!(parser.buffered().ends_with(b"GET /c HTTP/1.1\r\n\r\n"));
+
+    let methods: Vec<HttpMethod> = parser
+        .requests()
+        .map(|request| request.unwrap().method())
+        .collect();
+    assert_eq!(methods, vec![HttpMethod::Get, HttpMethod::Get]);
+    assert!(matches!(parser.next_request(), Ok(None)));
+}
+
+#[test]
+fn test_parser_truncated_message_is_not_clean_end() {
+    let text = b"HTTP/1.1 204 No Content\r\n\r\nHTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\nshort";
+    let mut reader = Cursor::new(&text[..]);
+    let mut parser = HttpParser::from_reader(&mut reader);
+    let mut responses = parser.responses();
+    assert_eq!(responses.next().unwrap().unwrap().status_code(), 204);
+    assert!(matches!(
+        responses.next().map(kind),
+        Some(Err(ParseErrorKind::UnexpectedEof))
+    ));
+    assert!(responses.next().is_none());
+
+    let mut empty = Cursor::new(&b""[..]);
+    assert!(HttpParser::from_reader(&mut empty)
+        .responses()
+        .next()
+        .is_none());
+}
+
+#[test]
+fn test_response_without_body_by_context() {
+    // the Content-Length of a response to HEAD describes the resource, not the response
+    let text = b"HTTP/1.1 200 OK\r\nContent-Length: 120\r\n\r\nHTTP/1.1 304 Not Modified\r\nContent-Length: 5\r\n\r\n";
+    let mut parser = ResponseParser::new();
+    parser.set_request_method(HttpMethod::Head);
+    let ParseStatus::Complete(head, used) = parser.feed(text).unwrap() else {
+        panic!("response should be complete");
+    };
+    assert!(head.data().is_empty());
+    let ParseStatus::Complete(not_modified, rest) = parser.feed(&text[used..]).unwrap() else {
+        panic!("response should be complete");
+    };
+    assert_eq!(not_modified.status_code(), 304);
+    assert!(not_modified.data().is_empty());
+    assert_eq!(used + rest, text.len());
+
+    let tunnel = b"HTTP/1.1 200 Connection Established\r\n\r\n";
+    let status = HttpResponseRef::parse_with_request_method(
+        tunnel,
+        &HttpMethod::Connect,
+        &ParserConfig::default(),
+    );
+    assert!(matches!(status, Ok(ParseStatus::Complete(_, 39))));
+}
+
+#[test]
+fn test_response_read_to_close_without_framing() {
+    let text = b"HTTP/1.0 200 OK\r\nContent-Type: text/plain\r\n\r\nuntil the end";
+    assert_eq!(HttpResponseRef::parse(text).unwrap(), ParseStatus::Partial);
+
+    let mut reader = Cursor::new(&text[..]);
+    let response = HttpParser::from_reader(&mut reader).response().unwrap();
+    assert_eq!(response.data(), b"until the end");
+}
+
+#[test]
+fn test_interim_responses_collected() {
+    let text = b"HTTP/1.1 100 Continue\r\n\r\nHTTP/1.1 103 Early Hints\r\nLink: </a.css>; rel=preload\r\n\r\nHTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok";
+    let mut reader = Cursor::new(&text[..]);
+    let response = HttpParser::from_reader(&mut reader).response().unwrap();
+    assert_eq!(response.status_code(), 200);
+    assert_eq!(response.data(), b"ok");
+    let interim = response.interim();
+    assert_eq!(interim.len(), 2);
+    assert_eq!(interim[0].status_code(), 100);
+    assert_eq!(
+        interim[1]
+            .header(H_LINK)
+            .unwrap()
+            .value::<String>()
+            .unwrap(),
+        "</a.css>; rel=preload"
+    );
+
+    // fed one byte at a time the result is the same
+    let mut parser = ResponseParser::new();
+    let mut parsed = None;
+    for byte in text.iter() {
+        if let ParseStatus::Complete(response, _) = parser.feed(&[*byte]).unwrap() {
+            parsed = Some(response);
+        }
+    }
+    assert_eq!(parsed, Some(response));
+}
+
+#[test]
+fn test_interim_responses_limit() {
+    let hints = b"HTTP/1.1 103 Early Hints\r\n\r\n".repeat(3);
+    let config = ParserConfig::new().max_interim_responses(2);
+    let mut parser = ResponseParser::with_config(config);
+    assert!(matches!(
+        kind(parser.feed(&hints)),
+        Err(ParseErrorKind::TooManyInterimResponses(2))
+    ));
+
+    let mut reader = Cursor::new(hints.repeat(4));
+    let result = HttpParser::from_reader(&mut reader).response();
+    assert!(matches!(
+        kind(result),
+        Err(ParseErrorKind::TooManyInterimResponses(10))
+    ));
+}
+
+#[test]
+fn test_switching_protocols_is_final() {
+    let text = b"HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\n\r\n\x81\x00";
+    let mut parser = ResponseParser::new();
+    let ParseStatus::Complete(response, used) = parser.feed(text).unwrap() else {
+        panic!("response should be complete");
+    };
+    assert_eq!(response.status_code(), 101);
+    assert_eq!(&text[used..], b"\x81\x00");
+
+    let mut parser = ResponseParser::new();
+    assert_eq!(
+        parser.feed(b"HTTP/1.1 100 Continue\r\n\r\n").unwrap(),
+        ParseStatus::Partial
+    );
+    assert!(matches!(
+        kind(parser.finish()),
+        Err(ParseErrorKind::UnexpectedEof)
+    ));
+}
+
+#[test]
+fn test_registered_and_extension_methods() {
+    let text = b"PATCH /doc HTTP/1.1\r\nContent-Length: 0\r\n\r\nPROPFIND /dav HTTP/1.1\r\n\r\nBREW /pot HTTP/1.1\r\n\r\n";
+    let mut reader = Cursor::new(&text[..]);
+    let mut parser = HttpParser::from_reader(&mut reader);
+    let methods: Vec<HttpMethod> = parser
+        .requests()
+        .map(|request| request.unwrap().method())
+        .collect();
+    assert_eq!(
+        methods,
+        vec![
+            HttpMethod::Patch,
+            HttpMethod::Extension("PROPFIND".to_string()),
+            HttpMethod::Extension("BREW".to_string()),
+        ]
+    );
+    assert!(methods[1].is_registered() && methods[1].is_safe());
+    assert!(!methods[2].is_registered() && !methods[2].is_idempotent());
+
+    assert!(matches!(
+        kind(HttpRequestRef::parse(b"GE(T / HTTP/1.1\r\n\r\n")),
+        Err(ParseErrorKind::Method(_))
+    ));
+}
+
+#[test]
+fn test_method_properties() {
+    assert!(HttpMethod::Get.is_safe() && HttpMethod::Get.is_cacheable());
+    assert!(!HttpMethod::Put.is_safe() && HttpMethod::Put.is_idempotent());
+    assert!(!HttpMethod::Post.is_idempotent() && HttpMethod::Post.is_cacheable());
+    assert!(!HttpMethod::Delete.is_cacheable() && HttpMethod::Delete.is_idempotent());
+    let lock: HttpMethod = "LOCK".parse().unwrap();
+    assert!(!lock.is_safe() && !lock.is_idempotent());
+    assert_eq!(
+        "CONNECT".parse::<HttpMethod>().unwrap(),
+        HttpMethod::Connect
+    );
+    assert_eq!(lock.to_string(), "LOCK");
+}
+
+#[test]
+fn test_request_target_forms() {
+    let origin = RequestTarget::parse("/a/b?x=1&y=%2Fz+w", &HttpMethod::Get).unwrap();
+    assert_eq!(origin.path(), "/a/b");
+    assert_eq!(
+        origin.query_pairs(),
+        vec![
+            ("x".to_string(), "1".to_string()),
+            ("y".to_string(), "/z w".to_string())
+        ]
+    );
+    // escapes need two hexadecimal digits, a sign is not one
+    let signed = RequestTarget::parse("/a?x=%+1&y=%-1&z=%4", &HttpMethod::Get).unwrap();
+    assert_eq!(
+        signed.query_pairs(),
+        vec![
+            ("x".to_string(), "% 1".to_string()),
+            ("y".to_string(), "%-1".to_string()),
+            ("z".to_string(), "%4".to_string())
+        ]
+    );
+
+    let absolute = RequestTarget::parse("HTTP://example.com:81?q", &HttpMethod::Get).unwrap();
+    assert_eq!(absolute.path(), "/");
+    let url = absolute.to_url(Some("ignored.org")).unwrap();
+    assert_eq!((url.host(), url.port()), ("example.com", Some(81)));
+
+    assert_eq!(
+        RequestTarget::parse("[::1]:443", &HttpMethod::Connect).unwrap(),
+        RequestTarget::Authority {
+            host: "[::1]".to_string(),
+            port: 443
+        }
+    );
+    assert_eq!(
+        RequestTarget::parse("*", &HttpMethod::Options).unwrap(),
+        RequestTarget::Asterisk
+    );
+    assert!(RequestTarget::parse("*", &HttpMethod::Get).is_err());
+    assert!(RequestTarget::parse("example.com", &HttpMethod::Connect).is_err());
+    assert!(RequestTarget::parse("index.html", &HttpMethod::Get).is_err());
+}
+
+#[test]
+fn test_request_url_from_host() {
+    let text = b"GET /docs?page=2 HTTP/1.1\r\nHost: example.com:8080\r\n\r\n";
+    let ParseStatus::Complete(request, _) = HttpRequestRef::parse(text).unwrap() else {
+        panic!("request should be complete");
+    };
+    let request = request.to_owned();
+    assert_eq!(request.raw_target(), "/docs?page=2");
+    let url = request.url().unwrap();
+    assert_eq!(url.host(), "example.com");
+    assert_eq!(url.port(), Some(8080));
+    assert_eq!(url.query("page"), Some(&"2".to_string()));
+
+    assert!(matches!(
+        kind(strict_request(b"OPTIONS index HTTP/1.1\r\nHost: a\r\n\r\n")),
+        Err(ParseErrorKind::Url(_))
+    ));
+    assert!(HttpRequest::new().url().is_err());
+}
+
+#[test]
+fn test_error_offsets_and_lines() {
+    let text = b"GET / HTTP/1.1\r\nHost: a\r\nHo st: b\r\n\r\n";
+    let error = strict_request(text).unwrap_err();
+    assert_eq!(
+        error.kind(),
+        &ParseErrorKind::InvalidHeaderName("Ho st: b".to_string())
+    );
+    assert_eq!(error.offset(), Some(25));
+    assert_eq!(error.line(), Some(3));
+    assert!(error.to_string().ends_with("at line 3 (byte 25)"));
+
+    // the same error found while the request arrives in pieces, after skipped empty lines
+    let mut parser = RequestParser::with_config(ParserConfig::new().mode(ParseMode::Strict));
+    assert!(matches!(
+        parser.feed(b"\r\nGET / HTTP/1.1\r\nHost: a\r\n"),
+        Ok(ParseStatus::Partial)
+    ));
+    let error = parser.feed(b"Ho st: b\r\n\r\n").unwrap_err();
+    assert_eq!((error.offset(), error.line()), (Some(27), Some(4)));
+
+    let text = b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n2\r\nab\r\nzz\r\n";
+    let error = HttpRequestRef::parse(text).unwrap_err();
+    assert!(matches!(error.kind(), ParseErrorKind::Body(_)));
+    assert_eq!((error.offset(), error.line()), (Some(54), Some(6)));
+}
+
+#[test]
+fn test_error_keeps_io_source() {
+    struct Failing;
+    impl Read for Failing {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                "slow client",
+            ))
+        }
+    }
+    let mut reader = Failing;
+    let error = HttpParser::from_reader(&mut reader).request().unwrap_err();
+    assert_eq!(
+        error.kind(),
+        &ParseErrorKind::Io(std::io::ErrorKind::TimedOut)
+    );
+    assert_eq!(error.io_error_kind(), Some(std::io::ErrorKind::TimedOut));
+    let source = std::error::Error::source(&error).unwrap();
+    assert_eq!(source.to_string(), "slow client");
+    assert_eq!(error.suggested_status(), StatusCode::REQUEST_TIME_OUT);
+
+    let error: std::io::Error = error.into();
+    assert_eq!(error.kind(), std::io::ErrorKind::TimedOut);
+    let error: std::io::Error = HttpRequestRef::parse(b"GET / HTTP/9.9\r\n\r\n")
+        .unwrap_err()
+        .into();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_error_suggested_status() {
+    let status = |text: &[u8], config: ParserConfig| {
+        HttpRequestRef::parse_with_config(text, &config)
+            .unwrap_err()
+            .suggested_status()
+    };
+    let config = ParserConfig::new();
+    assert_eq!(
+        status(b"GET / HTTP/1.1\r\nHost: a\r\n\r\n", config.max_headers(0)),
+        StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE
+    );
+    assert_eq!(
+        status(
+            b"GET /a-long-target HTTP/1.1\r\n\r\n",
+            config.max_start_line_length(8)
+        ),
+        StatusCode::REQUEST_URI_TOO_LARGE
+    );
+    assert_eq!(
+        status(
+            b"POST / HTTP/1.1\r\nContent-Length: 9\r\n\r\n",
+            config.max_body_size(4)
+        ),
+        StatusCode::REQUEST_ENTITY_TOO_LARGE
+    );
+    assert_eq!(
+        status(b"GET / HTTP/3.0\r\n\r\n", config),
+        StatusCode::HTTP_VERSION_NOT_SUPPORTED
+    );
+    assert_eq!(
+        status(b"POST / HTTP/1.1\r\nContent-Length: x\r\n\r\n", config),
+        StatusCode::BAD_REQUEST
+    );
+}
+
+/// A reader that hands out one piece at a time, with no bytes ready between pieces.
+struct NonBlocking {
+    pieces: Vec<&'static [u8]>,
+    ready: bool,
+}
+
+impl Read for NonBlocking {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if !self.ready && !self.pieces.is_empty() {
+            self.ready = true;
+            return Err(std::io::ErrorKind::WouldBlock.into());
+        }
+        self.ready = false;
+        let Some(piece) = self.pieces.first_mut() else {
+            return Ok(0);
+        };
+        let length = piece.len().min(buf.len());
+        buf[..length].copy_from_slice(&piece[..length]);
+        *piece = &piece[length..];
+        if piece.is_empty() {
+            self.pieces.remove(0);
+        }
+        Ok(length)
+    }
+}
+
+#[test]
+fn test_would_block_resumes_request() {
+    let pieces: Vec<&[u8]> = vec![
+        b"POST /upload HT",
+        b"TP/1.1\r\nHost: a\r\nTransfer-Enc",
+        b"oding: chunked\r\n\r\n4\r\nWi",
+        b"ki\r\n5;ext=1\r\npedia\r\n0\r\nExpi",
+        b"res: never\r\n\r\nGET /next HTTP/1.1\r\n\r\n",
+    ];
+    let text = pieces.concat();
+    let mut reader = Cursor::new(&text);
+    let expected: Vec<_> = HttpParser::from_reader(&mut reader)
+        .requests()
+        .map(|request| request.unwrap())
+        .collect();
+
+    let mut reader = NonBlocking {
+        pieces,
+        ready: false,
+    };
+    let mut parser = HttpParser::from_reader(&mut reader);
+    let mut requests = Vec::new();
+    let mut blocked = 0;
+    loop {
+        match parser.next_request() {
+            Ok(Some(request)) => requests.push(request),
+            Ok(None) => break,
+            Err(e) if e.is_would_block() => blocked += 1,
+            Err(e) => panic!("unexpected error {}", e),
+        }
+    }
+    assert_eq!(requests, expected);
+    assert_eq!(requests[0].data(), b"Wikipedia");
+    assert_eq!(requests[1].raw_target(), "/next");
+    assert!(blocked >= 5);
+}
+
+#[test]
+fn test_would_block_resumes_response_and_body_reader() {
+    let mut reader = NonBlocking {
+        pieces: vec![
+            b"HTTP/1.1 200 OK\r\nContent-",
+            b"Length: 11\r\n\r\nhello ",
+            b"world",
+        ],
+        ready: false,
+    };
+    let mut parser = HttpParser::from_reader(&mut reader);
+    let response = loop {
+        match parser.response() {
+            Ok(response) => break response,
+            Err(e) => assert!(e.is_would_block(), "unexpected error {}", e),
+        }
+    };
+    assert_eq!(response.data(), b"hello world");
+
+    let mut reader = NonBlocking {
+        pieces: vec![
+            b"HTTP/1.1 200 OK\r\nContent-Length: 11\r\n\r\nhello ",
+            b"world",
+        ],
+        ready: true,
+    };
+    let mut parser = HttpParser::from_reader(&mut reader);
+    let (_, mut body) = parser.response_streaming().unwrap();
+    let mut data = Vec::new();
+    let mut buf = [0; 4];
+    loop {
+        match body.read(&mut buf) {
+            Ok(0) => break,
+            Ok(read) => data.extend_from_slice(&buf[..read]),
+            Err(e) => assert_eq!(e.kind(), std::io::ErrorKind::WouldBlock),
+        }
+    }
+    assert_eq!(data, b"hello world");
+}
+
+#[test]
+fn test_header_map_repeated_headers() {
+    let text = "HTTP/1.1 200 OK\r\nSet-Cookie: a=1\r\nVia: 1.1 one\r\nset-cookie: b=2\r\nContent-Length: 0\r\n\r\n";
+    let mut reader = Cursor::new(text.as_bytes());
+    let mut response = HttpParser::from_reader(&mut reader).response().unwrap();
+    let cookies: Vec<String> = response
+        .header_all("SET-COOKIE")
+        .iter()
+        .map(|header| header.value().unwrap())
+        .collect();
+    assert_eq!(cookies, vec!["a=1", "b=2"]);
+    // original casing and order survive a round trip
+    assert_eq!(response.into_bytes(), text.as_bytes());
+
+    response.put_header("Set-Cookie", "c=3");
+    assert_eq!(response.header_all("set-cookie").len(), 1);
+    assert_eq!(response.headers()[0], &HttpHeader::new("Set-Cookie", "c=3"));
+    response.append_header("Via", "1.1 two");
+    assert_eq!(response.header_map().get_all("via").len(), 2);
+    assert_eq!(response.header_map_mut().remove("VIA").len(), 2);
+    assert!(response.header("Via").is_none());
+}
+
+#[test]
+fn test_header_map_builders_and_order() {
+    let request = HttpRequestBuilder::new()
+        .header("Host", "example.com")
+        .header("Accept", "text/html")
+        .header("accept", "application/json")
+        .build();
+    assert_eq!(request.header_all("ACCEPT").len(), 2);
+    let names: Vec<&String> = request.headers().iter().map(|h| h.name()).collect();
+    assert_eq!(names, vec!["Host", "Accept", "accept"]);
+
+    let mut headers: HeaderMap = request.headers().into_iter().cloned().collect();
+    headers.insert("ACCEPT", "*/*");
+    assert_eq!(headers.len(), 2);
+    assert_eq!(headers.get("accept").unwrap().name(), "Accept");
+    assert!(headers.remove("missing").is_empty());
+    assert!(!headers.contains("missing") && headers.contains("host"));
+}
+
+#[test]
+fn test_typed_headers_decode() {
+    let text = "HTTP/1.1 206 Partial Content\r\nContent-Type: Text/HTML; Charset=\"utf-8\"\r\n\
+                Content-Range: bytes 0-99/1234\r\nAllow: GET, HEAD\r\nallow: OPTIONS\r\n\
+                Connection: Keep-Alive\r\nContent-Length: 0\r\n\r\n";
+    let mut reader = Cursor::new(text.as_bytes());
+    let response = HttpParser::from_reader(&mut reader).response().unwrap();
+
+    let content_type = response.typed_header::<ContentType>().unwrap().unwrap();
+    assert_eq!(content_type.mime(), "text/html");
+    assert_eq!(content_type.charset(), Some("utf-8"));
+    let range = response.typed_header::<ContentRange>().unwrap().unwrap();
+    assert_eq!((range.range(), range.length()), (Some((0, 99)), 100));
+    assert_eq!(range.complete_length(), Some(1234));
+    let allow = response.typed_header::<Allow>().unwrap().unwrap();
+    assert_eq!(
+        allow.methods(),
+        &[HttpMethod::Get, HttpMethod::Head, HttpMethod::Options]
+    );
+    assert!(response
+        .typed_header::<Connection>()
+        .unwrap()
+        .unwrap()
+        .is_keep_alive());
+    assert_eq!(response.typed_header::<Host>().unwrap(), None);
+
+    let host = Host::decode(&["[::1]:8080"]).unwrap();
+    assert_eq!((host.host(), host.port()), ("[::1]", Some(8080)));
+    assert!(TransferEncoding::decode(&["gzip", "chunked"])
+        .unwrap()
+        .is_chunked());
+    assert!(!TransferEncoding::decode(&["chunked, gzip"])
+        .unwrap()
+        .is_chunked());
+}
+
+#[test]
+fn test_typed_headers_encode() {
+    let range = Range::bytes(500, None);
+    let content_type = ContentType::new("text/plain").param("charset", "utf-8");
+    let mut request = HttpRequestBuilder::new()
+        .header("Range", "bytes=0-1")
+        .typed_header(&range)
+        .typed_header(&content_type)
+        .build();
+    assert_eq!(request.header_all("range").len(), 1);
+    assert_eq!(
+        request.header("Range").unwrap().value::<String>().unwrap(),
+        "bytes=500-"
+    );
+    assert_eq!(request.typed_header::<Range>().unwrap(), Some(range));
+    assert_eq!(
+        request.typed_header::<ContentType>().unwrap(),
+        Some(content_type)
+    );
+
+    request.put_typed_header(&ContentType::new("text/plain").param("name", "a b"));
+    let value: String = request.header("Content-Type").unwrap().value().unwrap();
+    assert_eq!(value, "text/plain; name=\"a b\"");
+
+    let response = HttpResponseBuilder::new()
+        .status(StatusCode::PARTIAL_CONTENT)
+        .typed_header(&ContentRange::bytes(10, 19, None))
+        .typed_header(&ContentLength::new(10))
+        .body(&[0; 10])
+        .build();
+    let value: String = response.header("content-range").unwrap().value().unwrap();
+    assert_eq!(value, "bytes 10-19/*");
+    assert_eq!(
+        response.typed_header::<ContentLength>().unwrap(),
+        Some(ContentLength::new(10))
+    );
+}
+
+#[test]
+fn test_typed_headers_invalid() {
+    let request = HttpRequestBuilder::new()
+        .header("Content-Length", "10")
+        .header("Content-Length", "12")
+        .header("Range", "bytes=9-2")
+        .header("Content-Type", "text")
+        .build();
+    assert!(matches!(
+        kind(request.typed_header::<ContentLength>()),
+        Err(ParseErrorKind::Header(_))
+    ));
+    assert!(request.typed_header::<Range>().is_err());
+    assert!(request.typed_header::<ContentType>().is_err());
+    assert!(ContentRange::decode(&["bytes 0-10/5"]).is_err());
+    assert!(Allow::decode(&["GET, G@T"]).is_err());
+    assert_eq!(
+        ContentLength::decode(&["5", " 5"]).unwrap(),
+        ContentLength::new(5)
+    );
+}
+
+#[test]
+fn test_http_date_formats() {
+    let expected = HttpDate::from_unix_seconds(784_111_777);
+    for text in [
+        "Sun, 06 Nov 1994 08:49:37 GMT",
+        "Sunday, 06-Nov-94 08:49:37 GMT",
+        "Sun Nov  6 08:49:37 1994",
+    ] {
+        assert_eq!(text.parse::<HttpDate>().unwrap(), expected, "{}", text);
+    }
+    assert_eq!(expected.to_string(), "Sun, 06 Nov 1994 08:49:37 GMT");
+
+    // leap years, the end of the range and single digit days in asctime
+    let date: HttpDate = "Thu, 29 Feb 2024 23:59:59 GMT".parse().unwrap();
+    assert_eq!(date.unix_seconds(), 1_709_251_199);
+    assert_eq!(
+        HttpDate::from_unix_seconds(u64::MAX).to_string(),
+        "Fri, 31 Dec 9999 23:59:59 GMT"
+    );
+    let date: HttpDate = "Thu Jan  1 00:00:00 1970".parse().unwrap();
+    assert_eq!(date.unix_seconds(), 0);
+
+    for text in [
+        "Sun, 6 Nov 1994 08:49:37 GMT",
+        "Sun, 06 Nov 1994 08:49:37 UTC",
+        "Sun, 29 Feb 2023 08:49:37 GMT",
+        "Sun, 06 Nov 1994 24:00:00 GMT",
+        "Sun, 06 nov 1994 08:49:37 GMT",
+        "Sonntag, 06-Nov-94 08:49:37 GMT",
+        "Sun Nov 06 08:49:37 1994 ",
+        "Sun, 06 Nov 1969 08:49:37 GMT",
+        "0",
+    ] {
+        let error = text.parse::<HttpDate>().unwrap_err();
+        assert!(
+            matches!(error.kind(), ParseErrorKind::Header(_)),
+            "{}",
+            text
+        );
+    }
+}
+
+#[test]
+fn test_http_date_system_time_and_headers() {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    let time = UNIX_EPOCH + Duration::from_millis(1_000_000_500);
+    let date = HttpDate::from(time);
+    assert_eq!(date.unix_seconds(), 1_000_000);
+    assert_eq!(
+        SystemTime::from(date),
+        UNIX_EPOCH + Duration::from_secs(1_000_000)
+    );
+    let before_epoch = UNIX_EPOCH - Duration::from_secs(10);
+    assert_eq!(HttpDate::from(before_epoch).unix_seconds(), 0);
+
+    let response = HttpResponseBuilder::new()
+        .typed_header(&LastModified::new(date))
+        .header("Expires", "0")
+        .header("Retry-After", "120")
+        .date()
+        .build();
+    let sent = response.typed_header::<Date>().unwrap().unwrap().date();
+    assert!(sent >= date && sent <= HttpDate::now());
+    let value: String = response.header("Last-Modified").unwrap().value().unwrap();
+    assert_eq!(value, "Mon, 12 Jan 1970 13:46:40 GMT");
+    let expires = response.typed_header::<Expires>().unwrap().unwrap();
+    assert_eq!(expires.date().unix_seconds(), 0);
+    let retry = response.typed_header::<RetryAfter>().unwrap().unwrap();
+    assert_eq!(retry.retry_at(date).unix_seconds(), 1_000_120);
+
+    // a date given to the builder is kept
+    let request = HttpRequestBuilder::new()
+        .header("Date", "Sun, 06 Nov 1994 08:49:37 GMT")
+        .date()
+        .build();
+    assert_eq!(request.header_all("date").len(), 1);
+    let sent = request.typed_header::<Date>().unwrap().unwrap();
+    assert_eq!(sent.date().unix_seconds(), 784_111_777);
+    assert!(HttpRequestBuilder::new().build().header("Date").is_none());
+}
+
+#[test]
+fn test_accept_headers_decode() {
+    let accept = Accept::decode(&[
+        "text/html;level=1;q=0.7;ext=x, TEXT/*;q=0.3",
+        "*/*;q=0.001, application/json",
+    ])
+    .unwrap();
+    let ranges = accept.ranges();
+    assert_eq!(ranges.len(), 4);
+    assert_eq!(
+        ranges[0].params(),
+        &[("level".to_string(), "1".to_string())]
+    );
+    assert_eq!((ranges[0].quality(), ranges[1].mime()), (700, "text/*"));
+    assert_eq!(ranges[2].quality(), 1);
+    assert_eq!(
+        accept.encode(),
+        vec!["text/html;level=1;q=0.7, text/*;q=0.3, */*;q=0.001, application/json"]
+    );
+
+    let languages = AcceptLanguage::decode(&["da, en-GB;q=0.8, en;q=0.7"]).unwrap();
+    assert_eq!(languages.ranges()[1], QualityValue::new("en-GB", 800));
+    assert!(AcceptEncoding::decode(&[""]).unwrap().codings().is_empty());
+    for invalid in [
+        "gzip;q=1.5",
+        "gzip;q=0.0001",
+        "gzip;q",
+        "gzip;q=.5",
+        "gz ip",
+    ] {
+        assert!(AcceptEncoding::decode(&[invalid]).is_err(), "{}", invalid);
+    }
+    assert!(Accept::decode(&["*/html"]).is_err());
+    assert!(AcceptLanguage::decode(&["en_US"]).is_err());
+}
+
+#[test]
+fn test_accept_quality_and_negotiation() {
+    let accept = Accept::new(&[
+        MediaRange::new("text/*", 300),
+        MediaRange::new("text/html", 700),
+        MediaRange::new("text/html", 1000).param("level", 1),
+        MediaRange::new("*/*", 500),
+    ]);
+    assert_eq!(accept.quality("text/html;level=1"), 1000);
+    assert_eq!(accept.quality("text/html;level=2"), 700);
+    assert_eq!(accept.quality("text/plain"), 300);
+    assert_eq!(accept.quality("image/jpeg"), 500);
+    assert_eq!(
+        accept.negotiate(&["text/plain", "image/png"]),
+        Some("image/png")
+    );
+
+    let languages = AcceptLanguage::decode(&["en;q=0.5, en-GB, *;q=0.1"]).unwrap();
+    assert_eq!(languages.quality("en-gb"), 1000);
+    assert_eq!(languages.quality("en-US"), 500);
+    assert_eq!(languages.quality("eng"), 100);
+
+    let encodings = AcceptEncoding::decode(&["gzip;q=0.5, br"]).unwrap();
+    assert_eq!(encodings.negotiate(&["identity", "gzip"]), Some("identity"));
+    assert_eq!(encodings.quality("deflate"), 0);
+    let encodings = AcceptEncoding::decode(&["*;q=0"]).unwrap();
+    assert_eq!(encodings.negotiate(&["gzip", "identity"]), None);
+
+    let charsets = AcceptCharset::decode(&["utf-8, *;q=0.2"]).unwrap();
+    assert_eq!(charsets.negotiate(&["iso-8859-1", "UTF-8"]), Some("UTF-8"));
+}
+
+#[test]
+fn test_request_negotiation_status() {
+    let request = HttpRequestBuilder::new()
+        .header("Accept", "application/json;q=0.9, text/html")
+        .header("Accept-Encoding", "gzip")
+        .header("Accept-Charset", "utf-8;q=x")
+        .build();
+    assert_eq!(
+        request.negotiate_media_type(&["application/json", "text/html"]),
+        Ok("text/html")
+    );
+    assert_eq!(
+        request.negotiate_media_type(&["image/png"]),
+        Err(StatusCode::NOT_ACCEPTABLE)
+    );
+    assert_eq!(
+        request.negotiate_encoding(&["br", "identity"]),
+        Ok("identity")
+    );
+    assert_eq!(request.negotiate_language(&["de", "en"]), Ok("de"));
+    assert_eq!(
+        request.negotiate_charset(&["utf-8"]),
+        Err(StatusCode::BAD_REQUEST)
+    );
+    assert_eq!(
+        request.negotiate_language(&[]),
+        Err(StatusCode::NOT_ACCEPTABLE)
+    );
+}
+
+#[test]
+fn test_range_multiple_and_suffix() {
+    let range = Range::decode(&["bytes=0-0, 5-, -3 ,10-12"]).unwrap();
+    assert_eq!(
+        range.ranges(),
+        &[
+            ByteRange::FromTo(0, 0),
+            ByteRange::From(5),
+            ByteRange::Last(3),
+            ByteRange::FromTo(10, 12)
+        ]
+    );
+    assert_eq!(range.encode(), vec!["bytes=0-0, 5-, -3, 10-12"]);
+    for invalid in [
+        "bytes=",
+        "bytes=5-2",
+        "bytes=-",
+        "items=0-1",
+        "bytes=0-1,,x",
+    ] {
+        assert!(Range::decode(&[invalid]).is_err(), "{}", invalid);
+    }
+
+    // the range past the end is dropped and the others are cut to the length
+    let parts = range.resolve(8).unwrap();
+    assert_eq!(
+        parts,
+        vec![
+            ContentRange::bytes(0, 0, Some(8)),
+            ContentRange::bytes(5, 7, Some(8)),
+            ContentRange::bytes(5, 7, Some(8)),
+        ]
+    );
+    assert_eq!(
+        Range::suffix(100).resolve(8).unwrap()[0].range(),
+        Some((0, 7))
+    );
+    assert_eq!(Range::suffix(0).resolve(8), None);
+    assert_eq!(Range::bytes(0, None).resolve(0), None);
+}
+
+#[test]
+fn test_content_range_unsatisfied() {
+    let unsatisfied = ContentRange::decode(&["bytes */1234"]).unwrap();
+    assert_eq!(unsatisfied, ContentRange::unsatisfied(1234));
+    assert_eq!((unsatisfied.range(), unsatisfied.length()), (None, 0));
+    assert_eq!(unsatisfied.encode(), vec!["bytes */1234"]);
+    assert!(ContentRange::decode(&["bytes */*"]).is_err());
+
+    let response = HttpResponseBuilder::new()
+        .status(StatusCode::REQUESTED_RANGE_NOT_SATISFIABLE)
+        .typed_header(&ContentRange::unsatisfied(10))
+        .build();
+    let error = response
+        .validate_range(&Range::bytes(20, None))
+        .unwrap_err();
+    assert!(matches!(error.kind(), ParseErrorKind::StatusCode(_)));
+}
+
+#[test]
+fn test_validate_partial_response() {
+    let requested = Range::new(&[ByteRange::FromTo(0, 9), ByteRange::FromTo(20, 29)]);
+    let partial = |content_range: ContentRange, body: &[u8]| {
+        HttpResponseBuilder::new()
+            .status(StatusCode::PARTIAL_CONTENT)
+            .typed_header(&content_range)
+            .body(body)
+            .build()
+    };
+    let response = partial(ContentRange::bytes(0, 4, Some(100)), b"01234");
+    assert_eq!(
+        response.validate_range(&requested).unwrap().range(),
+        Some((0, 4))
+    );
+    // coalesced ranges are accepted
+    let response = partial(ContentRange::bytes(0, 29, None), &[b'x'; 30]);
+    assert!(response.validate_range(&requested).is_ok());
+
+    let response = partial(ContentRange::bytes(30, 34, Some(100)), b"01234");
+    let error = response.validate_range(&requested).unwrap_err();
+    assert!(matches!(error.kind(), ParseErrorKind::Header(_)));
+    // parts have to start and end in ranges asked for, not in the gaps between them
+    let response = partial(ContentRange::bytes(12, 15, Some(100)), b"0123");
+    let error = response.validate_range(&requested).unwrap_err();
+    assert!(matches!(error.kind(), ParseErrorKind::Header(_)));
+    let response = partial(ContentRange::bytes(5, 15, Some(100)), &[b'x'; 11]);
+    assert!(response.validate_range(&requested).is_err());
+    let response = partial(ContentRange::bytes(5, 25, Some(100)), &[b'x'; 21]);
+    assert!(response.validate_range(&requested).is_ok());
+    // multipart responses are split with byte_ranges and not validated as a whole
+    let multipart = HttpResponseBuilder::new()
+        .byte_ranges(&[b'x'; 100], &requested.resolve(100).unwrap(), "text/plain")
+        .build();
+    assert!(multipart.validate_range(&requested).is_err());
+    let parts = multipart.byte_ranges().unwrap();
+    assert!(parts
+        .iter()
+        .all(|(content_range, _)| requested.is_satisfied_by(content_range)));
+    let response = partial(ContentRange::bytes(0, 4, Some(100)), b"0123");
+    let error = response.validate_range(&requested).unwrap_err();
+    assert!(matches!(error.kind(), ParseErrorKind::Body(_)));
+    let response = partial(ContentRange::bytes(95, 99, Some(100)), b"56789");
+    assert!(response.validate_range(&Range::suffix(5)).is_ok());
+
+    let missing = HttpResponseBuilder::new()
+        .status(StatusCode::PARTIAL_CONTENT)
+        .build();
+    assert!(missing.validate_range(&requested).is_err());
+
+    // a range whose length does not fit in a u64 is rejected rather than overflowing
+    let hostile = HttpResponseBuilder::new()
+        .status(StatusCode::PARTIAL_CONTENT)
+        .header("Content-Range", "bytes 0-18446744073709551615/*")
+        .body(b"x")
+        .build();
+    let error = hostile
+        .validate_range(&Range::bytes(0, Some(u64::MAX)))
+        .unwrap_err();
+    assert!(matches!(error.kind(), ParseErrorKind::Header(_)));
+    assert_eq!(ContentRange::bytes(0, u64::MAX, None).length(), u64::MAX);
+    assert_eq!(ContentRange::bytes(5, 2, Some(10)).length(), 0);
+    let widest = partial(ContentRange::bytes(1, u64::MAX, None), b"x");
+    let error = widest
+        .validate_range(&Range::bytes(0, Some(u64::MAX)))
+        .unwrap_err();
+    assert!(matches!(error.kind(), ParseErrorKind::Body(_)));
+}
+
+#[test]
+fn test_byte_ranges_builder() {
+    let resource: Vec<u8> = (0..=255).collect();
+    let requested = Range::new(&[
+        ByteRange::FromTo(0, 3),
+        ByteRange::Last(2),
+        ByteRange::From(300),
+    ]);
+    let ranges = requested.resolve(resource.len() as u64).unwrap();
+    let response = HttpResponseBuilder::new()
+        .header("Content-Range", "bytes 0-0/1")
+        .byte_ranges_from(
+            &mut Cursor::new(&resource),
+            &ranges,
+            "application/octet-stream",
+            64,
+        )
+        .unwrap()
+        .build();
+    assert_eq!(response.status_code(), 206);
+    assert!(response.header("Content-Range").is_none());
+    let content_type = response.typed_header::<ContentType>().unwrap().unwrap();
+    assert_eq!(content_type.mime(), "multipart/byteranges");
+    let boundary = content_type.get_param("boundary").unwrap();
+    let body = String::from_utf8_lossy(response.data()).to_string();
+    assert!(body.starts_with(&format!(
+        "--{}\r\nContent-Type: application/octet-stream\r\nContent-Range: bytes 0-3/256\r\n\r\n",
+        boundary
+    )));
+    assert!(body.ends_with(&format!("--{}--\r\n", boundary)));
+    assert_eq!(
+        response.typed_header::<ContentLength>().unwrap(),
+        Some(ContentLength::new(response.data().len() as u64))
+    );
+
+    // the parts survive sending the response
+    let bytes = response.into_bytes();
+    let mut reader = Cursor::new(&bytes);
+    let parsed = HttpParser::from_reader(&mut reader).response().unwrap();
+    let parts = parsed.byte_ranges().unwrap();
+    assert_eq!(parts.len(), 2);
+    assert_eq!(
+        parts[0],
+        (ContentRange::bytes(0, 3, Some(256)), vec![0, 1, 2, 3])
+    );
+    assert_eq!(
+        parts[1],
+        (ContentRange::bytes(254, 255, Some(256)), vec![254, 255])
+    );
+
+    // the ranges are read into memory, up to the size allowed
+    let result = HttpResponseBuilder::new().byte_ranges_from(
+        &mut Cursor::new(&resource),
+        &ranges,
+        "text/plain",
+        5,
+    );
+    assert!(matches!(result, Err(error) if error.kind() == std::io::ErrorKind::InvalidInput));
+}
+
+#[test]
+fn test_byte_ranges_single_and_unsatisfied() {
+    let resource = b"hello world";
+    let response = HttpResponseBuilder::new()
+        .byte_ranges(
+            resource,
+            &[ContentRange::bytes(6, 20, Some(11))],
+            "text/plain",
+        )
+        .build();
+    assert_eq!(response.status_code(), 206);
+    assert_eq!(response.data(), b"world");
+    let value: String = response.header("Content-Range").unwrap().value().unwrap();
+    assert_eq!(value, "bytes 6-10/11");
+    assert_eq!(
+        response.byte_ranges().unwrap(),
+        vec![(ContentRange::bytes(6, 10, Some(11)), b"world".to_vec())]
+    );
+    assert!(response.validate_range(&Range::bytes(6, None)).is_ok());
+
+    let response = HttpResponseBuilder::new()
+        .byte_ranges(
+            resource,
+            &[ContentRange::bytes(11, 20, Some(11))],
+            "text/plain",
+        )
+        .build();
+    assert_eq!(response.status_code(), 416);
+    assert!(response.data().is_empty());
+    assert_eq!(
+        response.typed_header::<ContentRange>().unwrap(),
+        Some(ContentRange::unsatisfied(11))
+    );
+
+    // inverted ranges are left out rather than trusted
+    let response = HttpResponseBuilder::new()
+        .byte_ranges(
+            resource,
+            &[
+                ContentRange::bytes(5, 2, Some(11)),
+                ContentRange::bytes(0, 4, Some(11)),
+            ],
+            "text/plain",
+        )
+        .build();
+    assert_eq!(response.status_code(), 206);
+    assert_eq!(response.data(), b"hello");
+    let response = HttpResponseBuilder::new()
+        .byte_ranges(
+            resource,
+            &[ContentRange::bytes(5, 2, Some(10))],
+            "text/plain",
+        )
+        .build();
+    assert_eq!(response.status_code(), 416);
+}
+
+#[test]
+fn test_byte_ranges_parser() {
+    let body = "preamble\n--THIS_STRING_SEPARATES\nContent-Type: text/plain\nContent-Range: bytes 500-503/1000\n\n\
+                abcd\n--THIS_STRING_SEPARATES  \r\ncontent-range: bytes 7000-7001/8000\r\n\r\n\r\n\r\n\
+                --THIS_STRING_SEPARATES--\r\nepilogue";
+    let response = HttpResponseBuilder::new()
+        .status(StatusCode::PARTIAL_CONTENT)
+        .header(
+            "Content-Type",
+            "multipart/byteranges; boundary=THIS_STRING_SEPARATES",
+        )
+        .body(body.as_bytes())
+        .build();
+    let parts = response.byte_ranges().unwrap();
+    assert_eq!(
+        parts,
+        vec![
+            (ContentRange::bytes(500, 503, Some(1000)), b"abcd".to_vec()),
+            (
+                ContentRange::bytes(7000, 7001, Some(8000)),
+                b"\r\n".to_vec()
+            ),
+        ]
+    );
+
+    let broken = |content_type: &str, body: &str| {
+        HttpResponseBuilder::new()
+            .status(StatusCode::PARTIAL_CONTENT)
+            .header("Content-Type", content_type)
+            .body(body.as_bytes())
+            .build()
+            .byte_ranges()
+    };
+    let missing_range = broken(
+        "multipart/byteranges; boundary=b",
+        "--b\r\n\r\nabc\r\n--b--",
+    );
+    assert!(matches!(
+        kind(missing_range),
+        Err(ParseErrorKind::Header(_))
+    ));
+    let unclosed = broken(
+        "multipart/byteranges; boundary=b",
+        "--b\r\nContent-Range: bytes 0-2/3\r\n\r\nabc",
+    );
+    assert!(matches!(kind(unclosed), Err(ParseErrorKind::Body(_))));
+    assert!(broken("multipart/byteranges", "").is_err());
+    assert!(broken("text/plain", "abc").is_err());
+}
+
+#[test]
+fn test_set_cookie_parse_and_display() {
+    let cookie = Cookie::parse(
+        "sid=38afes7a8; expires=Sun, 06-Nov-1994 08:49:37 GMT; Domain=.Example.com; \
+         path=/docs; secure; HttpOnly; SameSite=Strict; Priority=High",
+    )
+    .unwrap();
+    assert_eq!((cookie.name(), cookie.value()), ("sid", "38afes7a8"));
+    assert_eq!(
+        cookie.expires(),
+        Some(HttpDate::from_unix_seconds(784111777))
+    );
+    assert_eq!(cookie.domain(), Some("example.com"));
+    assert_eq!(cookie.path(), Some("/docs"));
+    assert!(cookie.secure() && cookie.http_only());
+    assert_eq!(cookie.same_site(), Some(SameSite::Strict));
+    assert_eq!(
+        cookie.to_string(),
+        "sid=38afes7a8; Expires=Sun, 06 Nov 1994 08:49:37 GMT; Domain=example.com; \
+         Path=/docs; Secure; HttpOnly; SameSite=Strict"
+    );
+
+    // attributes with values that are not valid are ignored
+    let cookie = Cookie::parse("a=\"b\"; Max-Age=soon; Path=docs; Expires=never").unwrap();
+    assert_eq!(cookie.value(), "\"b\"");
+    assert_eq!(
+        (cookie.max_age(), cookie.path(), cookie.expires()),
+        (None, None, None)
+    );
+    assert_eq!(
+        Cookie::parse("a=b; Max-Age=-1").unwrap().max_age(),
+        Some(-1)
+    );
+    assert!(matches!(
+        kind(Cookie::parse("no pair; Path=/")),
+        Err(ParseErrorKind::Header(_))
+    ));
+    assert!(Cookie::parse("=value").is_err());
+
+    let mut response = HttpResponseBuilder::new()
+        .cookie(&Cookie::new("a", "1"))
+        .cookie(&Cookie::builder("b", "2").max_age(60).build())
+        .header("Set-Cookie", "broken")
+        .build();
+    response.add_cookie(&Cookie::new("c", "3"));
+    assert_eq!(response.header_all("set-cookie").len(), 4);
+    let names: Vec<String> = response
+        .cookies()
+        .iter()
+        .map(|cookie| cookie.name().to_string())
+        .collect();
+    assert_eq!(names, vec!["a", "b", "c"]);
+}
+
+#[test]
+fn test_request_cookies() {
+    let request = HttpRequestBuilder::new()
+        .cookie("theme", "dark")
+        .cookie("sid", "42")
+        .build();
+    assert_eq!(
+        request.header("Cookie"),
+        Some(&HttpHeader::new("Cookie", "theme=dark; sid=42"))
+    );
+    let cookies = request.cookies().unwrap();
+    assert_eq!(cookies.len(), 2);
+    assert_eq!(
+        request
+            .cookie("sid")
+            .map(|cookie| cookie.value().to_string()),
+        Some("42".into())
+    );
+    assert_eq!(request.cookie("lang"), None);
+
+    let request = HttpRequestBuilder::new()
+        .header("Cookie", "a=1; broken")
+        .build();
+    assert!(matches!(
+        kind(request.cookies()),
+        Err(ParseErrorKind::Header(_))
+    ));
+    assert!(HttpRequestBuilder::new()
+        .build()
+        .cookies()
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_cookie_jar() {
+    let mut jar = CookieJar::new();
+    let url = HttpUrl::parse("https://www.example.com/docs/guide/index.html?page=2").unwrap();
+    let response = HttpResponseBuilder::new()
+        .cookie(&Cookie::new("host", "1"))
+        .cookie(
+            &Cookie::builder("wide", "2")
+                .domain("example.com")
+                .path("/")
+                .build(),
+        )
+        .cookie(
+            &Cookie::builder("secure", "3")
+                .path("/docs")
+                .secure(true)
+                .build(),
+        )
+        .cookie(&Cookie::builder("other", "4").domain("other.org").build())
+        .cookie(&Cookie::builder("gone", "5").max_age(0).build())
+        .build();
+    jar.store(&url, &response);
+    assert_eq!(jar.len(), 3);
+
+    // longer paths first, the default path is the directory of the URL
+    assert_eq!(
+        jar.cookie_header(&url),
+        Some("host=1; secure=3; wide=2".to_string())
+    );
+    let sibling = HttpUrl::parse("http://api.example.com/docs").unwrap();
+    assert_eq!(jar.cookie_header(&sibling), Some("wide=2".to_string()));
+    let nearby = HttpUrl::parse("https://www.example.com/docsearch").unwrap();
+    assert_eq!(jar.cookie_header(&nearby), Some("wide=2".to_string()));
+    let unrelated = HttpUrl::parse("http://example.org/").unwrap();
+    assert_eq!(jar.cookie_header(&unrelated), None);
+
+    // same name, domain and path replaces, an expired cookie removes
+    let response = HttpResponseBuilder::new()
+        .cookie(
+            &Cookie::builder("wide", "6")
+                .domain("example.com")
+                .path("/")
+                .build(),
+        )
+        .cookie(
+            &Cookie::builder("host", "")
+                .path("/docs/guide")
+                .expires(HttpDate::from_unix_seconds(0))
+                .build(),
+        )
+        .build();
+    jar.store(&url, &response);
+    assert_eq!(jar.len(), 2);
+    let values: Vec<&str> = jar
+        .cookies_for(&url)
+        .iter()
+        .map(|cookie| cookie.value())
+        .collect();
+    assert_eq!(values, vec!["3", "6"]);
+
+    // secure cookies are not stored from insecure URLs, nor sent to them
+    let insecure = HttpUrl::parse("http://www.example.com/docs/").unwrap();
+    jar.store(
+        &insecure,
+        &HttpResponseBuilder::new()
+            .cookie(&Cookie::builder("s", "7").secure(true).build())
+            .build(),
+    );
+    assert_eq!(jar.cookie_header(&insecure), Some("wide=6".to_string()));
+
+    // cookies for a domain are not sent to addresses
+    let mut jar = CookieJar::new();
+    let address = HttpUrl::parse("http://127.0.0.1/").unwrap();
+    jar.store(
+        &address,
+        &HttpResponseBuilder::new()
+            .cookie(&Cookie::builder("a", "1").domain("0.0.1").build())
+            .cookie(&Cookie::new("b", "2"))
+            .build(),
+    );
+    assert_eq!(jar.cookie_header(&address), Some("b=2".to_string()));
+
+    // a single label domain is too broad, it is dropped unless it is the host itself
+    let mut jar = CookieJar::new();
+    let url = HttpUrl::parse("http://example.com/").unwrap();
+    jar.store(
+        &url,
+        &HttpResponseBuilder::new()
+            .cookie(&Cookie::builder("tld", "1").domain("com").build())
+            .build(),
+    );
+    assert!(jar.is_empty());
+    let local = HttpUrl::parse("http://localhost/").unwrap();
+    jar.store(
+        &local,
+        &HttpResponseBuilder::new()
+            .cookie(&Cookie::builder("dev", "2").domain("localhost").build())
+            .build(),
+    );
+    assert_eq!(jar.cookie_header(&local), Some("dev=2".to_string()));
+    let sub = HttpUrl::parse("http://api.localhost/").unwrap();
+    assert_eq!(jar.cookie_header(&sub), None);
+}
+
+#[test]
+fn test_authorization_basic_and_bearer() {
+    let request = HttpRequestBuilder::new()
+        .basic_auth("Aladdin", "open sesame")
+        .build();
+    assert_eq!(
+        request.header("Authorization"),
+        Some(&HttpHeader::new(
+            "Authorization",
+            "Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ=="
+        ))
+    );
+
+    // passwords may hold colons and any UTF-8, padding is optional
+    let decode = |value: &str| Authorization::decode(&[value]);
+    assert_eq!(
+        decode("basic dGVzdDoxMjPCow==").unwrap(),
+        Authorization::basic("test", "123£")
+    );
+    assert_eq!(
+        decode("Basic dTpwOnE").unwrap(),
+        Authorization::basic("u", "p:q")
+    );
+    for length in 0..6 {
+        let password = &"abcdef"[..length];
+        let encoded = Authorization::basic("user", password).encode();
+        assert_eq!(
+            decode(&encoded[0]).unwrap(),
+            Authorization::basic("user", password)
+        );
+    }
+    assert!(matches!(
+        kind(decode("Basic dXNlcg==")),
+        Err(ParseErrorKind::Header(_))
+    ));
+    assert!(decode("Basic ab!d").is_err());
+    assert!(decode("Basic QQ=").is_err());
+    assert!(decode("Basic").is_err());
+
+    let request = HttpRequestBuilder::new()
+        .bearer_auth("mF_9.B5f-4.1JqM")
+        .build();
+    let credentials = request.typed_header::<Authorization>().unwrap().unwrap();
+    assert_eq!(credentials, Authorization::bearer("mF_9.B5f-4.1JqM"));
+    assert_eq!(credentials.scheme(), "Bearer");
+    assert!(decode("Bearer two tokens").is_err());
+    assert!(decode("Negotiate a87421000492aa874209af8bc028").is_err());
+}
+
+#[test]
+fn test_authenticate_challenges() {
+    let header = WwwAuthenticate::decode(&[
+        "Basic realm=\"simple\", charset=UTF-8",
+        "Bearer realm = \"example\", error=\"invalid_token\", , Negotiate, NTLM TlRMTVNTUAABAAAAB4IIog==",
+    ])
+    .unwrap();
+    let schemes: Vec<&str> = header.challenges().iter().map(Challenge::scheme).collect();
+    assert_eq!(schemes, vec!["Basic", "Bearer", "Negotiate", "NTLM"]);
+    let bearer = header.find("bearer").unwrap();
+    assert_eq!(bearer.realm(), Some("example"));
+    assert_eq!(bearer.get_param("Error"), Some("invalid_token"));
+    assert_eq!(header.challenges()[0].get_param("charset"), Some("UTF-8"));
+    assert!(header.challenges()[2].params().is_empty());
+    assert_eq!(
+        header.challenges()[3].token68(),
+        Some("TlRMTVNTUAABAAAAB4IIog==")
+    );
+
+    let decode = |value: &str| WwwAuthenticate::decode(&[value]);
+    assert!(matches!(
+        kind(decode("realm=\"orphan\"")),
+        Err(ParseErrorKind::Header(_))
+    ));
+    assert!(decode("Basic realm=\"unterminated").is_err());
+    assert!(decode("NTLM abc==, realm=x").is_err());
+    assert!(decode("").is_err());
+
+    let header = ProxyAuthenticate::new(&[
+        Challenge::new("Basic").param("realm", "proxy"),
+        Challenge::new("Custom")
+            .param("Realm", "a \"b\"")
+            .param("hint", "two words"),
+    ]);
+    let response = HttpResponseBuilder::new()
+        .status(StatusCode::PROXY_AUTHENTICATION_REQUIRED)
+        .typed_header(&header)
+        .build();
+    let values: Vec<String> = response
+        .header_all("proxy-authenticate")
+        .iter()
+        .map(|header| header.value().unwrap())
+        .collect();
+    assert_eq!(
+        values,
+        vec![
+            "Basic realm=\"proxy\"",
+            "Custom realm=\"a \\\"b\\\"\", hint=\"two words\""
+        ]
+    );
+    assert_eq!(
+        response.typed_header::<ProxyAuthenticate>().unwrap(),
+        Some(header)
+    );
+}
+
+#[test]
+fn test_digest_hashes() {
+    let md5 = DigestAlgorithm::Md5;
+    assert_eq!(md5.hash(""), "d41d8cd98f00b204e9800998ecf8427e");
+    assert_eq!(
+        md5.hash("The quick brown fox jumps over the lazy dog"),
+        "9e107d9d372bb6826bd81d3542a419d6"
+    );
+    let sha256 = DigestAlgorithm::Sha256;
+    assert_eq!(
+        sha256.hash(""),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    assert_eq!(
+        sha256.hash("abc"),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    // messages whose padding spills into a second block
+    let long = "a".repeat(56);
+    assert_eq!(md5.hash(&long), "3b0c8ac703f828b04c6c197006d17218");
+    assert_eq!(
+        sha256.hash(&long),
+        "b35439a4ac6f0948b6d6f9e3c6af0f5f590ce20f1bde7090ef7970686ec6738a"
+    );
+    assert_eq!(
+        "sha-256-SESS".parse::<DigestAlgorithm>().unwrap(),
+        DigestAlgorithm::Sha256Sess
+    );
+    assert!("SHA-512-256".parse::<DigestAlgorithm>().is_err());
+}
+
+#[test]
+fn test_digest_rfc_7616_vectors() {
+    let challenge = |algorithm: &str| {
+        let header = WwwAuthenticate::decode(&[&format!(
+            "Digest realm=\"http-auth@example.org\", qop=\"auth, auth-int\", \
+             algorithm={}, nonce=\"7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v\", \
+             opaque=\"FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS\"",
+            algorithm
+        )])
+        .unwrap();
+        header.find("Digest").unwrap().clone()
+    };
+    let cnonce = "f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ";
+    let vectors = [
+        ("MD5", "8ca523f5e9506fed4657c9700eebdbec"),
+        (
+            "SHA-256",
+            "753927fa0e85d155564e2e272a28d1802ca10daf4496794697cf8db5856cb6c1",
+        ),
+    ];
+    for (algorithm, response) in vectors {
+        let mut client =
+            DigestClient::new(&challenge(algorithm), "Mufasa", "Circle of Life").unwrap();
+        let authorization =
+            client.authorize_with_cnonce(&HttpMethod::Get, "/dir/index.html", cnonce);
+        let Authorization::Digest(credentials) = &authorization else {
+            panic!("expected Digest credentials");
+        };
+        assert_eq!(credentials.response(), response);
+        assert_eq!(credentials.nonce_count(), Some(1));
+        assert_eq!(
+            credentials.opaque(),
+            Some("FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS")
+        );
+
+        // the server side accepts the same credentials, sent through the header
+        let request = HttpRequestBuilder::new()
+            .path("/dir/index.html")
+            .typed_header(&authorization)
+            .build();
+        let header = request
+            .header("Authorization")
+            .unwrap()
+            .value::<String>()
+            .unwrap();
+        assert!(header.contains("nc=00000001, cnonce=\""));
+        assert!(header.contains(", qop=auth, "));
+        let lookup = |user: &str| (user == "Mufasa").then(|| "Circle of Life".to_string());
+        let verified = request
+            .verify_digest("http-auth@example.org", algorithm.parse().unwrap(), lookup)
+            .unwrap();
+        assert_eq!(&verified, credentials);
+
+        let next = client.authorize_with_cnonce(&HttpMethod::Get, "/dir/index.html", cnonce);
+        let Authorization::Digest(next) = next else {
+            panic!("expected Digest credentials");
+        };
+        assert_eq!(next.nonce_count(), Some(2));
+        assert_ne!(next.response(), response);
+    }
+}
+
+#[test]
+fn test_digest_verification_failures() {
+    let challenge = Challenge::digest("device", "abc123", DigestAlgorithm::Sha256Sess);
+    assert_eq!(
+        challenge.to_string(),
+        "Digest realm=\"device\", qop=\"auth\", algorithm=SHA-256-sess, nonce=\"abc123\""
+    );
+    let mut client = DigestClient::new(&challenge, "admin", "secret").unwrap();
+    let lookup = |user: &str| (user == "admin").then(|| "secret".to_string());
+    let request = HttpRequestBuilder::new()
+        .method(HttpMethod::Post)
+        .path("/config")
+        .digest_auth(&mut client)
+        .build();
+    let sha = DigestAlgorithm::Sha256Sess;
+    assert!(request.verify_digest("device", sha, lookup).is_ok());
+    assert!(matches!(
+        kind(request.verify_digest("other", sha, lookup)),
+        Err(ParseErrorKind::Header(_))
+    ));
+    assert!(request
+        .verify_digest("device", sha, |_| Some("wrong".to_string()))
+        .is_err());
+    assert!(request.verify_digest("device", sha, |_| None).is_err());
+
+    // a client answering a SHA-256 challenge with MD5 is turned down
+    let downgraded = Challenge::digest("device", "abc123", DigestAlgorithm::Md5Sess);
+    let mut client = DigestClient::new(&downgraded, "admin", "secret").unwrap();
+    let md5 = HttpRequestBuilder::new()
+        .method(HttpMethod::Post)
+        .path("/config")
+        .digest_auth(&mut client)
+        .build();
+    assert!(md5.verify_digest("device", sha, lookup).is_err());
+
+    // the credentials are bound to the method and the target
+    let authorization = request.header("Authorization").unwrap();
+    let replayed = HttpRequestBuilder::new()
+        .method(HttpMethod::Get)
+        .path("/config")
+        .header("Authorization", authorization.value::<String>().unwrap())
+        .build();
+    assert!(replayed.verify_digest("device", sha, lookup).is_err());
+
+    let basic = HttpRequestBuilder::new()
+        .basic_auth("admin", "secret")
+        .build();
+    assert!(basic.verify_digest("device", sha, lookup).is_err());
+    assert!(HttpRequestBuilder::new()
+        .build()
+        .verify_digest("device", sha, lookup)
+        .is_err());
+
+    // RFC 2069 servers send no qop, the response then leaves out the counts
+    let legacy = Challenge::new("Digest")
+        .param("realm", "old")
+        .param("nonce", "n");
+    let mut client = DigestClient::new(&legacy, "admin", "secret").unwrap();
+    let request = HttpRequestBuilder::new().digest_auth(&mut client).build();
+    let Some(Authorization::Digest(sent)) = request.typed_header::<Authorization>().unwrap() else {
+        panic!("expected Digest credentials");
+    };
+    assert_eq!((sent.qop(), sent.nonce_count()), (None, None));
+    assert_eq!(sent.algorithm(), DigestAlgorithm::Md5);
+    // without a nonce count replays cannot be detected, the verifier turns them down
+    assert!(matches!(
+        kind(request.verify_digest("old", DigestAlgorithm::Md5, lookup)),
+        Err(ParseErrorKind::Header(_))
+    ));
+
+    assert!(DigestClient::new(&Challenge::new("Basic"), "a", "b").is_err());
+    let integrity_only = Challenge::new("Digest")
+        .param("realm", "r")
+        .param("nonce", "n")
+        .param("qop", "auth-int");
+    assert!(DigestClient::new(&integrity_only, "a", "b").is_err());
+    let no_nonce = Challenge::new("Digest").param("realm", "r");
+    assert!(DigestClient::new(&no_nonce, "a", "b").is_err());
+    assert!(Authorization::decode(&["Digest username=\"a\", realm=\"r\""]).is_err());
+    let with_count = |count: &str| {
+        Authorization::decode(&[&format!(
+            "Digest username=\"a\", realm=\"r\", nonce=\"n\", uri=\"/\", response=\"0\", \
+             qop=auth, cnonce=\"c\", nc={}",
+            count
+        )])
+    };
+    assert!(with_count("0000000a").is_ok());
+    assert!(with_count("+0000001").is_err());
+    assert!(with_count("-0000001").is_err());
+    assert!(with_count("0x000001").is_err());
+    assert!(with_count("1").is_err());
+}
+
+#[test]
+fn test_etag_and_condition_headers() {
+    let etag = ETag::decode(&["\"xyzzy\""]).unwrap();
+    assert_eq!((etag.tag(), etag.is_weak()), ("xyzzy", false));
+    assert_eq!(ETag::decode(&["W/\"\""]).unwrap(), ETag::weak(""));
+    assert!(matches!(
+        kind(ETag::decode(&["xyzzy"])),
+        Err(ParseErrorKind::Header(_))
+    ));
+    assert!(ETag::decode(&["\"a\"b\""]).is_err());
+    assert!(ETag::decode(&["w/\"a\""]).is_err());
+
+    // the comparison table of RFC 9110 section 8.8.3.2
+    let (weak_one, weak_two) = (ETag::weak("1"), ETag::weak("2"));
+    let strong_one = ETag::strong("1");
+    assert!(!weak_one.strong_eq(&weak_one) && weak_one.weak_eq(&weak_one));
+    assert!(!weak_one.strong_eq(&weak_two) && !weak_one.weak_eq(&weak_two));
+    assert!(!weak_one.strong_eq(&strong_one) && weak_one.weak_eq(&strong_one));
+    assert!(strong_one.strong_eq(&strong_one));
+
+    let if_match = IfMatch::decode(&["\"a,b\", W/\"c\"", "\"d\""]).unwrap();
+    assert_eq!(
+        if_match,
+        IfMatch::Tags(vec![
+            ETag::strong("a,b"),
+            ETag::weak("c"),
+            ETag::strong("d")
+        ])
+    );
+    assert_eq!(if_match.encode(), vec!["\"a,b\", W/\"c\", \"d\""]);
+    assert_eq!(IfNoneMatch::decode(&[" * "]).unwrap(), IfNoneMatch::Any);
+    assert!(IfNoneMatch::decode(&["*, \"a\""]).is_err());
+    assert!(IfMatch::decode(&[""]).is_err());
+
+    let date = HttpDate::from_unix_seconds(784111777);
+    let request = HttpRequestBuilder::new()
+        .header(H_IF_MODIFIED_SINCE, "Sun, 06 Nov 1994 08:49:37 GMT")
+        .typed_header(&IfUnmodifiedSince::new(date))
+        .build();
+    assert_eq!(H_IF_MODIFIED_SINCE, "If-Modified-Since");
+    assert_eq!(
+        request.typed_header::<IfModifiedSince>().unwrap(),
+        Some(IfModifiedSince::new(date))
+    );
+    assert_eq!(
+        request.header("If-Unmodified-Since"),
+        Some(&HttpHeader::new(
+            "If-Unmodified-Since",
+            "Sun, 06 Nov 1994 08:49:37 GMT"
+        ))
+    );
+    assert_eq!(
+        IfRange::decode(&["W/\"a\""]).unwrap(),
+        IfRange::ETag(ETag::weak("a"))
+    );
+    assert_eq!(
+        IfRange::decode(&["Sunday, 06-Nov-94 08:49:37 GMT"]).unwrap(),
+        IfRange::Date(date)
+    );
+    assert!(IfRange::decode(&["yesterday"]).is_err());
+}
+
+#[test]
+fn test_evaluate_preconditions() {
+    let modified = HttpDate::from_unix_seconds(1_000_000);
+    let (before, after) = (
+        HttpDate::from_unix_seconds(999_999),
+        HttpDate::from_unix_seconds(1_000_001),
+    );
+    let validators = Validators::new(Some(ETag::strong("v2")), Some(modified));
+    let (modified_text, before_text, after_text) =
+        (modified.to_string(), before.to_string(), after.to_string());
+    let evaluate = |method: HttpMethod, headers: &[(&str, &str)]| {
+        let mut builder = HttpRequestBuilder::new().method(method);
+        for (name, value) in headers {
+            builder = builder.header(name, value);
+        }
+        builder.build().evaluate_preconditions(&validators)
+    };
+    let get = |headers: &[(&str, &str)]| evaluate(HttpMethod::Get, headers);
+    let put = |headers: &[(&str, &str)]| evaluate(HttpMethod::Put, headers);
+
+    assert_eq!(get(&[]), Precondition::Proceed);
+
+    // If-Match uses strong comparison
+    assert_eq!(put(&[("If-Match", "\"v2\"")]), Precondition::Proceed);
+    assert_eq!(put(&[("If-Match", "W/\"v2\"")]), Precondition::Failed);
+    assert_eq!(put(&[("If-Match", "*")]), Precondition::Proceed);
+    // an If-Match that cannot be parsed fails rather than letting the change through
+    assert_eq!(put(&[("If-Match", "v2")]), Precondition::Failed);
+    // If-Match takes precedence over If-Unmodified-Since
+    let stale = ("If-Unmodified-Since", before_text.as_str());
+    assert_eq!(put(&[stale]), Precondition::Failed);
+    assert_eq!(put(&[("If-Match", "\"v2\""), stale]), Precondition::Proceed);
+    assert_eq!(
+        put(&[("If-Unmodified-Since", &modified_text)]),
+        Precondition::Proceed
+    );
+
+    // If-None-Match uses weak comparison, 304 for reads and 412 otherwise
+    let cached = ("If-None-Match", "\"v1\", W/\"v2\"");
+    assert_eq!(get(&[cached]), Precondition::NotModified);
+    assert_eq!(
+        evaluate(HttpMethod::Head, &[cached]),
+        Precondition::NotModified
+    );
+    assert_eq!(put(&[cached]), Precondition::Failed);
+    assert_eq!(get(&[("If-None-Match", "\"v1\"")]), Precondition::Proceed);
+    // a failed If-Match comes before a matching If-None-Match
+    assert_eq!(get(&[("If-Match", "\"v1\""), cached]), Precondition::Failed);
+
+    // If-Modified-Since is for reads only, and ignored along with If-None-Match
+    assert_eq!(
+        get(&[("If-Modified-Since", &modified_text)]),
+        Precondition::NotModified
+    );
+    assert_eq!(
+        get(&[("If-Modified-Since", &after_text)]),
+        Precondition::NotModified
+    );
+    assert_eq!(
+        get(&[("If-Modified-Since", &before_text)]),
+        Precondition::Proceed
+    );
+    assert_eq!(
+        put(&[("If-Modified-Since", &modified_text)]),
+        Precondition::Proceed
+    );
+    assert_eq!(
+        get(&[
+            ("If-None-Match", "\"v1\""),
+            ("If-Modified-Since", &modified_text)
+        ]),
+        Precondition::Proceed
+    );
+    assert_eq!(
+        get(&[("If-Modified-Since", "not a date")]),
+        Precondition::Proceed
+    );
+    assert_eq!(
+        Precondition::NotModified.status_code(),
+        Some(StatusCode::NOT_MODIFIED)
+    );
+    let failed = HttpResponseBuilder::new()
+        .status(Precondition::Failed.status_code().unwrap())
+        .build();
+    assert_eq!(failed.status_msg(), "Precondition Failed");
+
+    // `*` tells apart resources that exist from those that do not
+    let create = HttpRequestBuilder::new()
+        .method(HttpMethod::Put)
+        .header("If-None-Match", "*")
+        .build();
+    assert_eq!(
+        create.evaluate_preconditions(&Validators::missing()),
+        Precondition::Proceed
+    );
+    assert_eq!(
+        create.evaluate_preconditions(&Validators::new(None, None)),
+        Precondition::Failed
+    );
+    let update = HttpRequestBuilder::new()
+        .method(HttpMethod::Put)
+        .header("If-Match", "*")
+        .build();
+    assert_eq!(
+        update.evaluate_preconditions(&Validators::missing()),
+        Precondition::Failed
+    );
+}
+
+#[test]
+fn test_if_range() {
+    let modified = HttpDate::from_unix_seconds(1_000_000);
+    let validators = Validators::new(Some(ETag::strong("v2")), Some(modified));
+    let allows = |if_range: Option<String>| {
+        let mut builder = HttpRequestBuilder::new().typed_header(&Range::bytes(0, Some(9)));
+        if let Some(if_range) = if_range {
+            builder = builder.header("If-Range", if_range);
+        }
+        builder.build().if_range_allows(&validators)
+    };
+    assert!(allows(None));
+    assert!(allows(Some("\"v2\"".to_string())));
+    assert!(!allows(Some("\"v1\"".to_string())));
+    assert!(!allows(Some("W/\"v2\"".to_string())));
+    assert!(allows(Some(modified.to_string())));
+    assert!(!allows(Some(
+        HttpDate::from_unix_seconds(1_000_001).to_string()
+    )));
+    assert!(!allows(Some("garbage".to_string())));
+
+    let request = HttpRequestBuilder::new()
+        .typed_header(&IfRange::Date(modified))
+        .build();
+    assert!(!request.if_range_allows(&Validators::new(Some(ETag::strong("v2")), None)));
+}
@@ -0,0 +1,571 @@
+use std::fmt::Display;
+
+use crate::{
+    borrowed::{is_token, next_parameter},
+    target::split_authority,
+    types::{quote_if_needed, HttpParseError, ParseErrorKind},
+    HttpDate, HttpMethod, H_ALLOW, H_CONNECTION, H_CONTENT_LENGTH, H_CONTENT_TYPE, H_DATE,
+    H_EXPIRES, H_HOST, H_LAST_MODIFIED, H_LOCATION, H_RETRY_AFTER, H_TRANSFER_ENCODING,
+};
+
+/// A header whose value is decoded into a type of its own.
+///
+/// Headers that can be repeated are decoded from all their values at once, in order.
+///
+/// # Example:
+/// ```rust
+/// use http_parse::{ContentLength, ContentType, HttpRequestBuilder};
+///
+/// let mut request = HttpRequestBuilder::new()
+///     .header("Content-Type", "text/html; charset=\"UTF-8\"")
+///     .typed_header(&ContentLength::new(42))
+///     .build();
+/// let content_type = request.typed_header::<ContentType>().unwrap().unwrap();
+/// assert_eq!(content_type.mime(), "text/html");
+/// assert_eq!(content_type.charset(), Some("UTF-8"));
+///
+/// request.put_typed_header(&ContentLength::new(7));
+/// let length = request.typed_header::<ContentLength>().unwrap();
+/// assert_eq!(length, Some(ContentLength::new(7)));
+/// ```
+pub trait TypedHeader: Sized {
+    /// The name of the header.
+    fn name() -> &'static str;
+
+    /// Decode the header from every value it was sent with.
+    ///
+    /// # Errors:
+    /// When the values are not formatted properly.
+    fn decode(values: &[&str]) -> Result<Self, HttpParseError>;
+
+    /// Encode the header into the values to send, usually a single one.
+    fn encode(&self) -> Vec<String>;
+}
+
+pub(crate) fn invalid<T: TypedHeader>(values: &[&str]) -> HttpParseError {
+    ParseErrorKind::Header(format!("Invalid {} `{}`", T::name(), values.join(", "))).into()
+}
+
+/// The value of a header sent once, repeating the same value is tolerated.
+pub(crate) fn single<'a, T: TypedHeader>(values: &[&'a str]) -> Result<&'a str, HttpParseError> {
+    match values.split_first() {
+        Some((first, rest)) if rest.iter().all(|value| value.trim() == first.trim()) => {
+            Ok(first.trim())
+        }
+        _ => Err(invalid::<T>(values)),
+    }
+}
+
+/// The elements of a comma separated list header, across all its values.
+///
+/// Commas inside quoted strings do not separate elements.
+pub(crate) fn list<'a>(values: &[&'a str]) -> Vec<&'a str> {
+    let mut elements = Vec::new();
+    for value in values {
+        let (mut start, mut quoted, mut escaped) = (0, false, false);
+        for (index, byte) in value.bytes().enumerate() {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' if quoted => escaped = true,
+                b'"' => quoted = !quoted,
+                b',' if !quoted => {
+                    elements.push(value[start..index].trim());
+                    start = index + 1;
+                }
+                _ => {}
+            }
+        }
+        elements.push(value[start..].trim());
+    }
+    elements.retain(|element| !element.is_empty());
+    elements
+}
+
+/// `Content-Type`, the media type of the body and its parameters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContentType {
+    pub(crate) mime: String,
+    pub(crate) params: Vec<(String, String)>,
+}
+
+impl ContentType {
+    /// Create a content type from a media type, such as `text/html`.
+    pub fn new(mime: &str) -> Self {
+        Self {
+            mime: mime.to_ascii_lowercase(),
+            params: Vec::new(),
+        }
+    }
+
+    /// Add a parameter, such as `charset`.
+    pub fn param<T: Display>(mut self, name: &str, value: T) -> Self {
+        self.params
+            .push((name.to_ascii_lowercase(), value.to_string()));
+        self
+    }
+
+    /// The media type, in lower case.
+    pub fn mime(&self) -> &str {
+        &self.mime
+    }
+
+    /// The value of a parameter, names are not case sensitive.
+    pub fn get_param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(param, _)| param.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The `charset` parameter.
+    pub fn charset(&self) -> Option<&str> {
+        self.get_param("charset")
+    }
+}
+
+impl TypedHeader for ContentType {
+    fn name() -> &'static str {
+        H_CONTENT_TYPE
+    }
+
+    fn decode(values: &[&str]) -> Result<Self, HttpParseError> {
+        let value = single::<Self>(values)?;
+        // the parameters keep their leading `;`
+        let (mime, params) = value.split_at(value.find(';').unwrap_or(value.len()));
+        let valid_mime = mime.trim().split_once('/').is_some_and(|(kind, subtype)| {
+            is_token(kind.as_bytes()) && is_token(subtype.as_bytes())
+        });
+        if !valid_mime {
+            return Err(invalid::<Self>(values));
+        }
+        let mut content_type = Self::new(mime.trim());
+        let mut params = params.as_bytes().trim_ascii();
+        while !params.is_empty() {
+            let (name, value) =
+                next_parameter(&mut params).ok_or_else(|| invalid::<Self>(values))?;
+            let value = value.ok_or_else(|| invalid::<Self>(values))?;
+            content_type = content_type.param(&name, value);
+            params = params.trim_ascii_start();
+        }
+        Ok(content_type)
+    }
+
+    fn encode(&self) -> Vec<String> {
+        let mut value = self.mime.clone();
+        for (name, param) in &self.params {
+            value.push_str(&format!("; {}={}", name, quote_if_needed(param)));
+        }
+        vec![value]
+    }
+}
+
+/// `Content-Length`, the length of the body in bytes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContentLength(pub(crate) u64);
+
+impl ContentLength {
+    /// Create a content length of `length` bytes.
+    pub fn new(length: u64) -> Self {
+        Self(length)
+    }
+
+    /// The length in bytes.
+    pub fn length(&self) -> u64 {
+        self.0
+    }
+}
+
+impl TypedHeader for ContentLength {
+    fn name() -> &'static str {
+        H_CONTENT_LENGTH
+    }
+
+    fn decode(values: &[&str]) -> Result<Self, HttpParseError> {
+        let value = single::<Self>(values)?;
+        if value.is_empty() || !value.bytes().all(|byte| byte.is_ascii_digit()) {
+            return Err(invalid::<Self>(values));
+        }
+        value.parse().map(Self).map_err(|_| invalid::<Self>(values))
+    }
+
+    fn encode(&self) -> Vec<String> {
+        vec![self.0.to_string()]
+    }
+}
+
+pub(crate) fn parse_number(text: &str) -> Option<u64> {
+    if text.is_empty() || !text.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok()
+}
+
+/// `Host`, the server and port a request is sent to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Host {
+    pub(crate) host: String,
+    pub(crate) port: Option<u16>,
+}
+
+impl Host {
+    /// Create a host, with the port given if any.
+    pub fn new(host: &str, port: Option<u16>) -> Self {
+        Self {
+            host: host.to_string(),
+            port,
+        }
+    }
+
+    /// The name or address of the server, IPv6 addresses keep their brackets.
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// The port, when one was given.
+    pub fn port(&self) -> Option<u16> {
+        self.port
+    }
+}
+
+impl TypedHeader for Host {
+    fn name() -> &'static str {
+        H_HOST
+    }
+
+    fn decode(values: &[&str]) -> Result<Self, HttpParseError> {
+        let value = single::<Self>(values)?;
+        match split_authority(value) {
+            Some((host, port)) if !value.contains('@') => Ok(Self::new(host, port)),
+            _ => Err(invalid::<Self>(values)),
+        }
+    }
+
+    fn encode(&self) -> Vec<String> {
+        match self.port {
+            Some(port) => vec![format!("{}:{}", self.host, port)],
+            None => vec![self.host.clone()],
+        }
+    }
+}
+
+/// `Connection`, the options of the current connection, such as `close` or `keep-alive`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Connection(pub(crate) Vec<String>);
+
+impl Connection {
+    /// Create the header from its options.
+    pub fn new(options: &[&str]) -> Self {
+        Self(options.iter().map(|option| option.to_string()).collect())
+    }
+
+    /// The options, as they were sent.
+    pub fn options(&self) -> &[String] {
+        &self.0
+    }
+
+    /// Whether the option is present, options are not case sensitive.
+    pub fn contains(&self, option: &str) -> bool {
+        self.0
+            .iter()
+            .any(|value| value.eq_ignore_ascii_case(option))
+    }
+
+    /// Whether the connection closes after this message.
+    pub fn is_close(&self) -> bool {
+        self.contains("close")
+    }
+
+    /// Whether the connection is asked to stay open after this message.
+    pub fn is_keep_alive(&self) -> bool {
+        self.contains("keep-alive")
+    }
+}
+
+impl TypedHeader for Connection {
+    fn name() -> &'static str {
+        H_CONNECTION
+    }
+
+    fn decode(values: &[&str]) -> Result<Self, HttpParseError> {
+        let options = list(values);
+        if options.iter().any(|option| !is_token(option.as_bytes())) {
+            return Err(invalid::<Self>(values));
+        }
+        Ok(Self::new(&options))
+    }
+
+    fn encode(&self) -> Vec<String> {
+        vec![self.0.join(", ")]
+    }
+}
+
+/// `Transfer-Encoding`, the codings applied to the body to send it, in order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransferEncoding(pub(crate) Vec<String>);
+
+impl TransferEncoding {
+    /// Create the header from its codings, in the order they were applied.
+    pub fn new(codings: &[&str]) -> Self {
+        Self(codings.iter().map(|coding| coding.to_string()).collect())
+    }
+
+    /// The `chunked` coding alone.
+    pub fn chunked() -> Self {
+        Self::new(&["chunked"])
+    }
+
+    /// The codings, in the order they were applied.
+    pub fn codings(&self) -> &[String] {
+        &self.0
+    }
+
+    /// Whether the body is chunked, which must be the last coding applied.
+    pub fn is_chunked(&self) -> bool {
+        self.0
+            .last()
+            .is_some_and(|coding| coding.eq_ignore_ascii_case("chunked"))
+    }
+}
+
+impl TypedHeader for TransferEncoding {
+    fn name() -> &'static str {
+        H_TRANSFER_ENCODING
+    }
+
+    fn decode(values: &[&str]) -> Result<Self, HttpParseError> {
+        // codings may carry parameters, only their names are kept
+        let codings: Vec<&str> = list(values)
+            .into_iter()
+            .map(|coding| coding.split(';').next().unwrap_or_default().trim())
+            .collect();
+        if codings.is_empty() || codings.iter().any(|coding| !is_token(coding.as_bytes())) {
+            return Err(invalid::<Self>(values));
+        }
+        Ok(Self::new(&codings))
+    }
+
+    fn encode(&self) -> Vec<String> {
+        vec![self.0.join(", ")]
+    }
+}
+
+/// `Allow`, the methods a resource supports.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Allow(pub(crate) Vec<HttpMethod>);
+
+impl Allow {
+    /// Create the header from the methods allowed.
+    pub fn new(methods: &[HttpMethod]) -> Self {
+        Self(methods.to_vec())
+    }
+
+    /// The methods allowed.
+    pub fn methods(&self) -> &[HttpMethod] {
+        &self.0
+    }
+
+    /// Whether `method` is allowed.
+    pub fn allows(&self, method: &HttpMethod) -> bool {
+        self.0.contains(method)
+    }
+}
+
+impl TypedHeader for Allow {
+    fn name() -> &'static str {
+        H_ALLOW
+    }
+
+    /// An empty `Allow` is valid, the resource allows no method.
+    fn decode(values: &[&str]) -> Result<Self, HttpParseError> {
+        let methods = list(values)
+            .into_iter()
+            .map(str::parse)
+            .collect::<Result<Vec<HttpMethod>, _>>()
+            .map_err(|_| invalid::<Self>(values))?;
+        Ok(Self(methods))
+    }
+
+    fn encode(&self) -> Vec<String> {
+        let methods: Vec<String> = self.0.iter().map(HttpMethod::to_string).collect();
+        vec![methods.join(", ")]
+    }
+}
+
+/// `Location`, the URI a redirect or a created resource points to.
+///
+/// The URI may be relative to the request target.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Location(pub(crate) String);
+
+impl Location {
+    /// Create the header from a URI reference.
+    pub fn new(uri: &str) -> Self {
+        Self(uri.to_string())
+    }
+
+    /// The URI, as it was sent.
+    pub fn uri(&self) -> &str {
+        &self.0
+    }
+}
+
+impl TypedHeader for Location {
+    fn name() -> &'static str {
+        H_LOCATION
+    }
+
+    fn decode(values: &[&str]) -> Result<Self, HttpParseError> {
+        let value = single::<Self>(values)?;
+        if value.is_empty() || !value.bytes().all(|byte| byte.is_ascii_graphic()) {
+            return Err(invalid::<Self>(values));
+        }
+        Ok(Self::new(value))
+    }
+
+    fn encode(&self) -> Vec<String> {
+        vec![self.0.clone()]
+    }
+}
+
+/// `Date`, when the message was sent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Date(pub(crate) HttpDate);
+
+impl Date {
+    /// Create the header from a date.
+    pub fn new(date: HttpDate) -> Self {
+        Self(date)
+    }
+
+    /// The header for the current date.
+    pub fn now() -> Self {
+        Self(HttpDate::now())
+    }
+
+    /// The date the message was sent.
+    pub fn date(&self) -> HttpDate {
+        self.0
+    }
+}
+
+impl TypedHeader for Date {
+    fn name() -> &'static str {
+        H_DATE
+    }
+
+    fn decode(values: &[&str]) -> Result<Self, HttpParseError> {
+        let value = single::<Self>(values)?;
+        value.parse().map(Self)
+    }
+
+    fn encode(&self) -> Vec<String> {
+        vec![self.0.to_string()]
+    }
+}
+
+/// `Last-Modified`, when the origin server believes the representation last changed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LastModified(pub(crate) HttpDate);
+
+impl LastModified {
+    /// Create the header from a date.
+    pub fn new(date: HttpDate) -> Self {
+        Self(date)
+    }
+
+    /// The date the representation last changed.
+    pub fn date(&self) -> HttpDate {
+        self.0
+    }
+}
+
+impl TypedHeader for LastModified {
+    fn name() -> &'static str {
+        H_LAST_MODIFIED
+    }
+
+    fn decode(values: &[&str]) -> Result<Self, HttpParseError> {
+        let value = single::<Self>(values)?;
+        value.parse().map(Self)
+    }
+
+    fn encode(&self) -> Vec<String> {
+        vec![self.0.to_string()]
+    }
+}
+
+/// `Expires`, the date after which a response is stale.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Expires(pub(crate) HttpDate);
+
+impl Expires {
+    /// Create the header from a date.
+    pub fn new(date: HttpDate) -> Self {
+        Self(date)
+    }
+
+    /// The date after which the response is stale.
+    pub fn date(&self) -> HttpDate {
+        self.0
+    }
+}
+
+impl TypedHeader for Expires {
+    fn name() -> &'static str {
+        H_EXPIRES
+    }
+
+    /// A date that is not valid, such as `0`, means the response is already stale, it is
+    /// decoded as the UNIX epoch.
+    fn decode(values: &[&str]) -> Result<Self, HttpParseError> {
+        let value = single::<Self>(values)?;
+        let date = value.parse().unwrap_or(HttpDate::from_unix_seconds(0));
+        Ok(Self(date))
+    }
+
+    fn encode(&self) -> Vec<String> {
+        vec![self.0.to_string()]
+    }
+}
+
+/// `Retry-After`, how long to wait before making another request.
+///
+/// Given either as a date or as a number of seconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RetryAfter {
+    Date(HttpDate),
+    Delay(u64),
+}
+
+impl RetryAfter {
+    /// The date after which to retry, a delay is counted from `now`.
+    pub fn retry_at(&self, now: HttpDate) -> HttpDate {
+        match self {
+            RetryAfter::Date(date) => *date,
+            RetryAfter::Delay(seconds) => {
+                HttpDate::from_unix_seconds(now.unix_seconds().saturating_add(*seconds))
+            }
+        }
+    }
+}
+
+impl TypedHeader for RetryAfter {
+    fn name() -> &'static str {
+        H_RETRY_AFTER
+    }
+
+    fn decode(values: &[&str]) -> Result<Self, HttpParseError> {
+        let value = single::<Self>(values)?;
+        match parse_number(value) {
+            Some(seconds) => Ok(RetryAfter::Delay(seconds)),
+            None => value.parse().map(RetryAfter::Date),
+        }
+    }
+
+    fn encode(&self) -> Vec<String> {
+        match self {
+            RetryAfter::Date(date) => vec![date.to_string()],
+            RetryAfter::Delay(seconds) => vec![seconds.to_string()],
+        }
+    }
+}
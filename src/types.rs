@@ -5,10 +5,15 @@ use std::{
 };
 
 use crate::{
-    StatusCode, DEFAULT_HTTPS_PORT, DEFAULT_HTTP_PORT, H_CONTENT_LENGTH, H_TRANSFER_ENCODING,
+    typed_headers::Date, HeaderMap, RequestTarget, StatusCode, TypedHeader, DEFAULT_HTTPS_PORT,
+    DEFAULT_HTTP_PORT, H_CONTENT_LENGTH, H_DATE, H_HOST, H_TRANSFER_ENCODING,
 };
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+/// A HTTP request method.
+///
+/// Methods without a variant of their own, registered or not, are kept as `Extension`.
+/// Parse methods with `HttpMethod::from_str` so the common methods get their variant.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum HttpMethod {
     Post,
     Get,
@@ -19,6 +24,122 @@ pub enum HttpMethod {
     Patch,
     Delete,
     Connect,
+    Extension(String),
+}
+
+/// The methods in the IANA HTTP Method Registry without a variant of their own,
+/// along with whether they are safe and idempotent.
+const REGISTERED_METHODS: &[(&str, bool, bool)] = &[
+    ("ACL", false, true),
+    ("BASELINE-CONTROL", false, true),
+    ("BIND", false, true),
+    ("CHECKIN", false, true),
+    ("CHECKOUT", false, true),
+    ("COPY", false, true),
+    ("LABEL", false, true),
+    ("LINK", false, true),
+    ("LOCK", false, false),
+    ("MERGE", false, true),
+    ("MKACTIVITY", false, true),
+    ("MKCALENDAR", false, true),
+    ("MKCOL", false, true),
+    ("MKREDIRECTREF", false, true),
+    ("MKWORKSPACE", false, true),
+    ("MOVE", false, true),
+    ("ORDERPATCH", false, true),
+    ("PRI", true, true),
+    ("PROPFIND", true, true),
+    ("PROPPATCH", false, true),
+    ("QUERY", true, true),
+    ("REBIND", false, true),
+    ("REPORT", true, true),
+    ("SEARCH", true, true),
+    ("UNBIND", false, true),
+    ("UNCHECKOUT", false, true),
+    ("UNLINK", false, true),
+    ("UNLOCK", false, true),
+    ("UPDATE", false, true),
+    ("UPDATEREDIRECTREF", false, true),
+    ("VERSION-CONTROL", false, true),
+];
+
+impl HttpMethod {
+    /// Whether this method is in the IANA HTTP Method Registry.
+    pub fn is_registered(&self) -> bool {
+        match self {
+            HttpMethod::Extension(_) => self.registration().is_some(),
+            _ => true,
+        }
+    }
+
+    /// Whether this method is safe, it is essentially read-only on the server.
+    ///
+    /// Unregistered extension methods are assumed not to be safe.
+    pub fn is_safe(&self) -> bool {
+        match self {
+            HttpMethod::Get | HttpMethod::Head | HttpMethod::Options | HttpMethod::Trace => true,
+            HttpMethod::Extension(_) => self.registration().is_some_and(|(_, safe, _)| safe),
+            _ => false,
+        }
+    }
+
+    /// Whether sending a request with this method several times has the same effect as
+    /// sending it once, such requests can be retried automatically.
+    ///
+    /// Unregistered extension methods are assumed not to be idempotent.
+    pub fn is_idempotent(&self) -> bool {
+        match self {
+            HttpMethod::Post | HttpMethod::Patch | HttpMethod::Connect => false,
+            HttpMethod::Extension(_) => self
+                .registration()
+                .is_some_and(|(_, _, idempotent)| idempotent),
+            _ => true,
+        }
+    }
+
+    /// Whether responses to this method can be stored by a cache.
+    ///
+    /// Responses to `POST` and `QUERY` are only reused when their headers allow it explicitly.
+    pub fn is_cacheable(&self) -> bool {
+        match self {
+            HttpMethod::Get | HttpMethod::Head | HttpMethod::Post => true,
+            HttpMethod::Extension(name) => name == "QUERY",
+            _ => false,
+        }
+    }
+
+    fn registration(&self) -> Option<(&'static str, bool, bool)> {
+        let HttpMethod::Extension(name) = self else {
+            return None;
+        };
+        REGISTERED_METHODS
+            .iter()
+            .find(|(registered, _, _)| registered == name)
+            .copied()
+    }
+}
+
+impl FromStr for HttpMethod {
+    type Err = HttpParseError;
+
+    /// Parse a method, methods are case sensitive and must be a token.
+    fn from_str(method: &str) -> Result<Self, Self::Err> {
+        match method {
+            "GET" => Ok(HttpMethod::Get),
+            "POST" => Ok(HttpMethod::Post),
+            "PUT" => Ok(HttpMethod::Put),
+            "HEAD" => Ok(HttpMethod::Head),
+            "OPTIONS" => Ok(HttpMethod::Options),
+            "PATCH" => Ok(HttpMethod::Patch),
+            "DELETE" => Ok(HttpMethod::Delete),
+            "TRACE" => Ok(HttpMethod::Trace),
+            "CONNECT" => Ok(HttpMethod::Connect),
+            method if crate::borrowed::is_token(method.as_bytes()) => {
+                Ok(HttpMethod::Extension(method.to_string()))
+            }
+            method => Err(ParseErrorKind::Method(method.to_string()).into()),
+        }
+    }
 }
 
 impl Display for HttpMethod {
@@ -33,6 +154,7 @@ impl Display for HttpMethod {
             HttpMethod::Patch => write!(f, "PATCH"),
             HttpMethod::Delete => write!(f, "DELETE"),
             HttpMethod::Connect => write!(f, "CONNECT"),
+            HttpMethod::Extension(method) => write!(f, "{}", method),
         }
     }
 }
@@ -74,7 +196,7 @@ impl HttpHeader {
 }
 impl PartialEq for HttpHeader {
     fn eq(&self, other: &Self) -> bool {
-        self.name.to_lowercase().eq(&other.name.to_lowercase()) && self.value == other.value
+        self.name.eq_ignore_ascii_case(&other.name) && self.value == other.value
     }
 }
 impl Display for HttpHeader {
@@ -83,6 +205,102 @@ impl Display for HttpHeader {
     }
 }
 
+/// An extension sent along with the size of a chunk in a chunked body, `name` or `name=value`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpChunkExtension {
+    pub(crate) name: String,
+    pub(crate) value: Option<String>,
+}
+
+impl HttpChunkExtension {
+    /// Create a new chunk extension from a name and an optional value.
+    pub fn new<T: Display>(name: T, value: Option<T>) -> Self {
+        Self {
+            name: name.to_string(),
+            value: value.map(|value| value.to_string()),
+        }
+    }
+
+    /// The name of this extension.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The value of this extension, already unquoted.
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+}
+
+impl Display for HttpChunkExtension {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+        match &self.value {
+            Some(value) => write!(f, "={}", quote_if_needed(value)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Write `value` as a token when it is one, otherwise as a quoted string.
+pub(crate) fn quote_if_needed(value: &str) -> String {
+    if crate::borrowed::is_token(value.as_bytes()) {
+        value.to_string()
+    } else {
+        let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+        format!("\"{}\"", escaped)
+    }
+}
+
+/// Write a chunked body, along with its chunk extensions and trailer fields.
+fn write_chunked(
+    bytes: &mut Vec<u8>,
+    body: &[u8],
+    chunks: &[(usize, usize)],
+    extensions: &[Vec<HttpChunkExtension>],
+    trailers: &[HttpHeader],
+) {
+    for (index, (start, end)) in chunks.iter().enumerate() {
+        let count = end - start;
+        bytes.extend_from_slice(format!("{:X}", count).as_bytes());
+        for extension in extensions.get(index).into_iter().flatten() {
+            bytes.extend_from_slice(format!(";{}", extension).as_bytes());
+        }
+        bytes.extend_from_slice(b"\r\n");
+        bytes.extend_from_slice(&body[*start..*end]);
+        if count == 0 {
+            for trailer in trailers {
+                bytes.extend_from_slice(format!("{}\r\n", trailer).as_bytes());
+            }
+        }
+        bytes.push(b'\r');
+        bytes.push(b'\n');
+    }
+}
+
+/// Lay out a built body as a single chunk when there are trailers to send after it,
+/// replacing `Content-Length` with `Transfer-Encoding: chunked`.
+fn chunks_for_trailers(
+    body: &[u8],
+    trailers: &[HttpHeader],
+    headers: &mut HeaderMap,
+) -> Vec<(usize, usize)> {
+    if trailers.is_empty() {
+        return Vec::new();
+    }
+    headers.remove(H_CONTENT_LENGTH);
+    if !headers.contains(H_TRANSFER_ENCODING) {
+        headers.append(H_TRANSFER_ENCODING, "chunked");
+    }
+    let mut chunks = Vec::new();
+    if !body.is_empty() {
+        chunks.push((0, body.len()));
+    }
+    // last chunk 0 data
+    chunks.push((0, 0));
+    chunks
+}
+
 /// A HTTP Version struct. Can be HTTP1.1, HTTP2, HTTP3
 #[derive(PartialEq, Clone, Copy, Debug)]
 pub enum HttpVersion {
@@ -110,14 +328,16 @@ impl Display for HttpVersion {
 /// `Version`
 /// `body`
 /// `chunks` (in the case of Chunk requests)
-#[derive(PartialEq)]
+#[derive(PartialEq, Debug)]
 pub struct HttpRequest {
     pub(crate) method: HttpMethod,
     pub(crate) url: String,
     pub(crate) version: HttpVersion,
-    pub(crate) headers: Vec<HttpHeader>,
+    pub(crate) headers: HeaderMap,
     pub(crate) body: Vec<u8>,
     pub(crate) chunks: Vec<(usize, usize)>,
+    pub(crate) extensions: Vec<Vec<HttpChunkExtension>>,
+    pub(crate) trailers: Vec<HttpHeader>,
     pub(crate) chunked: bool,
 }
 
@@ -134,11 +354,13 @@ impl HttpRequest {
     pub fn new() -> Self {
         Self {
             version: HttpVersion::Http11,
-            headers: Vec::new(),
+            headers: HeaderMap::new(),
             body: Vec::new(),
             method: HttpMethod::Get,
-            url: "\\".to_string(),
+            url: "/".to_string(),
             chunks: Vec::new(),
+            extensions: Vec::new(),
+            trailers: Vec::new(),
             chunked: false,
         }
     }
@@ -162,6 +384,17 @@ impl HttpRequest {
         &self.body
     }
 
+    /// Get the trailer fields sent after a chunked body of this request.
+    pub fn trailers(&self) -> Vec<&HttpHeader> {
+        self.trailers.iter().collect()
+    }
+
+    /// Get the extensions sent along with the chunk at `index` of a chunked body,
+    /// the last (empty) chunk included.
+    pub fn chunk_extensions(&self, index: usize) -> &[HttpChunkExtension] {
+        self.extensions.get(index).map_or(&[], Vec::as_slice)
+    }
+
     /// Get the version portion of this request.
     pub fn version(&self) -> HttpVersion {
         self.version
@@ -172,6 +405,31 @@ impl HttpRequest {
         self.headers.iter().collect()
     }
 
+    /// The headers of this request, see [`HeaderMap`].
+    pub fn header_map(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// The headers of this request for modification, see [`HeaderMap`].
+    pub fn header_map_mut(&mut self) -> &mut HeaderMap {
+        &mut self.headers
+    }
+
+    /// Decode a header into its type, such as [`ContentLength`](crate::ContentLength).
+    ///
+    /// Returns `None` when the header is absent.
+    ///
+    /// # Errors:
+    /// When the header values are not formatted properly.
+    pub fn typed_header<T: TypedHeader>(&self) -> Result<Option<T>, HttpParseError> {
+        self.headers.typed()
+    }
+
+    /// Set a typed header, replacing every header with its name.
+    pub fn put_typed_header<T: TypedHeader>(&mut self, header: &T) {
+        self.headers.insert_typed(header);
+    }
+
     /// Retrieve the value for a header with the give name.
     /// `name` the header being searched.
     ///
@@ -180,12 +438,12 @@ impl HttpRequest {
     where
         T: AsRef<str>,
     {
-        self.headers.iter().find(|header| {
-            header
-                .name
-                .to_lowercase()
-                .eq(&name.as_ref().to_lowercase().to_string())
-        })
+        self.headers.get(name.as_ref())
+    }
+
+    /// Retrieve every header with the given name, for headers that can be repeated.
+    pub fn header_all(&self, name: &str) -> Vec<&HttpHeader> {
+        self.headers.get_all(name)
     }
 
     /// Ad a header to this request.
@@ -199,18 +457,17 @@ impl HttpRequest {
     where
         T: Display,
     {
-        if let Some(index) = self
-            .headers
-            .iter()
-            .position(|header| header.name.to_lowercase().eq(&name.to_lowercase()))
-        {
-            self.headers[index].value = format!("{}", value);
-        } else {
-            self.headers.push(HttpHeader {
-                name: name.to_string(),
-                value: format!("{}", value),
-            });
-        }
+        self.headers.insert(name, value);
+    }
+
+    /// Add a header, keeping any header with the same name already present.
+    ///
+    /// Use it for headers that can be repeated, such as `Via` or `Set-Cookie`.
+    pub fn append_header<T>(&mut self, name: &str, value: T)
+    where
+        T: Display,
+    {
+        self.headers.append(name, value);
     }
 
     /// Removes a header from this request if it exists.
@@ -219,13 +476,34 @@ impl HttpRequest {
     /// `name` name for the header to search adn remove.
     /// The name is not case sensitive as the protocol specifies.
     pub fn remove_header(&mut self, name: &str) {
-        self.headers
-            .retain(|header| header.name.to_lowercase().ne(&name.to_lowercase()));
+        self.headers.remove(name);
     }
 
     /// Retrieve the method for this request.
     pub fn method(&self) -> HttpMethod {
-        self.method
+        self.method.clone()
+    }
+
+    /// Retrieve the raw target of this request, as sent on the request line.
+    pub fn raw_target(&self) -> &str {
+        &self.url
+    }
+
+    /// Retrieve the target of this request, see [`RequestTarget`].
+    ///
+    /// # Errors:
+    /// When the target is not one of the forms allowed for the method of this request.
+    pub fn target(&self) -> Result<RequestTarget, HttpParseError> {
+        RequestTarget::parse(&self.url, &self.method)
+    }
+
+    /// Retrieve the URL this request refers to, built from its target and `Host` header.
+    ///
+    /// # Errors:
+    /// When the target is invalid or the URL needs a `Host` header that is missing.
+    pub fn url(&self) -> Result<HttpUrl, HttpParseError> {
+        let host = self.header(H_HOST).map(|header| header.value.as_str());
+        self.target()?.to_url(host)
     }
 
     /// Convert this request into a byte vector.
@@ -244,19 +522,16 @@ impl HttpRequest {
         bytes.push(b'\n');
 
         // next the body
-        if !self.body.is_empty() {
-            if self.chunked {
-                for (start, end) in &self.chunks {
-                    let count = end - start;
-                    bytes.extend_from_slice(format!("{:X}\r\n", count).as_bytes());
-                    bytes.extend_from_slice(&self.body[*start..*end]);
-
-                    bytes.push(b'\r');
-                    bytes.push(b'\n');
-                }
-            } else {
-                bytes.extend_from_slice(&self.body);
-            }
+        if self.chunked {
+            write_chunked(
+                &mut bytes,
+                &self.body,
+                &self.chunks,
+                &self.extensions,
+                &self.trailers,
+            );
+        } else {
+            bytes.extend_from_slice(&self.body);
         }
         bytes
     }
@@ -274,20 +549,15 @@ impl Display for HttpRequest {
         });
         write!(f, "\r\n")?;
         if self.chunked {
-            for (start, end) in &self.chunks {
-                let count = end - start;
-                write!(f, "{:X}\r\n", count)?;
-                write!(
-                    f,
-                    "{}\r\n",
-                    String::from_utf8_lossy(&self.body[*start..*end])
-                )?;
-
-                if count == 0 {
-                    write!(f, "\r\n\r\n")?;
-                }
-            }
-            Ok(())
+            let mut bytes = Vec::new();
+            write_chunked(
+                &mut bytes,
+                &self.body,
+                &self.chunks,
+                &self.extensions,
+                &self.trailers,
+            );
+            write!(f, "{}", String::from_utf8_lossy(&bytes))
         } else {
             write!(f, "{}", String::from_utf8_lossy(&self.body))
         }
@@ -302,15 +572,18 @@ impl Display for HttpRequest {
 /// `Status Message`
 /// `body`
 /// `chunks` (in the case of Chunk requests)
-#[derive(PartialEq)]
+#[derive(PartialEq, Debug)]
 pub struct HttpResponse {
     pub(crate) version: HttpVersion,
     pub(crate) status_code: usize,
     pub(crate) status_msg: String,
-    pub(crate) headers: Vec<HttpHeader>,
+    pub(crate) headers: HeaderMap,
     pub(crate) body: Vec<u8>,
     pub(crate) chunks: Vec<(usize, usize)>,
+    pub(crate) extensions: Vec<Vec<HttpChunkExtension>>,
+    pub(crate) trailers: Vec<HttpHeader>,
     pub(crate) chunked: bool,
+    pub(crate) interim: Vec<HttpResponse>,
 }
 
 impl Default for HttpResponse {
@@ -330,10 +603,13 @@ impl HttpResponse {
             version: HttpVersion::Http11,
             status_code: 200,
             status_msg: "Ok".to_string(),
-            headers: Vec::new(),
+            headers: HeaderMap::new(),
             body: Vec::new(),
             chunks: Vec::new(),
+            extensions: Vec::new(),
+            trailers: Vec::new(),
             chunked: false,
+            interim: Vec::new(),
         }
     }
 
@@ -356,6 +632,17 @@ impl HttpResponse {
         &self.body
     }
 
+    /// Retrieve the trailer fields sent after a chunked body of this response.
+    pub fn trailers(&self) -> Vec<&HttpHeader> {
+        self.trailers.iter().collect()
+    }
+
+    /// Retrieve the extensions sent along with the chunk at `index` of a chunked body,
+    /// the last (empty) chunk included.
+    pub fn chunk_extensions(&self, index: usize) -> &[HttpChunkExtension] {
+        self.extensions.get(index).map_or(&[], Vec::as_slice)
+    }
+
     /// Retrieve the version currently set in this response.
     pub fn version(&self) -> HttpVersion {
         self.version
@@ -366,6 +653,13 @@ impl HttpResponse {
         self.status_code
     }
 
+    /// Retrieve the interim (`1xx`) responses received ahead of this response, in order.
+    ///
+    /// For example `103 Early Hints` responses with `Link` headers for resources to preload.
+    pub fn interim(&self) -> Vec<&HttpResponse> {
+        self.interim.iter().collect()
+    }
+
     /// Retrieve status message string passed on the HTTP response.
     pub fn status_msg(&self) -> String {
         self.status_msg.clone()
@@ -379,9 +673,12 @@ impl HttpResponse {
     /// `name` name for the header being added.
     /// `value` value for the header being added. It must implement Display so it can be turned into a string.
     pub fn header(&self, name: &str) -> Option<&HttpHeader> {
-        self.headers
-            .iter()
-            .find(|header| header.name.to_lowercase().eq(&name.to_lowercase()))
+        self.headers.get(name)
+    }
+
+    /// Retrieve every header with the given name, for headers that can be repeated.
+    pub fn header_all(&self, name: &str) -> Vec<&HttpHeader> {
+        self.headers.get_all(name)
     }
 
     /// Removes a header from this response if it exists.
@@ -393,18 +690,17 @@ impl HttpResponse {
     where
         T: Display,
     {
-        if let Some(index) = self
-            .headers
-            .iter()
-            .position(|header| header.name.to_lowercase().eq(&name.to_lowercase()))
-        {
-            self.headers[index].value = format!("{}", value);
-        } else {
-            self.headers.push(HttpHeader {
-                name: name.to_string(),
-                value: format!("{}", value),
-            });
-        }
+        self.headers.insert(name, value);
+    }
+
+    /// Add a header, keeping any header with the same name already present.
+    ///
+    /// Use it for headers that can be repeated, such as `Via` or `Set-Cookie`.
+    pub fn append_header<T>(&mut self, name: &str, value: T)
+    where
+        T: Display,
+    {
+        self.headers.append(name, value);
     }
 
     /// Retrieve the value for a header with the give name.
@@ -415,6 +711,31 @@ impl HttpResponse {
         self.headers.iter().collect()
     }
 
+    /// The headers of this response, see [`HeaderMap`].
+    pub fn header_map(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// The headers of this response for modification, see [`HeaderMap`].
+    pub fn header_map_mut(&mut self) -> &mut HeaderMap {
+        &mut self.headers
+    }
+
+    /// Decode a header into its type, such as [`ContentLength`](crate::ContentLength).
+    ///
+    /// Returns `None` when the header is absent.
+    ///
+    /// # Errors:
+    /// When the header values are not formatted properly.
+    pub fn typed_header<T: TypedHeader>(&self) -> Result<Option<T>, HttpParseError> {
+        self.headers.typed()
+    }
+
+    /// Set a typed header, replacing every header with its name.
+    pub fn put_typed_header<T: TypedHeader>(&mut self, header: &T) {
+        self.headers.insert_typed(header);
+    }
+
     /// Convert this response into a byte vector.
     /// Useful when transmitting a request across a communication medium.
     pub fn into_bytes(&self) -> Vec<u8> {
@@ -437,19 +758,16 @@ impl HttpResponse {
         bytes.push(b'\n');
 
         // next the body
-        if !self.body.is_empty() {
-            if self.chunked {
-                for (start, end) in &self.chunks {
-                    let count = end - start;
-                    bytes.extend_from_slice(format!("{:X}\r\n", count).as_bytes());
-                    bytes.extend_from_slice(&self.body[*start..*end]);
-
-                    bytes.push(b'\r');
-                    bytes.push(b'\n');
-                }
-            } else {
-                bytes.extend_from_slice(&self.body);
-            }
+        if self.chunked {
+            write_chunked(
+                &mut bytes,
+                &self.body,
+                &self.chunks,
+                &self.extensions,
+                &self.trailers,
+            );
+        } else {
+            bytes.extend_from_slice(&self.body);
         }
         bytes
     }
@@ -473,20 +791,15 @@ impl Display for HttpResponse {
         write!(f, "\r\n")?;
 
         if self.chunked {
-            for (start, end) in &self.chunks {
-                let count = end - start;
-                write!(f, "{:X}\r\n", count)?;
-                write!(
-                    f,
-                    "{}\r\n",
-                    String::from_utf8_lossy(&self.body[*start..*end])
-                )?;
-
-                if count == 0 {
-                    write!(f, "\r\n")?;
-                }
-            }
-            Ok(())
+            let mut bytes = Vec::new();
+            write_chunked(
+                &mut bytes,
+                &self.body,
+                &self.chunks,
+                &self.extensions,
+                &self.trailers,
+            );
+            write!(f, "{}", String::from_utf8_lossy(&bytes))
         } else {
             write!(f, "{}", String::from_utf8_lossy(&self.body))
         }
@@ -500,7 +813,7 @@ impl Display for HttpResponse {
 /// # Example
 /// ```no_run
 ///   use http_parse::HttpResponseBuilder;
-
+///
 ///   let mut response = HttpResponseBuilder::new()
 ///         .header("Content-Type", "text/plain")
 ///         .header("Content-Length", 11)
@@ -508,10 +821,12 @@ impl Display for HttpResponse {
 /// ```
 pub struct HttpResponseBuilder {
     version: Option<HttpVersion>,
-    status_code: Option<StatusCode>,
-    headers: Option<Vec<HttpHeader>>,
-    data: Option<Vec<u8>>,
+    pub(crate) status_code: Option<StatusCode>,
+    pub(crate) headers: Option<HeaderMap>,
+    pub(crate) data: Option<Vec<u8>>,
     chunks: Option<Vec<(usize, usize)>>,
+    trailers: Option<Vec<HttpHeader>>,
+    date: bool,
 }
 
 impl Default for HttpResponseBuilder {
@@ -533,6 +848,8 @@ impl HttpResponseBuilder {
             headers: None,
             data: None,
             chunks: None,
+            trailers: None,
+            date: false,
         }
     }
 
@@ -552,8 +869,23 @@ impl HttpResponseBuilder {
     where
         T: Display,
     {
-        let headers = self.headers.get_or_insert(Vec::new());
-        headers.push(HttpHeader::new(name, value));
+        let headers = self.headers.get_or_insert_with(HeaderMap::new);
+        headers.append(name, value);
+        self
+    }
+
+    /// Set a typed header, replacing any header with its name added before.
+    pub fn typed_header<T: TypedHeader>(mut self, header: &T) -> Self {
+        let headers = self.headers.get_or_insert_with(HeaderMap::new);
+        headers.insert_typed(header);
+        self
+    }
+
+    /// Set the `Date` header to the time this response is built.
+    ///
+    /// A `Date` header added to the builder is kept as is.
+    pub fn date(mut self) -> Self {
+        self.date = true;
         self
     }
 
@@ -567,6 +899,22 @@ impl HttpResponseBuilder {
         self
     }
 
+    /// Add a trailer field to this HTTP Response, sent after the body.
+    /// Trailers require a chunked body, so a response with trailers is built with
+    /// `Transfer-Encoding: chunked` in place of `Content-Length`.
+    ///
+    /// # Arguments
+    /// `name`  name of the trailer being added.
+    /// `value` value being added for this trailer. Must have a type that implements `std::fmt::Display`
+    pub fn trailer<T>(mut self, name: &str, value: T) -> Self
+    where
+        T: Display,
+    {
+        let trailers = self.trailers.get_or_insert(Vec::new());
+        trailers.push(HttpHeader::new(name, value));
+        self
+    }
+
     /// Construct a response from the given data.
     ///
     /// # Example
@@ -581,15 +929,23 @@ impl HttpResponseBuilder {
         let version = self.version.unwrap();
         let status = self.status_code.unwrap();
         let body = self.data.unwrap_or_default();
-        let headers = self.headers.unwrap_or_default();
+        let mut headers = self.headers.unwrap_or_default();
+        let trailers = self.trailers.unwrap_or_default();
+        if self.date && !headers.contains(H_DATE) {
+            headers.insert_typed(&Date::now());
+        }
+        let chunks = chunks_for_trailers(&body, &trailers, &mut headers);
         HttpResponse {
             version,
             status_code: status.0,
             status_msg: status.1.to_string(),
             body,
             headers,
-            chunks: Vec::new(),
-            chunked: false,
+            chunked: !chunks.is_empty(),
+            chunks,
+            extensions: Vec::new(),
+            trailers,
+            interim: Vec::new(),
         }
     }
 }
@@ -612,12 +968,14 @@ impl HttpResponseBuilder {
 /// // code here
 /// ```
 pub struct HttpRequestBuilder {
-    method: Option<HttpMethod>,
-    url: Option<String>,
+    pub(crate) method: Option<HttpMethod>,
+    pub(crate) url: Option<String>,
     version: Option<HttpVersion>,
-    headers: Option<Vec<HttpHeader>>,
+    pub(crate) headers: Option<HeaderMap>,
     data: Option<Vec<u8>>,
     chunks: Option<Vec<(usize, usize)>>,
+    trailers: Option<Vec<HttpHeader>>,
+    date: bool,
 }
 
 impl Default for HttpRequestBuilder {
@@ -641,6 +999,8 @@ impl HttpRequestBuilder {
             headers: None,
             data: None,
             chunks: None,
+            trailers: None,
+            date: false,
         }
     }
 
@@ -660,8 +1020,23 @@ impl HttpRequestBuilder {
     where
         T: std::fmt::Display,
     {
-        let headers = self.headers.get_or_insert(Vec::new());
-        headers.push(HttpHeader::new(name, value));
+        let headers = self.headers.get_or_insert_with(HeaderMap::new);
+        headers.append(name, value);
+        self
+    }
+
+    /// Set a typed header, replacing any header with its name added before.
+    pub fn typed_header<T: TypedHeader>(mut self, header: &T) -> Self {
+        let headers = self.headers.get_or_insert_with(HeaderMap::new);
+        headers.insert_typed(header);
+        self
+    }
+
+    /// Set the `Date` header to the time this request is built.
+    ///
+    /// A `Date` header added to the builder is kept as is.
+    pub fn date(mut self) -> Self {
+        self.date = true;
         self
     }
 
@@ -675,6 +1050,22 @@ impl HttpRequestBuilder {
         self
     }
 
+    /// Add a trailer field to this HTTP Request, sent after the body.
+    /// Trailers require a chunked body, so a request with trailers is built with
+    /// `Transfer-Encoding: chunked` in place of `Content-Length`.
+    ///
+    /// # Arguments
+    /// `name`  name of the trailer being added.
+    /// `value` value being added for this trailer. Must have a type that implements `std::fmt::Display`
+    pub fn trailer<T>(mut self, name: &str, value: T) -> Self
+    where
+        T: Display,
+    {
+        let trailers = self.trailers.get_or_insert(Vec::new());
+        trailers.push(HttpHeader::new(name, value));
+        self
+    }
+
     // Add a path to this HTTP Request.
     /// See also [`crate::HttpRequestBuilder::url`] method but can be given a string rather than [HttpUrl].
     ///
@@ -723,14 +1114,21 @@ impl HttpRequestBuilder {
         let version = self.version.unwrap();
         let method = self.method.unwrap();
         let body = self.data.unwrap_or_default();
-        let headers = self.headers.unwrap_or_default();
+        let mut headers = self.headers.unwrap_or_default();
+        let trailers = self.trailers.unwrap_or_default();
+        if self.date && !headers.contains(H_DATE) {
+            headers.insert_typed(&Date::now());
+        }
+        let chunks = chunks_for_trailers(&body, &trailers, &mut headers);
         let url = self.url.unwrap();
         HttpRequest {
             version,
             body,
             headers,
-            chunks: Vec::new(),
-            chunked: false,
+            chunked: !chunks.is_empty(),
+            chunks,
+            extensions: Vec::new(),
+            trailers,
             method,
             url: url.to_string(),
         }
@@ -1020,55 +1418,258 @@ impl Default for HttpUrlBuilder {
     }
 }
 
+/// An error produced while parsing a HTTP message, see [`ParseErrorKind`] for what went wrong.
+///
+/// Along with its kind an error carries where it was found, as a byte offset from the
+/// start of the message and a line number, when they are known. Errors produced by the
+/// underlying reader keep the original `std::io::Error` as their source.
+///
+/// # Example:
+/// ```rust
+/// use http_parse::{HttpRequestRef, ParseErrorKind, StatusCode};
+///
+/// let error = HttpRequestRef::parse(b"GET / HTTP/1.1\r\nHost: a\r\nContent-Length: x\r\n\r\n")
+///     .unwrap_err();
+/// assert_eq!(error.kind(), &ParseErrorKind::InvalidContentLength("x".to_string()));
+/// assert_eq!(error.suggested_status(), StatusCode::BAD_REQUEST);
+/// ```
 #[derive(Debug)]
-pub enum HttpParseError {
+pub struct HttpParseError {
+    kind: ParseErrorKind,
+    offset: Option<usize>,
+    line: Option<usize>,
+    source: Option<std::io::Error>,
+}
+
+impl HttpParseError {
+    /// What went wrong.
+    pub fn kind(&self) -> &ParseErrorKind {
+        &self.kind
+    }
+
+    /// The offset in bytes from the start of the message at which the error was found.
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    /// The line of the message, counting from 1, on which the error was found.
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    /// The kind of the I/O error behind this error, when reading the stream failed.
+    pub fn io_error_kind(&self) -> Option<std::io::ErrorKind> {
+        self.source.as_ref().map(std::io::Error::kind)
+    }
+
+    /// Whether the reader had no bytes ready, as a non-blocking socket reports with
+    /// `std::io::ErrorKind::WouldBlock`.
+    ///
+    /// Nothing read so far is lost, parse again with the same parser once the reader is ready
+    /// and it continues where it stopped.
+    pub fn is_would_block(&self) -> bool {
+        self.kind == ParseErrorKind::Io(std::io::ErrorKind::WouldBlock)
+    }
+
+    /// The status code a server should reply with when a request fails with this error.
+    pub fn suggested_status(&self) -> StatusCode {
+        match self.kind {
+            ParseErrorKind::StartLineTooLong(_) => StatusCode::REQUEST_URI_TOO_LARGE,
+            ParseErrorKind::TooManyHeaders(_) | ParseErrorKind::HeaderTooLarge(_) => {
+                StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE
+            }
+            ParseErrorKind::BodyTooLarge(_) => StatusCode::REQUEST_ENTITY_TOO_LARGE,
+            ParseErrorKind::Version(_) => StatusCode::HTTP_VERSION_NOT_SUPPORTED,
+            ParseErrorKind::Io(_) if self.io_error_kind() == Some(std::io::ErrorKind::TimedOut) => {
+                StatusCode::REQUEST_TIME_OUT
+            }
+            _ => StatusCode::BAD_REQUEST,
+        }
+    }
+
+    /// Record where the error was found, unless a more precise location is known already.
+    pub(crate) fn at(mut self, offset: usize, line: Option<usize>) -> Self {
+        if self.offset.is_none() {
+            self.offset = Some(offset);
+            self.line = line;
+        }
+        self
+    }
+
+    /// Record where the error was found in `buffer`, counting the lines ahead of `offset`.
+    pub(crate) fn within(self, buffer: &[u8], offset: usize) -> Self {
+        let line = line_number(buffer, offset);
+        self.at(offset, Some(line))
+    }
+
+    /// Move the location of the error by `offset` bytes and `lines` lines.
+    pub(crate) fn shifted(mut self, offset: usize, lines: usize) -> Self {
+        self.offset = self.offset.map(|position| position + offset);
+        self.line = self.line.map(|line| line + lines);
+        self
+    }
+}
+
+/// The line of `buffer`, counting from 1, that holds the byte at `offset`.
+pub(crate) fn line_number(buffer: &[u8], offset: usize) -> usize {
+    let end = offset.min(buffer.len());
+    buffer[..end].iter().filter(|byte| **byte == b'\n').count() + 1
+}
+
+impl From<ParseErrorKind> for HttpParseError {
+    fn from(kind: ParseErrorKind) -> Self {
+        Self {
+            kind,
+            offset: None,
+            line: None,
+            source: None,
+        }
+    }
+}
+
+/// The kinds of [`HttpParseError`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
     Method(String),
     Version(String),
     Url(String),
     StatusCode(String),
     Header(String),
-    Other(String),
+    Body(String),
+    StartLineTooLong(usize),
+    TooManyHeaders(usize),
+    HeaderTooLarge(usize),
+    BodyTooLarge(usize),
+    TooManyInterimResponses(usize),
+    InvalidHeaderName(String),
+    InvalidHeaderValue(String),
+    MissingColon(String),
+    WhitespaceBeforeColon(String),
+    ObsoleteLineFolding(String),
+    BareLineFeed,
+    ConflictingFraming,
+    InvalidContentLength(String),
+    ConflictingContentLength(String),
+    ChunkedNotFinal(String),
+    InvalidTransferEncoding(String),
+    UnexpectedEof,
+    Io(std::io::ErrorKind),
 }
 
-impl Display for HttpParseError {
+impl Display for ParseErrorKind {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            HttpParseError::Method(value) => write!(f, "Unsupported HTTP Method `{}`", value),
-            HttpParseError::Version(value) => write!(f, "Unsupported HTTP Version `{}`", value),
-            HttpParseError::Url(value) => write!(f, "Invalid HTTP URL `{}`", value),
-            HttpParseError::StatusCode(value) => write!(f, "Invalid HTTP Status Code `{}`", value),
-            HttpParseError::Header(value) => write!(f, "Error reading header `{}`", value),
-            HttpParseError::Other(value) => write!(f, "Read error: `{}`", value),
+            ParseErrorKind::Method(value) => write!(f, "Unsupported HTTP Method `{}`", value),
+            ParseErrorKind::Version(value) => write!(f, "Unsupported HTTP Version `{}`", value),
+            ParseErrorKind::Url(value) => write!(f, "Invalid HTTP URL `{}`", value),
+            ParseErrorKind::StatusCode(value) => write!(f, "Invalid HTTP Status Code `{}`", value),
+            ParseErrorKind::Header(value) => write!(f, "Error reading header `{}`", value),
+            ParseErrorKind::Body(value) => write!(f, "Error reading body `{}`", value),
+            ParseErrorKind::StartLineTooLong(limit) => {
+                write!(f, "Start line is longer than {} bytes", limit)
+            }
+            ParseErrorKind::TooManyHeaders(limit) => {
+                write!(f, "Message has more than {} headers", limit)
+            }
+            ParseErrorKind::HeaderTooLarge(limit) => {
+                write!(f, "Header is longer than {} bytes", limit)
+            }
+            ParseErrorKind::BodyTooLarge(limit) => {
+                write!(f, "Body is larger than {} bytes", limit)
+            }
+            ParseErrorKind::TooManyInterimResponses(limit) => {
+                write!(
+                    f,
+                    "More than {} interim responses ahead of the final one",
+                    limit
+                )
+            }
+            ParseErrorKind::InvalidHeaderName(line) => {
+                write!(f, "Header name is not a valid token in `{}`", line)
+            }
+            ParseErrorKind::InvalidHeaderValue(line) => {
+                write!(f, "Header value contains control characters in `{}`", line)
+            }
+            ParseErrorKind::MissingColon(line) => {
+                write!(f, "Header line is missing a colon `{}`", line)
+            }
+            ParseErrorKind::WhitespaceBeforeColon(line) => {
+                write!(f, "Whitespace between header name and colon `{}`", line)
+            }
+            ParseErrorKind::ObsoleteLineFolding(line) => {
+                write!(f, "Obsolete line folding is not allowed `{}`", line)
+            }
+            ParseErrorKind::BareLineFeed => write!(f, "Line ended without a carriage return"),
+            ParseErrorKind::ConflictingFraming => {
+                write!(f, "Both Transfer-Encoding and Content-Length are present")
+            }
+            ParseErrorKind::InvalidContentLength(value) => {
+                write!(f, "Invalid Content-Length `{}`", value)
+            }
+            ParseErrorKind::ConflictingContentLength(values) => {
+                write!(f, "Conflicting Content-Length values `{}`", values)
+            }
+            ParseErrorKind::ChunkedNotFinal(value) => {
+                write!(f, "Chunked is not the final transfer coding in `{}`", value)
+            }
+            ParseErrorKind::InvalidTransferEncoding(value) => {
+                write!(f, "Invalid Transfer-Encoding `{}`", value)
+            }
+            ParseErrorKind::UnexpectedEof => {
+                write!(f, "Stream ended in the middle of a message")
+            }
+            ParseErrorKind::Io(kind) => write!(f, "Read error: `{}`", kind),
         }
     }
 }
 
-impl core::error::Error for HttpParseError {}
+impl Display for HttpParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.source {
+            Some(source) => write!(f, "Read error: `{}`", source)?,
+            None => write!(f, "{}", self.kind)?,
+        }
+        match (self.line, self.offset) {
+            (Some(line), Some(offset)) => write!(f, " at line {} (byte {})", line, offset),
+            (None, Some(offset)) => write!(f, " at byte {}", offset),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl core::error::Error for HttpParseError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        self.source
+            .as_ref()
+            .map(|source| source as &(dyn core::error::Error + 'static))
+    }
+}
 
 impl From<std::io::Error> for HttpParseError {
     fn from(value: std::io::Error) -> Self {
-        Self::Other(value.to_string())
+        Self {
+            kind: ParseErrorKind::Io(value.kind()),
+            offset: None,
+            line: None,
+            source: Some(value),
+        }
     }
 }
+
 impl From<HttpParseError> for std::io::Error {
+    /// Errors of the underlying reader are returned as they were,
+    /// a message that ends early is `UnexpectedEof` and any other error is `InvalidData`.
     fn from(value: HttpParseError) -> Self {
-        match value {
-            HttpParseError::Method(value) => {
-                std::io::Error::new(std::io::ErrorKind::InvalidData, value)
-            }
-            HttpParseError::Version(value) => {
-                std::io::Error::new(std::io::ErrorKind::InvalidData, value)
-            }
-            HttpParseError::Url(value) => {
-                std::io::Error::new(std::io::ErrorKind::InvalidData, value)
-            }
-            HttpParseError::StatusCode(value) => {
-                std::io::Error::new(std::io::ErrorKind::InvalidData, value)
-            }
-            HttpParseError::Header(value) => {
-                std::io::Error::new(std::io::ErrorKind::InvalidData, value)
+        let message = value.to_string();
+        match value.kind {
+            ParseErrorKind::Io(_) => match value.source {
+                Some(source) => source,
+                None => std::io::Error::other(message),
+            },
+            ParseErrorKind::UnexpectedEof => {
+                std::io::Error::new(std::io::ErrorKind::UnexpectedEof, message)
             }
-            HttpParseError::Other(value) => std::io::Error::new(std::io::ErrorKind::Other, value),
+            _ => std::io::Error::new(std::io::ErrorKind::InvalidData, message),
         }
     }
 }